@@ -0,0 +1,316 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Datelike, Timelike};
+use chrono_tz::Tz;
+use railway_core::{Mode, Place, Station};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::{profile::ProfileId, storage};
+
+/// A station of a commute, pinned by its provider-specific id.
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize, Deserialize)]
+pub struct CommuteStation {
+    pub id: String,
+    /// Only used for display, the station is always queried by id.
+    pub name: String,
+}
+
+impl CommuteStation {
+    pub fn to_place(&self) -> Place {
+        Place::Station(Station {
+            id: self.id.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+/// The modes of transport a commute may use, mirroring [`Mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize)]
+pub enum TransportMode {
+    HighSpeedTrain,
+    RegionalTrain,
+    SuburbanTrain,
+    Subway,
+    Tram,
+    Bus,
+    Ferry,
+    Cablecar,
+    OnDemand,
+    Unknown,
+}
+
+impl From<TransportMode> for Mode {
+    fn from(mode: TransportMode) -> Self {
+        match mode {
+            TransportMode::HighSpeedTrain => Mode::HighSpeedTrain,
+            TransportMode::RegionalTrain => Mode::RegionalTrain,
+            TransportMode::SuburbanTrain => Mode::SuburbanTrain,
+            TransportMode::Subway => Mode::Subway,
+            TransportMode::Tram => Mode::Tram,
+            TransportMode::Bus => Mode::Bus,
+            TransportMode::Ferry => Mode::Ferry,
+            TransportMode::Cablecar => Mode::Cablecar,
+            TransportMode::OnDemand => Mode::OnDemand,
+            TransportMode::Unknown => Mode::Unknown,
+        }
+    }
+}
+
+impl From<Mode> for TransportMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::HighSpeedTrain => TransportMode::HighSpeedTrain,
+            Mode::RegionalTrain => TransportMode::RegionalTrain,
+            Mode::SuburbanTrain => TransportMode::SuburbanTrain,
+            Mode::Subway => TransportMode::Subway,
+            Mode::Tram => TransportMode::Tram,
+            Mode::Bus => TransportMode::Bus,
+            Mode::Ferry => TransportMode::Ferry,
+            Mode::Cablecar => TransportMode::Cablecar,
+            Mode::OnDemand => TransportMode::OnDemand,
+            Mode::Unknown => TransportMode::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Mon => Weekday::Mon,
+            chrono::Weekday::Tue => Weekday::Tue,
+            chrono::Weekday::Wed => Weekday::Wed,
+            chrono::Weekday::Thu => Weekday::Thu,
+            chrono::Weekday::Fri => Weekday::Fri,
+            chrono::Weekday::Sat => Weekday::Sat,
+            chrono::Weekday::Sun => Weekday::Sun,
+        }
+    }
+}
+
+/// A range of hours of the day, `from` inclusive and `until` exclusive.
+///
+/// Wraps around midnight if `from` is after `until`, e.g. from 22 until 6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize)]
+pub struct ActiveHours {
+    pub from: u32,
+    pub until: u32,
+}
+
+impl ActiveHours {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.from <= self.until {
+            (self.from..self.until).contains(&hour)
+        } else {
+            hour >= self.from || hour < self.until
+        }
+    }
+}
+
+/// When to notify about changes of a commute.
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// Notify once the departure is delayed by more than this many minutes.
+    pub delay_threshold_minutes: u32,
+    /// Hours without notifications, [`None`] to always notify.
+    pub quiet_hours: Option<ActiveHours>,
+    /// Notify when it is time to leave for the station.
+    pub leave_reminder: bool,
+}
+
+impl NotificationSettings {
+    /// Whether notifications may be shown at the given time.
+    pub fn allows(&self, now: &DateTime<Tz>) -> bool {
+        self.enabled
+            && !self
+                .quiet_hours
+                .is_some_and(|quiet| quiet.contains(now.hour()))
+    }
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            delay_threshold_minutes: 5,
+            quiet_hours: None,
+            leave_reminder: true,
+        }
+    }
+}
+
+/// A commute without its id, as given by the frontend when adding one.
+#[derive(Debug, Clone, PartialEq, Type, Serialize, Deserialize)]
+pub struct CommuteInput {
+    pub name: String,
+    pub profile: ProfileId,
+    pub origin: CommuteStation,
+    pub destination: CommuteStation,
+    pub products: Vec<TransportMode>,
+    /// How many minutes it takes to walk to the origin station.
+    #[serde(default)]
+    pub walk_minutes: u32,
+    /// Additional minutes on top of the walk to search journeys from.
+    pub offset_minutes: i32,
+    /// An empty list means every day.
+    pub active_weekdays: Vec<Weekday>,
+    /// [`None`] means the whole day.
+    pub active_hours: Option<ActiveHours>,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Type, Serialize, Deserialize)]
+pub struct Commute {
+    pub id: u32,
+    pub input: CommuteInput,
+}
+
+impl Commute {
+    pub fn modes(&self) -> HashSet<Mode> {
+        self.input.products.iter().map(|&p| Mode::from(p)).collect()
+    }
+
+    pub fn is_active(&self, now: &DateTime<Tz>) -> bool {
+        let weekday = Weekday::from(now.weekday());
+        let day_active =
+            self.input.active_weekdays.is_empty() || self.input.active_weekdays.contains(&weekday);
+        let hour_active = self
+            .input
+            .active_hours
+            .map(|h| h.contains(now.hour()))
+            .unwrap_or(true);
+        day_active && hour_active
+    }
+}
+
+/// The commutes configured by the user, persisted as JSON.
+#[derive(Clone)]
+pub struct CommuteStore {
+    path: PathBuf,
+    commutes: Arc<Mutex<Vec<Commute>>>,
+}
+
+impl CommuteStore {
+    /// Load the commutes from the given file, starting without commutes if it does not exist yet.
+    pub fn load(path: PathBuf) -> std::io::Result<Self> {
+        let commutes = storage::read_json(&path)?;
+        Ok(Self {
+            path,
+            commutes: Arc::new(Mutex::new(commutes)),
+        })
+    }
+
+    pub fn list(&self) -> Vec<Commute> {
+        self.commutes.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, id: u32) -> Option<Commute> {
+        self.commutes
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|c| c.id == id)
+            .cloned()
+    }
+
+    pub fn add(&self, input: CommuteInput) -> std::io::Result<Commute> {
+        let mut commutes = self.commutes.lock().unwrap();
+        let commute = Commute {
+            id: commutes.iter().map(|c| c.id + 1).max().unwrap_or_default(),
+            input,
+        };
+        commutes.push(commute.clone());
+        storage::write_json(&self.path, &*commutes)?;
+        Ok(commute)
+    }
+
+    /// Replace the commute with the same id, returning whether it existed.
+    pub fn edit(&self, commute: Commute) -> std::io::Result<bool> {
+        let mut commutes = self.commutes.lock().unwrap();
+        let Some(existing) = commutes.iter_mut().find(|c| c.id == commute.id) else {
+            return Ok(false);
+        };
+        *existing = commute;
+        storage::write_json(&self.path, &*commutes)?;
+        Ok(true)
+    }
+
+    /// Delete the commute with the given id, returning whether it existed.
+    pub fn delete(&self, id: u32) -> std::io::Result<bool> {
+        let mut commutes = self.commutes.lock().unwrap();
+        let len = commutes.len();
+        commutes.retain(|c| c.id != id);
+        if commutes.len() == len {
+            return Ok(false);
+        }
+        storage::write_json(&self.path, &*commutes)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn commute(active_hours: Option<ActiveHours>) -> Commute {
+        let station = |id: &str| CommuteStation {
+            id: id.to_string(),
+            name: id.to_string(),
+        };
+        Commute {
+            id: 0,
+            input: CommuteInput {
+                name: "Night shift".to_string(),
+                profile: ProfileId::Db,
+                origin: station("a"),
+                destination: station("b"),
+                products: vec![],
+                walk_minutes: 0,
+                offset_minutes: 0,
+                active_weekdays: vec![],
+                active_hours,
+                notifications: NotificationSettings::default(),
+            },
+        }
+    }
+
+    fn at(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Europe::Berlin
+            .with_ymd_and_hms(2024, 9, 2, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn active_hours_wrap_around_midnight() {
+        let overnight = commute(Some(ActiveHours { from: 22, until: 6 }));
+        assert!(overnight.is_active(&at(23)));
+        assert!(overnight.is_active(&at(2)));
+        assert!(!overnight.is_active(&at(6)));
+        assert!(!overnight.is_active(&at(12)));
+
+        let morning = commute(Some(ActiveHours { from: 6, until: 9 }));
+        assert!(morning.is_active(&at(6)));
+        assert!(!morning.is_active(&at(9)));
+        assert!(commute(None).is_active(&at(3)));
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt::Display;
+
+use railway_provider_hafas::Error as HafasError;
+use rspc::ErrorCode;
+use serde::Serialize;
+use specta::Type;
+use thiserror::Error as ThisError;
+
+/// Errors of the app, serialized as JSON into the message of the [`rspc::Error`] sent to the frontend.
+#[derive(ThisError, Debug, Clone, PartialEq, Eq, Type, Serialize)]
+#[serde(tag = "type", content = "message")]
+pub enum AppError {
+    #[error("no location found")]
+    NoLocation,
+    #[error("no journey found")]
+    NoJourney,
+    #[error("provider error: {0}")]
+    Provider(String),
+    /// Not fatal, planned times are used instead.
+    #[error("no real-time data available")]
+    MissingRealtime,
+    /// A debounced search was superseded by a later one before it started.
+    #[error("superseded by a later search")]
+    Superseded,
+}
+
+impl<E: Display> From<railway_core::Error<E, HafasError>> for AppError {
+    fn from(e: railway_core::Error<E, HafasError>) -> Self {
+        match e {
+            railway_core::Error::Provider(HafasError::Hafas { code, .. })
+                if matches!(code.as_str(), "LOCATION" | "H9220") =>
+            {
+                AppError::NoLocation
+            }
+            railway_core::Error::Provider(HafasError::Hafas { code, .. })
+                if matches!(
+                    code.as_str(),
+                    "H890" | "H891" | "H892" | "H895" | "H9240" | "H9380"
+                ) =>
+            {
+                AppError::NoJourney
+            }
+            e => AppError::Provider(e.to_string()),
+        }
+    }
+}
+
+impl From<AppError> for rspc::Error {
+    fn from(e: AppError) -> Self {
+        let code = match e {
+            AppError::NoLocation | AppError::NoJourney => ErrorCode::NotFound,
+            AppError::Provider(_) | AppError::MissingRealtime => ErrorCode::InternalServerError,
+            AppError::Superseded => ErrorCode::ClientClosedRequest,
+        };
+        let message = serde_json::to_string(&e).unwrap_or_else(|_| e.to_string());
+        rspc::Error::with_cause(code, message, e)
+    }
+}
@@ -0,0 +1,434 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Datelike, Timelike, Utc};
+use railway_core::{Journey, Leg, Place};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use thiserror::Error as ThisError;
+
+use crate::commute::Weekday;
+
+#[derive(ThisError, Debug)]
+pub enum HistoryError {
+    #[error("{0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("{0}")]
+    Csv(#[from] csv::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+pub type Result<T> = std::result::Result<T, HistoryError>;
+
+/// What to compute [`Statistics`] for.
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct StatisticsQuery {
+    /// [`None`] for all commutes.
+    pub commute_id: Option<u32>,
+    /// A leg counts as punctual if it arrives less than this many minutes late.
+    pub punctual_minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct WeekdayDelay {
+    pub weekday: Weekday,
+    pub legs: u32,
+    pub average_delay_minutes: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct HourDelay {
+    /// The hour of the planned departure.
+    pub hour: u32,
+    pub legs: u32,
+    pub average_delay_minutes: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct DayDelay {
+    /// The day of the planned departure, e.g. "2024-09-02".
+    pub day: String,
+    pub legs: u32,
+    pub cancelled: u32,
+    pub average_delay_minutes: f64,
+}
+
+/// How reliable the recorded legs were.
+///
+/// Delays are arrival delays, falling back to departure delays, of legs that were not cancelled and have real-time data.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct Statistics {
+    pub legs: u32,
+    pub cancelled: u32,
+    pub platform_changes: u32,
+    /// The share of legs that were punctual and not cancelled, [`None`] without legs.
+    ///
+    /// Legs without real-time data that were not cancelled are left out.
+    pub punctuality: Option<f64>,
+    pub by_weekday: Vec<WeekdayDelay>,
+    pub by_hour: Vec<HourDelay>,
+    /// The days with the highest average delay, worst first.
+    pub worst_days: Vec<DayDelay>,
+}
+
+const WORST_DAYS: u32 = 5;
+
+/// The delay in seconds, see [`Statistics`].
+const DELAY: &str = "COALESCE(arrival - planned_arrival, departure - planned_departure)";
+
+/// A local history of the observed legs of commutes.
+#[derive(Clone)]
+pub struct History {
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn place_name(place: &Place) -> Option<String> {
+    match place {
+        Place::Station(station) => station.name.clone().or_else(|| Some(station.id.clone())),
+        Place::Location(_) => None,
+    }
+}
+
+fn is_riding(leg: &Leg) -> bool {
+    !leg.walking && !leg.transfer
+}
+
+impl History {
+    /// Open the history database at the given path, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::new(Connection::open(path)?)
+    }
+
+    fn new(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS legs (
+                commute_id INTEGER NOT NULL,
+                leg_id TEXT NOT NULL,
+                line TEXT,
+                origin TEXT,
+                destination TEXT,
+                planned_departure INTEGER NOT NULL,
+                departure INTEGER,
+                planned_arrival INTEGER,
+                arrival INTEGER,
+                planned_departure_platform TEXT,
+                departure_platform TEXT,
+                planned_arrival_platform TEXT,
+                arrival_platform TEXT,
+                cancelled INTEGER NOT NULL,
+                remarks TEXT NOT NULL,
+                day TEXT NOT NULL,
+                weekday INTEGER NOT NULL,
+                hour INTEGER NOT NULL,
+                observed_at INTEGER NOT NULL,
+                PRIMARY KEY (commute_id, leg_id)
+            );",
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Record the legs of a journey of a commute, replacing earlier observations of the same legs.
+    pub fn record(&self, commute_id: u32, journey: &Journey) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for leg in journey.legs.iter().filter(|l| is_riding(l)) {
+            let Some(planned_departure) = leg.planned_departure else {
+                continue;
+            };
+            let remarks = leg
+                .remarks
+                .iter()
+                .map(|r| r.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            tx.execute(
+                "INSERT INTO legs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
+                ON CONFLICT (commute_id, leg_id) DO UPDATE SET
+                    departure = excluded.departure,
+                    arrival = excluded.arrival,
+                    departure_platform = excluded.departure_platform,
+                    arrival_platform = excluded.arrival_platform,
+                    cancelled = excluded.cancelled,
+                    remarks = excluded.remarks,
+                    observed_at = excluded.observed_at",
+                params![
+                    commute_id,
+                    leg.id(),
+                    leg.line.as_ref().and_then(|l| l.name.clone()),
+                    place_name(&leg.origin),
+                    place_name(&leg.destination),
+                    planned_departure.timestamp(),
+                    leg.departure.map(|t| t.timestamp()),
+                    leg.planned_arrival.map(|t| t.timestamp()),
+                    leg.arrival.map(|t| t.timestamp()),
+                    leg.planned_departure_platform,
+                    leg.departure_platform,
+                    leg.planned_arrival_platform,
+                    leg.arrival_platform,
+                    leg.cancelled,
+                    remarks,
+                    planned_departure.format("%Y-%m-%d").to_string(),
+                    planned_departure.weekday().num_days_from_monday(),
+                    planned_departure.hour(),
+                    Utc::now().timestamp(),
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn statistics(&self, query: &StatisticsQuery) -> Result<Statistics> {
+        let conn = self.conn.lock().unwrap();
+        // `?1` is the commute id, NULL for all commutes.
+        let filter = "(?1 IS NULL OR commute_id = ?1)";
+        let delayed = format!("cancelled = 0 AND {DELAY} IS NOT NULL");
+
+        let (legs, cancelled, platform_changes, rated, punctual): (u32, u32, u32, u32, u32) =
+            conn.query_row(
+                &format!(
+                    "SELECT
+                        COUNT(*),
+                        COALESCE(SUM(cancelled), 0),
+                        COALESCE(SUM(departure_platform IS NOT NULL AND planned_departure_platform IS NOT NULL
+                            AND departure_platform != planned_departure_platform), 0),
+                        COALESCE(SUM(cancelled = 1 OR {DELAY} IS NOT NULL), 0),
+                        COALESCE(SUM({delayed} AND {DELAY} < ?2 * 60), 0)
+                    FROM legs WHERE {filter}"
+                ),
+                params![query.commute_id, query.punctual_minutes],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT weekday, COUNT(*), AVG({DELAY}) / 60.0 FROM legs
+            WHERE {filter} AND {delayed} GROUP BY weekday ORDER BY weekday"
+        ))?;
+        let by_weekday = stmt
+            .query_map(params![query.commute_id], |row| {
+                let weekday: u8 = row.get(0)?;
+                Ok(WeekdayDelay {
+                    weekday: chrono::Weekday::try_from(weekday)
+                        .unwrap_or(chrono::Weekday::Mon)
+                        .into(),
+                    legs: row.get(1)?,
+                    average_delay_minutes: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT hour, COUNT(*), AVG({DELAY}) / 60.0 FROM legs
+            WHERE {filter} AND {delayed} GROUP BY hour ORDER BY hour"
+        ))?;
+        let by_hour = stmt
+            .query_map(params![query.commute_id], |row| {
+                Ok(HourDelay {
+                    hour: row.get(0)?,
+                    legs: row.get(1)?,
+                    average_delay_minutes: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT day, COUNT(*), SUM(cancelled),
+                COALESCE(AVG(CASE WHEN {delayed} THEN {DELAY} END) / 60.0, 0.0) AS average
+            FROM legs WHERE {filter} GROUP BY day
+            ORDER BY SUM(cancelled) DESC, average DESC LIMIT ?2"
+        ))?;
+        let worst_days = stmt
+            .query_map(params![query.commute_id, WORST_DAYS], |row| {
+                Ok(DayDelay {
+                    day: row.get(0)?,
+                    legs: row.get(1)?,
+                    cancelled: row.get(2)?,
+                    average_delay_minutes: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Statistics {
+            legs,
+            cancelled,
+            platform_changes,
+            punctuality: (rated > 0).then(|| f64::from(punctual) / f64::from(rated)),
+            by_weekday,
+            by_hour,
+            worst_days,
+        })
+    }
+
+    /// All recorded legs as CSV, times in seconds since the Unix epoch.
+    pub fn export_csv(&self, commute_id: Option<u32>) -> Result<String> {
+        const COLUMNS: [&str; 15] = [
+            "commute_id",
+            "line",
+            "origin",
+            "destination",
+            "planned_departure",
+            "departure",
+            "planned_arrival",
+            "arrival",
+            "planned_departure_platform",
+            "departure_platform",
+            "planned_arrival_platform",
+            "arrival_platform",
+            "cancelled",
+            "remarks",
+            "day",
+        ];
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM legs WHERE (?1 IS NULL OR commute_id = ?1) ORDER BY planned_departure",
+            COLUMNS.join(", ")
+        ))?;
+        let mut rows = stmt.query(params![commute_id])?;
+
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.write_record(COLUMNS)?;
+        while let Some(row) = rows.next()? {
+            let record = (0..COLUMNS.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        rusqlite::types::ValueRef::Null => String::new(),
+                        rusqlite::types::ValueRef::Integer(i) => i.to_string(),
+                        rusqlite::types::ValueRef::Real(f) => f.to_string(),
+                        value => value.as_str()?.to_string(),
+                    })
+                })
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            writer.write_record(record)?;
+        }
+        let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(String::from_utf8(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{leg, time_on};
+
+    /// A leg on September `day`, 2024, delayed by `delay` minutes.
+    fn journey(day: u32, delay: i64, cancelled: bool) -> Journey {
+        let leg = leg(time_on(day, 17, 42), time_on(day, 18, 0), Some("S12"));
+        let delay = chrono::Duration::minutes(delay);
+        crate::test::journey(vec![Leg {
+            departure: leg.departure.map(|t| t + delay),
+            arrival: leg.arrival.map(|t| t + delay),
+            trip_id: Some(format!("trip {day}")),
+            departure_platform: Some("2".to_string()),
+            planned_departure_platform: Some("1".to_string()),
+            cancelled,
+            ..leg
+        }])
+    }
+
+    #[test]
+    fn computes_statistics() {
+        let history = History::new(Connection::open_in_memory().unwrap()).unwrap();
+        // Monday to Wednesday
+        history.record(1, &journey(2, 0, false)).unwrap();
+        history.record(1, &journey(3, 10, false)).unwrap();
+        history.record(1, &journey(4, 0, true)).unwrap();
+        // Observing a leg again replaces it.
+        history.record(1, &journey(2, 2, false)).unwrap();
+        history.record(2, &journey(2, 30, false)).unwrap();
+        // Thursday, without real-time data
+        let mut unknown = journey(5, 0, false);
+        unknown.legs[0].departure = None;
+        unknown.legs[0].arrival = None;
+        history.record(1, &unknown).unwrap();
+
+        let stats = history
+            .statistics(&StatisticsQuery {
+                commute_id: Some(1),
+                punctual_minutes: 6,
+            })
+            .unwrap();
+        assert_eq!(stats.legs, 4);
+        assert_eq!(stats.cancelled, 1);
+        assert_eq!(stats.platform_changes, 4);
+        assert_eq!(stats.punctuality, Some(1. / 3.));
+        assert_eq!(
+            stats.by_weekday,
+            vec![
+                WeekdayDelay {
+                    weekday: Weekday::Mon,
+                    legs: 1,
+                    average_delay_minutes: 2.,
+                },
+                WeekdayDelay {
+                    weekday: Weekday::Tue,
+                    legs: 1,
+                    average_delay_minutes: 10.,
+                },
+            ]
+        );
+        assert_eq!(
+            stats.by_hour,
+            vec![HourDelay {
+                hour: 17,
+                legs: 2,
+                average_delay_minutes: 6.,
+            }]
+        );
+        assert_eq!(
+            stats
+                .worst_days
+                .iter()
+                .map(|d| d.day.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2024-09-04", "2024-09-03", "2024-09-02", "2024-09-05"]
+        );
+
+        let all = history
+            .statistics(&StatisticsQuery {
+                commute_id: None,
+                punctual_minutes: 6,
+            })
+            .unwrap();
+        assert_eq!(all.legs, 5);
+    }
+
+    #[test]
+    fn exports_csv() {
+        let history = History::new(Connection::open_in_memory().unwrap()).unwrap();
+        history.record(1, &journey(2, 3, false)).unwrap();
+
+        let csv = history.export_csv(None).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("commute_id,line,origin"));
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "1,S12,a,b,{},{},{},{},1,2,,,0,,2024-09-02",
+                time_on(2, 17, 42).timestamp(),
+                time_on(2, 17, 45).timestamp(),
+                time_on(2, 18, 0).timestamp(),
+                time_on(2, 18, 3).timestamp(),
+            )
+        );
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,216 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use railway_core::{
+    CalendarEvents, JourneysOptions, JourneysResponse, Leg, Provider, RefreshJourneyOptions,
+    Requester, TransferTimes,
+};
+use railway_provider_hafas::client::HafasClient;
+use serde::Serialize;
+use specta::Type;
+
+use crate::{commute::Commute, error::AppError, reminder};
+
+#[derive(Debug, Clone, Type, Serialize)]
+pub struct Journey {
+    /// Identifies the journey for the provider, e.g. for the `journeys.calendar` query.
+    id: String,
+    from: String,
+    to: String,
+    /// The real-time departure, or the planned one if there is no real-time data.
+    departure: NaiveDateTime,
+    /// The real-time arrival, or the planned one if there is no real-time data.
+    arrival: NaiveDateTime,
+    planned_depature: NaiveDateTime,
+    planned_arrival: NaiveDateTime,
+    /// The line of the first leg not on foot, [`None`] if the whole journey is walked.
+    line: Option<String>,
+    /// The latest time to leave for the origin station, see [`reminder::leave_time`].
+    leave_at: Option<NaiveDateTime>,
+    /// The number of changes between legs not on foot.
+    transfers: u32,
+    /// The real-time duration in minutes.
+    duration_minutes: i32,
+    /// Whether any leg is cancelled.
+    cancelled: bool,
+    /// Whether the journey cannot be taken as planned, see the `journeys.alternatives` query.
+    broken: bool,
+    legs: Vec<JourneyLeg>,
+    /// Non-fatal problems, e.g. [`AppError::MissingRealtime`].
+    warnings: Vec<AppError>,
+}
+
+#[derive(Debug, Clone, Type, Serialize)]
+pub struct JourneyLeg {
+    line: Option<String>,
+    walking: bool,
+    departure: NaiveDateTime,
+    arrival: NaiveDateTime,
+    /// [`None`] if there is no real-time data.
+    departure_delay_minutes: Option<i32>,
+    /// [`None`] if there is no real-time data.
+    arrival_delay_minutes: Option<i32>,
+    cancelled: bool,
+}
+
+/// Some journeys of a commute, with a reference to load later ones.
+#[derive(Debug, Clone, Type, Serialize)]
+pub struct Journeys {
+    journeys: Vec<Journey>,
+    /// Pass to the `journeys.later` query to load later journeys.
+    later_ref: Option<String>,
+}
+
+/// Use the planned time if there is no real-time one, returning whether it was missing.
+fn with_fallback(
+    time: Option<DateTime<Tz>>,
+    planned: Option<DateTime<Tz>>,
+) -> Result<(NaiveDateTime, NaiveDateTime, bool), AppError> {
+    let planned = planned
+        .or(time)
+        .ok_or_else(|| AppError::Provider("journey without times".to_string()))?;
+    Ok((
+        time.unwrap_or(planned).naive_local(),
+        planned.naive_local(),
+        time.is_none(),
+    ))
+}
+
+fn delay_minutes(time: Option<DateTime<Tz>>, planned: Option<DateTime<Tz>>) -> Option<i32> {
+    Some((time? - planned?).num_minutes() as i32)
+}
+
+/// Search journeys of a commute, by default departing now.
+///
+/// `later_than` continues a previous search, see [`JourneysResponse::later_ref`]. Only a new search fails with [`AppError::NoJourney`], continuing one may yield no journeys.
+/// At least `results` journeys are requested, but the provider may return more; they are all kept so that `later_ref` continues right after them.
+pub async fn search<R: Requester>(
+    client: &HafasClient<R>,
+    commute: &Commute,
+    results: u64,
+    later_than: Option<String>,
+) -> Result<JourneysResponse, AppError> {
+    let continued = later_than.is_some();
+    let departure = match later_than {
+        Some(_) => None,
+        None => Some(
+            Utc::now().with_timezone(&commute.input.profile.timezone())
+                + Duration::minutes(commute.input.walk_minutes.into())
+                + Duration::minutes(commute.input.offset_minutes.into()),
+        ),
+    };
+    let res = client
+        .journeys(
+            commute.input.origin.to_place(),
+            commute.input.destination.to_place(),
+            JourneysOptions {
+                departure,
+                later_than,
+                results,
+                products: commute.modes().into(),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    if res.journeys.is_empty() && !continued {
+        return Err(AppError::NoJourney);
+    }
+    Ok(res)
+}
+
+/// An iCalendar file with the journey of the given id, refreshed for current times.
+pub async fn calendar<R: Requester>(
+    client: &HafasClient<R>,
+    journey_id: String,
+    events: CalendarEvents,
+) -> Result<String, AppError> {
+    let journey = railway_core::Journey {
+        id: journey_id,
+        legs: vec![],
+        price: None,
+    };
+    let journey = client
+        .refresh_journey(&journey, RefreshJourneyOptions::default())
+        .await?;
+    Ok(railway_core::to_icalendar(&[journey], events))
+}
+
+impl JourneyLeg {
+    fn new(leg: &Leg) -> Result<Self, AppError> {
+        Ok(JourneyLeg {
+            line: leg.line.as_ref().and_then(|l| l.name.clone()),
+            walking: leg.walking || leg.transfer,
+            departure: with_fallback(leg.departure, leg.planned_departure)?.0,
+            arrival: with_fallback(leg.arrival, leg.planned_arrival)?.0,
+            departure_delay_minutes: delay_minutes(leg.departure, leg.planned_departure),
+            arrival_delay_minutes: delay_minutes(leg.arrival, leg.planned_arrival),
+            cancelled: leg.cancelled,
+        })
+    }
+}
+
+impl Journey {
+    /// Summarize a journey of the given commute for the frontend.
+    pub fn new(commute: &Commute, journey: &railway_core::Journey) -> Result<Self, AppError> {
+        let (Some(first), Some(last)) = (journey.legs.first(), journey.legs.last()) else {
+            return Err(AppError::NoJourney);
+        };
+
+        let (departure, planned_depature, departure_missing) =
+            with_fallback(first.departure, first.planned_departure)?;
+        let (arrival, planned_arrival, arrival_missing) =
+            with_fallback(last.arrival, last.planned_arrival)?;
+        let mut warnings = vec![];
+        if departure_missing || arrival_missing {
+            warnings.push(AppError::MissingRealtime);
+        }
+        let riding = journey
+            .legs
+            .iter()
+            .filter(|l| !l.walking && !l.transfer)
+            .count();
+
+        Ok(Journey {
+            id: journey.id.clone(),
+            from: commute.input.origin.name.clone(),
+            to: commute.input.destination.name.clone(),
+            departure,
+            arrival,
+            planned_arrival,
+            planned_depature,
+            line: journey
+                .legs
+                .iter()
+                .filter(|l| !l.walking && !l.transfer)
+                .find_map(|l| l.line.as_ref()?.name.clone()),
+            leave_at: reminder::leave_time(
+                journey,
+                Duration::minutes(commute.input.walk_minutes.into()),
+            )
+            .map(|t| t.naive_local()),
+            transfers: riding.saturating_sub(1) as u32,
+            duration_minutes: (arrival - departure).num_minutes() as i32,
+            cancelled: journey.legs.iter().any(|l| l.cancelled),
+            broken: journey.break_point(&TransferTimes::default()).is_some(),
+            legs: journey
+                .legs
+                .iter()
+                .map(JourneyLeg::new)
+                .collect::<Result<_, _>>()?,
+            warnings,
+        })
+    }
+}
+
+impl Journeys {
+    pub fn new(commute: &Commute, res: &JourneysResponse) -> Result<Self, AppError> {
+        Ok(Journeys {
+            journeys: res
+                .journeys
+                .iter()
+                .map(|j| Journey::new(commute, j))
+                .collect::<Result<_, _>>()?,
+            later_ref: res.later_ref.clone(),
+        })
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    Stream,
+};
+use railway_core::{JourneyDiff, Leg, LegChange, Provider, RefreshJourneyOptions, TransferTimes};
+use serde::Serialize;
+use specta::Type;
+
+use crate::{journey::Journey, router::AppData};
+
+/// The last journey found for each commute, refreshed by [`updates`].
+#[derive(Clone, Default)]
+pub struct TrackedJourneys(Arc<Mutex<HashMap<u32, railway_core::Journey>>>);
+
+impl TrackedJourneys {
+    pub fn get(&self, commute_id: u32) -> Option<railway_core::Journey> {
+        self.0.lock().unwrap().get(&commute_id).cloned()
+    }
+
+    pub fn track(&self, commute_id: u32, journey: railway_core::Journey) {
+        self.0.lock().unwrap().insert(commute_id, journey);
+    }
+}
+
+/// What changed about a leg, delays are in minutes.
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ChangeKind {
+    DepartureDelay(Option<i32>),
+    ArrivalDelay(Option<i32>),
+    DeparturePlatform(Option<String>),
+    ArrivalPlatform(Option<String>),
+    Cancelled,
+    Reinstated,
+    Remark(String),
+    ConnectionLost,
+    /// Legs were added or removed.
+    Rerouted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize)]
+pub struct JourneyChange {
+    /// The line of the changed leg, if any.
+    pub line: Option<String>,
+    pub kind: ChangeKind,
+}
+
+/// Sent whenever a refresh changed the tracked journey of a commute.
+#[derive(Debug, Clone, Type, Serialize)]
+pub struct JourneyUpdate {
+    pub commute_id: u32,
+    pub journey: Journey,
+    pub changes: Vec<JourneyChange>,
+}
+
+fn minutes(d: Option<Duration>) -> Option<i32> {
+    d.map(|d| d.num_minutes() as i32)
+}
+
+fn changes(diff: &JourneyDiff, legs: &[Leg]) -> Vec<JourneyChange> {
+    let mut changes = vec![];
+    for leg in &diff.legs {
+        let line = legs
+            .iter()
+            .find(|l| l.id() == leg.leg_id)
+            .and_then(|l| l.line.as_ref()?.name.clone());
+        changes.extend(leg.changes.iter().filter_map(|change| {
+            let kind = match change {
+                LegChange::DepartureDelay { current, .. } => {
+                    ChangeKind::DepartureDelay(minutes(*current))
+                }
+                LegChange::ArrivalDelay { current, .. } => {
+                    ChangeKind::ArrivalDelay(minutes(*current))
+                }
+                LegChange::DeparturePlatform { current, .. } => {
+                    ChangeKind::DeparturePlatform(current.clone())
+                }
+                LegChange::ArrivalPlatform { current, .. } => {
+                    ChangeKind::ArrivalPlatform(current.clone())
+                }
+                LegChange::Cancelled => ChangeKind::Cancelled,
+                LegChange::Reinstated => ChangeKind::Reinstated,
+                LegChange::RemarkAdded(remark) => ChangeKind::Remark(remark.text.clone()),
+                LegChange::RemarkRemoved(_) => return None,
+                LegChange::ConnectionLost { .. } => ChangeKind::ConnectionLost,
+            };
+            Some(JourneyChange {
+                line: line.clone(),
+                kind,
+            })
+        }));
+    }
+    if !diff.added_legs.is_empty() || !diff.removed_legs.is_empty() {
+        changes.push(JourneyChange {
+            line: None,
+            kind: ChangeKind::Rerouted,
+        });
+    }
+    changes
+}
+
+/// How long to wait before the next refresh, refreshing more often close to departure.
+pub fn refresh_interval(departure: Option<DateTime<Tz>>, now: DateTime<Utc>) -> Duration {
+    let seconds = match departure.map(|d| d.with_timezone(&Utc) - now) {
+        Some(left) if left < Duration::minutes(15) => 30,
+        Some(left) if left < Duration::hours(1) => 60,
+        Some(left) if left < Duration::hours(3) => 5 * 60,
+        _ => 15 * 60,
+    };
+    Duration::seconds(seconds)
+}
+
+/// The next change of the tracked journey of a commute, [`None`] once there will be none or nobody subscribed anymore.
+async fn next_update(
+    ctx: &AppData,
+    commute_id: u32,
+    sender: &UnboundedSender<JourneyUpdate>,
+) -> Option<JourneyUpdate> {
+    loop {
+        if sender.is_closed() {
+            return None;
+        }
+        let commute = ctx.commutes.get(commute_id)?;
+        let old = ctx.tracked.get(commute_id)?;
+        let first = old.legs.first()?;
+        let last = old.legs.last()?;
+        if last.arrival.or(last.planned_arrival)? < Utc::now() {
+            return None;
+        }
+
+        tokio::time::sleep(
+            refresh_interval(first.departure.or(first.planned_departure), Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+        )
+        .await;
+        // Do not refresh for a subscription that ended while sleeping.
+        if sender.is_closed() {
+            return None;
+        }
+
+        let Ok(new) = ctx
+            .clients
+            .get(commute.input.profile)
+            .refresh_journey(&old, RefreshJourneyOptions::default())
+            .await
+        else {
+            // Try again at the next interval, a single failed refresh does not end the updates.
+            continue;
+        };
+        let diff = JourneyDiff::new(&old, &new, &TransferTimes::default());
+        ctx.tracked.track(commute_id, new.clone());
+        if diff.is_empty() {
+            continue;
+        }
+        let Ok(journey) = Journey::new(&commute, &new) else {
+            continue;
+        };
+        return Some(JourneyUpdate {
+            commute_id,
+            journey,
+            changes: changes(&diff, &new.legs),
+        });
+    }
+}
+
+/// Refresh the tracked journey of a commute until it arrives, yielding only changes.
+///
+/// Ends if no journey is tracked for the commute, i.e. the `journeys` query was not run for it.
+pub fn updates(
+    ctx: AppData,
+    commute_id: u32,
+) -> impl Stream<Item = JourneyUpdate> + Send + Sync + 'static {
+    // rspc requires the stream to be `Sync`, which the refresh futures are not, so refresh on a separate task.
+    let (sender, receiver) = mpsc::unbounded();
+    tokio::spawn(async move {
+        while let Some(update) = next_update(&ctx, commute_id, &sender).await {
+            if sender.unbounded_send(update).is_err() {
+                // The subscription ended.
+                break;
+            }
+        }
+    });
+    receiver
+}
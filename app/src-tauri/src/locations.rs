@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use railway_core::{Location, LocationsOptions, Place, Provider};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::{
+    commute::TransportMode,
+    error::AppError,
+    profile::{Clients, ProfileId},
+};
+
+/// How long to wait for further input before searching.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+/// How long search results are cached.
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct LocationQuery {
+    pub profile: ProfileId,
+    pub query: String,
+}
+
+/// A station found by [`LocationSearch::search`], to be pinned as a commute station by its id.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct StationCandidate {
+    pub id: String,
+    pub name: String,
+    pub products: Vec<TransportMode>,
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+}
+
+impl StationCandidate {
+    /// [`None`] for places other than stations, which cannot be pinned.
+    fn new(place: Place) -> Option<Self> {
+        let Place::Station(station) = place else {
+            return None;
+        };
+        let (latitude, longitude) = match station.location {
+            Some(Location::Address {
+                latitude,
+                longitude,
+                ..
+            })
+            | Some(Location::Point {
+                latitude,
+                longitude,
+                ..
+            }) => (Some(latitude), Some(longitude)),
+            None => (None, None),
+        };
+        let mut products: Vec<TransportMode> = vec![];
+        for product in &station.products {
+            let mode = product.mode.clone().into();
+            if !products.contains(&mode) {
+                products.push(mode);
+            }
+        }
+        Some(StationCandidate {
+            name: station.name.unwrap_or_else(|| station.id.clone()),
+            id: station.id,
+            products,
+            latitude,
+            longitude,
+        })
+    }
+}
+
+type CacheKey = (ProfileId, String);
+
+#[derive(Default)]
+struct Cache(HashMap<CacheKey, (Instant, Vec<StationCandidate>)>);
+
+impl Cache {
+    fn get(&self, key: &CacheKey, now: Instant) -> Option<Vec<StationCandidate>> {
+        self.0
+            .get(key)
+            .filter(|(inserted, _)| now.duration_since(*inserted) < CACHE_TTL)
+            .map(|(_, candidates)| candidates.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, candidates: Vec<StationCandidate>, now: Instant) {
+        self.0
+            .retain(|_, (inserted, _)| now.duration_since(*inserted) < CACHE_TTL);
+        self.0.insert(key, (now, candidates));
+    }
+}
+
+/// Debounced and cached station search for autocompletion.
+#[derive(Clone, Default)]
+pub struct LocationSearch {
+    cache: Arc<Mutex<Cache>>,
+    latest: Arc<AtomicU64>,
+}
+
+impl LocationSearch {
+    /// Wait for further input, failing with [`AppError::Superseded`] if another search started meanwhile.
+    async fn debounce(&self) -> Result<(), AppError> {
+        let search = self.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::time::sleep(DEBOUNCE).await;
+        if self.latest.load(Ordering::SeqCst) != search {
+            return Err(AppError::Superseded);
+        }
+        Ok(())
+    }
+
+    /// Search stations matching the query.
+    ///
+    /// Searches superseded by a later one within [`DEBOUNCE`] fail with [`AppError::Superseded`] without querying the provider.
+    pub async fn search(
+        &self,
+        clients: &Clients,
+        query: LocationQuery,
+    ) -> Result<Vec<StationCandidate>, AppError> {
+        let key = (query.profile, query.query.trim().to_lowercase());
+        if key.1.is_empty() {
+            return Ok(vec![]);
+        }
+        if let Some(candidates) = self.cache.lock().unwrap().get(&key, Instant::now()) {
+            return Ok(candidates);
+        }
+
+        self.debounce().await?;
+
+        let places = clients
+            .get(query.profile)
+            .locations(LocationsOptions {
+                query: query.query,
+                ..Default::default()
+            })
+            .await?;
+        let candidates: Vec<_> = places
+            .into_iter()
+            .filter_map(StationCandidate::new)
+            .collect();
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(key, candidates.clone(), Instant::now());
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cache_expires() {
+        let mut cache = Cache::default();
+        let key = (ProfileId::Db, "köln".to_string());
+        let candidates = vec![StationCandidate {
+            id: "8000207".to_string(),
+            name: "Köln Hbf".to_string(),
+            products: vec![TransportMode::HighSpeedTrain],
+            latitude: Some(50.943),
+            longitude: Some(6.959),
+        }];
+        let now = Instant::now();
+        cache.insert(key.clone(), candidates.clone(), now);
+
+        assert_eq!(
+            cache.get(&key, now + Duration::from_secs(60)),
+            Some(candidates)
+        );
+        assert_eq!(cache.get(&key, now + CACHE_TTL), None);
+        assert_eq!(cache.get(&(ProfileId::Kvb, "köln".to_string()), now), None);
+    }
+
+    #[tokio::test]
+    async fn superseded_searches_fail() {
+        let search = LocationSearch::default();
+        let (first, second) = tokio::join!(search.debounce(), async {
+            tokio::time::sleep(DEBOUNCE / 5).await;
+            search.debounce().await
+        });
+        assert_eq!(first, Err(AppError::Superseded));
+        assert_eq!(second, Ok(()));
+
+        // Searches after the debounce time are independent.
+        assert_eq!(search.debounce().await, Ok(()));
+    }
+}
@@ -1,143 +1,34 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{collections::HashSet, path::PathBuf};
-
-use chrono::{Duration, NaiveDateTime, Utc};
-use railway_core::{HyperRustlsRequester, HyperRustlsRequesterBuilder, JourneysOptions, LocationsOptions, Mode, Provider, Requester};
-use railway_provider_hafas::{
-    client::HafasClient, profile::db::DbProfile, profile::kvb::KvbProfile,
-};
-use rspc::Config;
-use serde::Serialize;
-use specta::Type;
+mod commute;
+mod error;
+mod history;
+mod journey;
+mod live;
+mod locations;
+mod profile;
+mod reminder;
+mod router;
+mod settings;
+mod storage;
+#[cfg(test)]
+mod test;
+mod tray;
+mod watcher;
+
+use commute::CommuteStore;
+use history::History;
+use live::TrackedJourneys;
+use locations::LocationSearch;
+use profile::Clients;
+use router::AppData;
+use settings::SettingsStore;
+use tauri::api::notification::Notification;
 use tauri::{CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu};
 use tauri_plugin_positioner::{Position, WindowExt};
 use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
 
-struct AppData {
-    db_client: HafasClient<HyperRustlsRequester>,
-    kvb_client: HafasClient<HyperRustlsRequester>,
-}
-
-#[derive(Debug, Type, Serialize)]
-struct Journey {
-    from: String,
-    to: String,
-    departure: NaiveDateTime,
-    arrival: NaiveDateTime,
-    planned_depature: NaiveDateTime,
-    planned_arrival: NaiveDateTime,
-    line: String,
-}
-
-impl Journey {
-    async fn new<R: Requester>(client: &HafasClient<R>, start: &str, end: &str, modes: HashSet<Mode>) -> Self {
-        let from = client
-                    .locations(LocationsOptions {
-                        query: start.to_string(),
-                        results: 1,
-                        language: Some("de".to_string()),
-                    })
-                    .await
-                    .unwrap()
-                    .pop()
-                    .unwrap();
-
-                let to = client
-                    .locations(LocationsOptions {
-                        query: end.to_string(),
-                        results: 1,
-                        language: Some("de".to_string()),
-                    })
-                    .await
-                    .unwrap()
-                    .pop()
-                    .unwrap();
-
-                let res = client
-                    .journeys(
-                        from,
-                        to,
-                        JourneysOptions {
-                            departure: Some(
-                                Utc::now()
-                                    .with_timezone(&chrono_tz::Europe::Berlin)
-                                    .checked_add_signed(Duration::minutes(15))
-                                    .unwrap(),
-                            ),
-                            products: modes.into(),
-                            ..Default::default()
-                        },
-                    )
-                    .await
-                    .unwrap();
-
-                Journey {
-                    from: start.to_string(),
-                    to: end.to_string(),
-                    departure: res
-                        .journeys
-                        .first()
-                        .unwrap()
-                        .legs
-                        .first()
-                        .unwrap()
-                        .departure
-                        .unwrap()
-                        .clone()
-                        .naive_local(),
-                    arrival: res
-                        .journeys
-                        .first()
-                        .unwrap()
-                        .legs
-                        .last()
-                        .unwrap()
-                        .arrival
-                        .unwrap()
-                        .clone()
-                        .naive_local(),
-                    planned_arrival: res
-                        .journeys
-                        .first()
-                        .unwrap()
-                        .legs
-                        .last()
-                        .unwrap()
-                        .planned_arrival
-                        .unwrap()
-                        .clone()
-                        .naive_local(),
-                    planned_depature: res
-                        .journeys
-                        .first()
-                        .unwrap()
-                        .legs
-                        .first()
-                        .unwrap()
-                        .planned_departure
-                        .unwrap()
-                        .clone()
-                        .naive_local(),
-                    line: res
-                        .journeys
-                        .first()
-                        .unwrap()
-                        .legs
-                        .first()
-                        .unwrap()
-                        .line
-                        .as_ref()
-                        .unwrap()
-                        .name
-                        .as_ref()
-                        .unwrap()
-                        .clone(),
-                }
-    }
-}
-
 #[tokio::main]
 async fn main() {
     // system tray
@@ -147,24 +38,7 @@ async fn main() {
     let tray = SystemTray::new().with_menu(system_tray_menu);
 
     // rspc
-    let router = <rspc::Router<AppData>>::new()
-        .config(Config::new().export_ts_bindings(
-            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../src/bindings.ts"),
-        ))
-        .query("version", |t| t(|_, _: ()| env!("CARGO_PKG_VERSION")))
-        .query("greet", |t| t(|_, name: String| format!("Hello, {name}!")))
-        .query("db", |t| {
-            t(|ctx, _: ()| async move {
-                Journey::new(&ctx.db_client, "Köln Messe/Deutz", "Köln-Weiden West", HashSet::from([Mode::SuburbanTrain])).await
-            })
-        })
-        .query("kvb", |t| {
-            t(|ctx, _: ()| async move {
-                Journey::new(&ctx.kvb_client, "Bahnhof Deutz/Messe LANXESS arena, Köln", "Weiden West, Köln", HashSet::from([Mode::RegionalTrain])).await
-            })
-        })
-        .build()
-        .arced();
+    let router = router::router();
 
     tauri::Builder::default()
         .setup(|app| {
@@ -179,12 +53,59 @@ async fn main() {
             apply_acrylic(&window, Some((18, 18, 18, 125)))
                 .expect("Unsupported platform! 'apply_blur' is only supported on Windows");
 
+            let config_dir = app
+                .path_resolver()
+                .app_config_dir()
+                .expect("failed to resolve the app config directory");
+            let data = AppData {
+                clients: Clients::default(),
+                commutes: CommuteStore::load(config_dir.join("commutes.json"))?,
+                tracked: TrackedJourneys::default(),
+                settings: SettingsStore::load(config_dir.join("settings.json"))?,
+                locations: LocationSearch::default(),
+                history: History::open(
+                    &app.path_resolver()
+                        .app_data_dir()
+                        .expect("failed to resolve the app data directory")
+                        .join("history.sqlite3"),
+                )?,
+            };
+            app.manage(data.clone());
+
+            // notifications and tray text
+            let identifier = app.config().tauri.bundle.identifier.clone();
+            let handle = app.handle();
+            let settings = data.settings.clone();
+            let commutes = data.commutes.clone();
+            tauri::async_runtime::spawn(watcher::run(
+                data,
+                move |notification| {
+                    let _ = Notification::new(&identifier)
+                        .title(notification.title)
+                        .body(notification.body)
+                        .show();
+                },
+                move |commute_id, journey| {
+                    let settings = settings.get();
+                    let primary = settings
+                        .primary_commute
+                        .or_else(|| commutes.list().first().map(|c| c.id));
+                    if primary != Some(commute_id) {
+                        return;
+                    }
+                    let text = tray::format(&settings.tray_format, journey);
+                    let tray = handle.tray_handle();
+                    #[cfg(target_os = "macos")]
+                    let _ = tray.set_title(&text);
+                    let _ = tray.set_tooltip(&text);
+                },
+            ));
+
             Ok(())
         })
         .system_tray(tray)
-        .plugin(rspc_tauri::plugin(router, |_| AppData {
-            db_client: HafasClient::new(DbProfile {}, HyperRustlsRequesterBuilder::default()),
-            kvb_client: HafasClient::new(KvbProfile {}, HyperRustlsRequesterBuilder::default()),
+        .plugin(rspc_tauri::plugin(router, |app| {
+            app.state::<AppData>().inner().clone()
         }))
         .plugin(tauri_plugin_positioner::init())
         .on_system_tray_event(|app, event| {
@@ -0,0 +1,49 @@
+use chrono_tz::Tz;
+use railway_core::{HyperRustlsRequester, HyperRustlsRequesterBuilder};
+use railway_provider_hafas::{
+    client::HafasClient, profile::db::DbProfile, profile::kvb::KvbProfile, profile::Profile,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// The HAFAS profiles compiled into the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize)]
+pub enum ProfileId {
+    Db,
+    Kvb,
+}
+
+impl ProfileId {
+    /// The timezone of the profile, e.g. to search journeys departing now.
+    pub fn timezone(self) -> Tz {
+        match self {
+            ProfileId::Db => DbProfile {}.timezone(),
+            ProfileId::Kvb => KvbProfile {}.timezone(),
+        }
+    }
+}
+
+/// One client per [`ProfileId`].
+#[derive(Clone)]
+pub struct Clients {
+    db: HafasClient<HyperRustlsRequester>,
+    kvb: HafasClient<HyperRustlsRequester>,
+}
+
+impl Default for Clients {
+    fn default() -> Self {
+        Self {
+            db: HafasClient::new(DbProfile {}, HyperRustlsRequesterBuilder::default()),
+            kvb: HafasClient::new(KvbProfile {}, HyperRustlsRequesterBuilder::default()),
+        }
+    }
+}
+
+impl Clients {
+    pub fn get(&self, profile: ProfileId) -> &HafasClient<HyperRustlsRequester> {
+        match profile {
+            ProfileId::Db => &self.db,
+            ProfileId::Kvb => &self.kvb,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+use railway_core::{Journey, Leg};
+
+use crate::{commute::CommuteInput, watcher::Notification};
+
+/// The first leg not on foot, i.e. the one to catch.
+pub fn first_riding_leg(journey: &Journey) -> Option<&Leg> {
+    journey.legs.iter().find(|l| !l.walking && !l.transfer)
+}
+
+/// The latest time to leave for a journey, given the time it takes to walk to the origin station.
+///
+/// Based on the real-time departure of the first leg not on foot, if known. Walks at the start of the journey are subtracted as well.
+pub fn leave_time(journey: &Journey, walk: Duration) -> Option<DateTime<Tz>> {
+    let leg = first_riding_leg(journey)?;
+    let departure = leg.departure.or(leg.planned_departure)?;
+    let initial_walks = journey
+        .legs
+        .iter()
+        .take_while(|l| l.walking || l.transfer)
+        .filter_map(|l| Some(l.planned_arrival? - l.planned_departure?))
+        .fold(Duration::zero(), |sum, walk| sum + walk);
+    Some(departure - initial_walks - walk)
+}
+
+/// The reminder to leave now for a journey of the commute, if it is time to.
+///
+/// Reminds from the leave time until the first leg not on foot departs, callers need to make sure to remind only once.
+pub fn leave_reminder(
+    commute: &CommuteInput,
+    journey: &Journey,
+    now: DateTime<Tz>,
+) -> Option<Notification> {
+    let settings = &commute.notifications;
+    if !settings.leave_reminder || !settings.allows(&now) {
+        return None;
+    }
+    let leg = first_riding_leg(journey)?;
+    let departure = leg.departure.or(leg.planned_departure)?;
+    let leave = leave_time(journey, Duration::minutes(commute.walk_minutes.into()))?;
+    if now < leave || now >= departure {
+        return None;
+    }
+    let line = leg
+        .line
+        .as_ref()
+        .and_then(|l| l.name.as_deref())
+        .unwrap_or("your connection");
+    Some(Notification {
+        title: commute.name.clone(),
+        body: format!("Leave now to catch {line} at {}", departure.format("%H:%M")),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{journey, time};
+    use crate::{
+        commute::{CommuteStation, NotificationSettings},
+        profile::ProfileId,
+    };
+
+    fn leg(departure: (u32, u32), arrival: (u32, u32), line: Option<&str>) -> Leg {
+        crate::test::leg(
+            time(departure.0, departure.1),
+            time(arrival.0, arrival.1),
+            line,
+        )
+    }
+
+    fn commute() -> CommuteInput {
+        let station = CommuteStation {
+            id: "a".to_string(),
+            name: "a".to_string(),
+        };
+        CommuteInput {
+            name: "Work".to_string(),
+            profile: ProfileId::Db,
+            origin: station.clone(),
+            destination: station,
+            products: vec![],
+            walk_minutes: 10,
+            offset_minutes: 0,
+            active_weekdays: vec![],
+            active_hours: None,
+            notifications: NotificationSettings::default(),
+        }
+    }
+
+    #[test]
+    fn leave_time_skips_initial_walks() {
+        let journey = journey(vec![
+            leg((17, 30), (17, 35), None),
+            leg((17, 42), (18, 0), Some("S12")),
+        ]);
+        assert_eq!(
+            leave_time(&journey, Duration::minutes(10)),
+            Some(time(17, 27))
+        );
+    }
+
+    #[test]
+    fn reminds_between_leave_time_and_departure() {
+        let mut journey = journey(vec![leg((17, 42), (18, 0), Some("S12"))]);
+        let commute = commute();
+        assert_eq!(leave_reminder(&commute, &journey, time(17, 31)), None);
+        assert_eq!(
+            leave_reminder(&commute, &journey, time(17, 32)).map(|n| n.body),
+            Some("Leave now to catch S12 at 17:42".to_string())
+        );
+        assert_eq!(leave_reminder(&commute, &journey, time(17, 42)), None);
+
+        // A delay postpones the reminder.
+        journey.legs[0].departure = Some(time(17, 47));
+        assert_eq!(leave_reminder(&commute, &journey, time(17, 35)), None);
+        assert!(leave_reminder(&commute, &journey, time(17, 37)).is_some());
+    }
+}
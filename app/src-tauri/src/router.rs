@@ -0,0 +1,209 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::Utc;
+use railway_core::{CalendarEvents, JourneysOptions, TransferTimes};
+use rspc::{Config, ErrorCode, Router};
+use serde::Deserialize;
+use specta::Type;
+
+use crate::{
+    commute::{Commute, CommuteInput, CommuteStore},
+    error::AppError,
+    history::{History, HistoryError, StatisticsQuery},
+    journey::{self, Journey, Journeys},
+    live::{self, TrackedJourneys},
+    locations::{LocationQuery, LocationSearch},
+    profile::Clients,
+    settings::{Settings, SettingsStore},
+};
+
+#[derive(Clone)]
+pub struct AppData {
+    pub clients: Clients,
+    pub commutes: CommuteStore,
+    pub tracked: TrackedJourneys,
+    pub settings: SettingsStore,
+    pub locations: LocationSearch,
+    pub history: History,
+}
+
+#[derive(Debug, Deserialize, Type)]
+pub struct LaterJourneys {
+    pub commute_id: u32,
+    /// See [`Journeys`].
+    pub later_ref: String,
+}
+
+#[derive(Debug, Deserialize, Type)]
+pub struct CalendarExport {
+    pub commute_id: u32,
+    /// See [`Journey`].
+    pub journey_id: String,
+    /// One event per leg instead of one for the whole journey.
+    pub per_leg: bool,
+}
+
+fn storage_error(e: std::io::Error) -> rspc::Error {
+    rspc::Error::with_cause(
+        ErrorCode::InternalServerError,
+        "failed to store configuration".to_string(),
+        e,
+    )
+}
+
+fn history_error(e: HistoryError) -> rspc::Error {
+    rspc::Error::with_cause(
+        ErrorCode::InternalServerError,
+        "failed to read the history".to_string(),
+        e,
+    )
+}
+
+fn commute_not_found(id: u32) -> rspc::Error {
+    rspc::Error::new(ErrorCode::NotFound, format!("no commute with id {id}"))
+}
+
+pub fn router() -> Arc<Router<AppData>> {
+    <rspc::Router<AppData>>::new()
+        .config(Config::new().export_ts_bindings(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../src/bindings.ts"),
+        ))
+        .query("version", |t| t(|_, _: ()| env!("CARGO_PKG_VERSION")))
+        .query("greet", |t| t(|_, name: String| format!("Hello, {name}!")))
+        .query("commutes.list", |t| t(|ctx, _: ()| ctx.commutes.list()))
+        .query("commutes.active", |t| {
+            t(|ctx, _: ()| {
+                let now = Utc::now();
+                ctx.commutes
+                    .list()
+                    .into_iter()
+                    .filter(|c| c.is_active(&now.with_timezone(&c.input.profile.timezone())))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .mutation("commutes.add", |t| {
+            t(|ctx, input: CommuteInput| ctx.commutes.add(input).map_err(storage_error))
+        })
+        .mutation("commutes.edit", |t| {
+            t(|ctx, commute: Commute| {
+                let id = commute.id;
+                match ctx.commutes.edit(commute).map_err(storage_error)? {
+                    true => Ok(()),
+                    false => Err(commute_not_found(id)),
+                }
+            })
+        })
+        .mutation("commutes.delete", |t| {
+            t(
+                |ctx, id: u32| match ctx.commutes.delete(id).map_err(storage_error)? {
+                    true => Ok(()),
+                    false => Err(commute_not_found(id)),
+                },
+            )
+        })
+        .query("search_locations", |t| {
+            t(|ctx, query: LocationQuery| async move {
+                Ok(ctx.locations.search(&ctx.clients, query).await?)
+            })
+        })
+        .query("history.statistics", |t| {
+            t(|ctx, query: StatisticsQuery| ctx.history.statistics(&query).map_err(history_error))
+        })
+        .query("history.export_csv", |t| {
+            t(|ctx, commute_id: Option<u32>| {
+                ctx.history.export_csv(commute_id).map_err(history_error)
+            })
+        })
+        .query("settings.get", |t| t(|ctx, _: ()| ctx.settings.get()))
+        .mutation("settings.set", |t| {
+            t(|ctx, settings: Settings| ctx.settings.set(settings).map_err(storage_error))
+        })
+        .query("journeys", |t| {
+            t(|ctx, commute_id: u32| async move {
+                let commute = ctx
+                    .commutes
+                    .get(commute_id)
+                    .ok_or_else(|| commute_not_found(commute_id))?;
+                let res = journey::search(
+                    ctx.clients.get(commute.input.profile),
+                    &commute,
+                    ctx.settings.get().journeys_per_commute.into(),
+                    None,
+                )
+                .await?;
+                let journeys = Journeys::new(&commute, &res)?;
+                if let Some(first) = res.journeys.into_iter().next() {
+                    ctx.tracked.track(commute_id, first);
+                }
+                Ok(journeys)
+            })
+        })
+        .query("journeys.later", |t| {
+            t(|ctx, input: LaterJourneys| async move {
+                let commute = ctx
+                    .commutes
+                    .get(input.commute_id)
+                    .ok_or_else(|| commute_not_found(input.commute_id))?;
+                let res = journey::search(
+                    ctx.clients.get(commute.input.profile),
+                    &commute,
+                    ctx.settings.get().journeys_per_commute.into(),
+                    Some(input.later_ref),
+                )
+                .await?;
+                Ok(Journeys::new(&commute, &res)?)
+            })
+        })
+        .query("journeys.alternatives", |t| {
+            t(|ctx, commute_id: u32| async move {
+                let commute = ctx
+                    .commutes
+                    .get(commute_id)
+                    .ok_or_else(|| commute_not_found(commute_id))?;
+                let Some(tracked) = ctx.tracked.get(commute_id) else {
+                    return Ok(vec![]);
+                };
+                let alternatives = tracked
+                    .alternatives(
+                        ctx.clients.get(commute.input.profile),
+                        &TransferTimes::default(),
+                        JourneysOptions {
+                            departure: Some(
+                                Utc::now().with_timezone(&commute.input.profile.timezone()),
+                            ),
+                            products: commute.modes().into(),
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                    .map_err(AppError::from)?;
+                Ok(alternatives
+                    .iter()
+                    .map(|j| Journey::new(&commute, j))
+                    .collect::<Result<Vec<_>, _>>()?)
+            })
+        })
+        .query("journeys.calendar", |t| {
+            t(|ctx, input: CalendarExport| async move {
+                let commute = ctx
+                    .commutes
+                    .get(input.commute_id)
+                    .ok_or_else(|| commute_not_found(input.commute_id))?;
+                let events = match input.per_leg {
+                    true => CalendarEvents::Legs,
+                    false => CalendarEvents::Journey,
+                };
+                Ok(journey::calendar(
+                    ctx.clients.get(commute.input.profile),
+                    input.journey_id,
+                    events,
+                )
+                .await?)
+            })
+        })
+        .subscription("journeys.live", |t| {
+            t(|ctx, commute_id: u32| live::updates(ctx, commute_id))
+        })
+        .build()
+        .arced()
+}
@@ -0,0 +1,59 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::{storage, tray};
+
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The commute shown in the tray, [`None`] for the first one.
+    pub primary_commute: Option<u32>,
+    /// The tray text, see [`tray::format`] for the placeholders.
+    pub tray_format: String,
+    /// How many journeys to request per commute at least, providers may return more.
+    pub journeys_per_commute: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            primary_commute: None,
+            tray_format: tray::DEFAULT_FORMAT.to_string(),
+            journeys_per_commute: 3,
+        }
+    }
+}
+
+/// The app settings, persisted as JSON.
+#[derive(Clone)]
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Arc<Mutex<Settings>>,
+}
+
+impl SettingsStore {
+    /// Load the settings from the given file, using the defaults if it does not exist yet.
+    pub fn load(path: PathBuf) -> std::io::Result<Self> {
+        let settings = storage::read_json(&path)?;
+        Ok(Self {
+            path,
+            settings: Arc::new(Mutex::new(settings)),
+        })
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, settings: Settings) -> std::io::Result<()> {
+        let mut current = self.settings.lock().unwrap();
+        storage::write_json(&self.path, &settings)?;
+        *current = settings;
+        Ok(())
+    }
+}
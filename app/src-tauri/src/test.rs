@@ -0,0 +1,69 @@
+//! Fixtures shared by the tests of the app.
+
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+use railway_core::{Journey, Leg, Line, Mode, Place, Product, Station};
+
+/// A time on Monday, September 2, 2024 in Berlin.
+pub fn time(hour: u32, minute: u32) -> DateTime<Tz> {
+    time_on(2, hour, minute)
+}
+
+/// A time on a day of September 2024 in Berlin.
+pub fn time_on(day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+    chrono_tz::Europe::Berlin
+        .with_ymd_and_hms(2024, 9, day, hour, minute, 0)
+        .unwrap()
+}
+
+pub fn station(id: &str) -> Place {
+    Place::Station(Station {
+        id: id.to_string(),
+        name: Some(id.to_string()),
+        ..Default::default()
+    })
+}
+
+/// A punctual leg from `a` to `b` with the given line, or a walk without one.
+pub fn leg(departure: DateTime<Tz>, arrival: DateTime<Tz>, line: Option<&str>) -> Leg {
+    Leg {
+        origin: station("a"),
+        destination: station("b"),
+        departure: Some(departure),
+        planned_departure: Some(departure),
+        arrival: Some(arrival),
+        planned_arrival: Some(arrival),
+        reachable: true,
+        trip_id: None,
+        line: line.map(|name| Line {
+            name: Some(name.to_string()),
+            fahrt_nr: None,
+            mode: Mode::SuburbanTrain,
+            product: Product::unknown(),
+            operator: None,
+            product_name: None,
+        }),
+        direction: None,
+        arrival_platform: None,
+        planned_arrival_platform: None,
+        departure_platform: None,
+        planned_departure_platform: None,
+        frequency: None,
+        cancelled: false,
+        intermediate_locations: vec![],
+        load_factor: None,
+        remarks: vec![],
+        polyline: None,
+        walking: line.is_none(),
+        transfer: false,
+        distance: None,
+    }
+}
+
+pub fn journey(legs: Vec<Leg>) -> Journey {
+    Journey {
+        id: "journey".to_string(),
+        legs,
+        price: None,
+    }
+}
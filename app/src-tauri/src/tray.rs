@@ -0,0 +1,109 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use railway_core::{Journey, Leg};
+
+/// The default tray text, e.g. "S12 17:42 (+3)".
+pub const DEFAULT_FORMAT: &str = "{line} {time} {status}";
+
+fn departing_leg(journey: &Journey) -> Option<&Leg> {
+    journey
+        .legs
+        .iter()
+        .find(|l| !l.walking && !l.transfer)
+        .or(journey.legs.first())
+}
+
+fn clock(time: Option<DateTime<Tz>>) -> String {
+    time.map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// The tray text for a journey.
+///
+/// Supported placeholders, all referring to the first leg not on foot:
+/// - `{line}`: the line name, e.g. "S12"
+/// - `{time}`: the planned departure, e.g. "17:42"
+/// - `{realtime}`: the real-time departure, or the planned one if unknown
+/// - `{status}`: "(+3)" if delayed, "cancelled" if cancelled, empty otherwise
+/// - `{platform}`: the real-time departure platform, or the planned one if unknown
+///
+/// Whitespace left over by empty placeholders is collapsed.
+pub fn format(format: &str, journey: &Journey) -> String {
+    let Some(leg) = departing_leg(journey) else {
+        return String::new();
+    };
+    let planned = leg.planned_departure.or(leg.departure);
+    let realtime = leg.departure.or(planned);
+    let status = if leg.cancelled {
+        "cancelled".to_string()
+    } else {
+        match leg
+            .departure
+            .zip(leg.planned_departure)
+            .map(|(departure, planned)| (departure - planned).num_minutes())
+        {
+            // Delays of less than a minute are not shown.
+            Some(minutes) if minutes != 0 => format!("({:+})", minutes),
+            _ => String::new(),
+        }
+    };
+    let text = format
+        .replace(
+            "{line}",
+            leg.line
+                .as_ref()
+                .and_then(|l| l.name.as_deref())
+                .unwrap_or_default(),
+        )
+        .replace("{time}", &clock(planned))
+        .replace("{realtime}", &clock(realtime))
+        .replace("{status}", &status)
+        .replace(
+            "{platform}",
+            leg.departure_platform
+                .as_deref()
+                .or(leg.planned_departure_platform.as_deref())
+                .unwrap_or_default(),
+        );
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::test::{leg, time};
+
+    fn journey() -> Journey {
+        crate::test::journey(vec![Leg {
+            planned_departure_platform: Some("3".to_string()),
+            ..leg(time(17, 42), time(18, 0), Some("S12"))
+        }])
+    }
+
+    #[test]
+    fn formats_delay_and_cancellation() {
+        let mut journey = journey();
+        assert_eq!(format(DEFAULT_FORMAT, &journey), "S12 17:42");
+
+        journey.legs[0].departure = Some(time(17, 42) + Duration::seconds(30));
+        assert_eq!(format(DEFAULT_FORMAT, &journey), "S12 17:42");
+
+        journey.legs[0].departure = Some(time(17, 45));
+        assert_eq!(format(DEFAULT_FORMAT, &journey), "S12 17:42 (+3)");
+
+        journey.legs[0].cancelled = true;
+        assert_eq!(format(DEFAULT_FORMAT, &journey), "S12 17:42 cancelled");
+    }
+
+    #[test]
+    fn formats_custom_placeholders() {
+        let mut journey = journey();
+        journey.legs[0].departure = Some(time(17, 45));
+        assert_eq!(
+            format("{line} at {realtime} from platform {platform}", &journey),
+            "S12 at 17:45 from platform 3"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use railway_core::{
+    Journey, JourneyDiff, LegChange, Provider, RefreshJourneyOptions, RemarkType, TransferTimes,
+};
+
+use crate::{
+    commute::{Commute, CommuteInput},
+    journey,
+    live::refresh_interval,
+    reminder,
+    router::AppData,
+};
+
+/// A desktop notification to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+}
+
+/// The notifications to show for the changes between two versions of the next journey of a commute.
+pub fn detect(
+    commute: &CommuteInput,
+    old: &Journey,
+    new: &Journey,
+    now: DateTime<Tz>,
+) -> Vec<Notification> {
+    let settings = &commute.notifications;
+    if !settings.allows(&now) {
+        return vec![];
+    }
+    let threshold = Duration::minutes(settings.delay_threshold_minutes.into());
+
+    let diff = JourneyDiff::new(old, new, &TransferTimes::default());
+    let mut notifications = vec![];
+    for leg_diff in &diff.legs {
+        let line = new
+            .legs
+            .iter()
+            .find(|l| l.id() == leg_diff.leg_id)
+            .and_then(|l| l.line.as_ref()?.name.clone())
+            .unwrap_or_else(|| "Your connection".to_string());
+        for change in &leg_diff.changes {
+            let body = match change {
+                LegChange::DepartureDelay {
+                    previous,
+                    current: Some(current),
+                } if *current > threshold && previous.is_none_or(|p| p <= threshold) => {
+                    format!("{line} is delayed by {} min", current.num_minutes())
+                }
+                LegChange::Cancelled => format!("{line} is cancelled"),
+                LegChange::DeparturePlatform {
+                    current: Some(platform),
+                    ..
+                } => format!("{line} now departs from platform {platform}"),
+                LegChange::RemarkAdded(remark) if remark.r#type == RemarkType::Status => {
+                    format!("{line}: {}", remark.text)
+                }
+                _ => continue,
+            };
+            notifications.push(Notification {
+                title: commute.name.clone(),
+                body,
+            });
+        }
+    }
+    notifications
+}
+
+fn departed(journey: &Journey, now: DateTime<Utc>) -> bool {
+    journey
+        .legs
+        .first()
+        .and_then(|l| l.departure.or(l.planned_departure))
+        .is_none_or(|departure| departure < now)
+}
+
+/// Refresh the next journey of a commute, moving on to the following one once it departed.
+async fn refresh<F, G>(
+    ctx: &AppData,
+    commute: &Commute,
+    seen: &mut HashMap<u32, Journey>,
+    due: &mut HashMap<u32, DateTime<Utc>>,
+    notify: &F,
+    refreshed: &G,
+) where
+    F: Fn(Notification),
+    G: Fn(u32, &Journey),
+{
+    let now = Utc::now();
+    let client = ctx.clients.get(commute.input.profile);
+    // The watcher keeps its own journey per commute, the one selected in the frontend is tracked separately.
+    let journey = match seen.get(&commute.id).filter(|j| !departed(j, now)) {
+        Some(previous) => client
+            .refresh_journey(previous, RefreshJourneyOptions::default())
+            .await
+            .ok(),
+        None => journey::search(client, commute, 1, None)
+            .await
+            .ok()
+            .and_then(|res| res.journeys.into_iter().next()),
+    };
+    let Some(journey) = journey else {
+        due.insert(commute.id, now + Duration::minutes(1));
+        return;
+    };
+
+    if let Some(old) = seen.get(&commute.id) {
+        let local = now.with_timezone(&commute.input.profile.timezone());
+        for notification in detect(&commute.input, old, &journey, local) {
+            notify(notification);
+        }
+    }
+    let departure = journey
+        .legs
+        .first()
+        .and_then(|l| l.departure.or(l.planned_departure));
+    due.insert(commute.id, now + refresh_interval(departure, now));
+    refreshed(commute.id, &journey);
+    if let Err(e) = ctx.history.record(commute.id, &journey) {
+        log::warn!("failed to record the history: {e}");
+    }
+    seen.insert(commute.id, journey);
+}
+
+/// Track the next journey of each active commute in the background, notifying about changes and when to leave.
+///
+/// `refreshed` is called with the commute id and journey after each refresh.
+pub async fn run<F, G>(ctx: AppData, notify: F, refreshed: G)
+where
+    F: Fn(Notification) + Send,
+    G: Fn(u32, &Journey) + Send,
+{
+    let mut seen: HashMap<u32, Journey> = HashMap::new();
+    let mut due: HashMap<u32, DateTime<Utc>> = HashMap::new();
+    // The first leg not on foot of the journey last reminded to leave for, per commute.
+    let mut reminded: HashMap<u32, String> = HashMap::new();
+    loop {
+        let now = Utc::now();
+        for commute in ctx.commutes.list() {
+            let local = now.with_timezone(&commute.input.profile.timezone());
+            if !commute.is_active(&local) {
+                continue;
+            }
+
+            if due.get(&commute.id).is_none_or(|d| *d <= now) {
+                refresh(&ctx, &commute, &mut seen, &mut due, &notify, &refreshed).await;
+            }
+
+            // Re-evaluated on every iteration, as refreshes may change the leave time.
+            let Some(journey) = seen.get(&commute.id) else {
+                continue;
+            };
+            let Some(leg_id) = reminder::first_riding_leg(journey).map(|l| l.id()) else {
+                continue;
+            };
+            if reminded.get(&commute.id) == Some(&leg_id) {
+                continue;
+            }
+            if let Some(notification) = reminder::leave_reminder(&commute.input, journey, local) {
+                notify(notification);
+                reminded.insert(commute.id, leg_id);
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use railway_core::{Leg, Remark, RemarkAssociation};
+
+    use super::*;
+    use crate::test::{leg, time};
+    use crate::{
+        commute::{ActiveHours, CommuteStation, NotificationSettings},
+        profile::ProfileId,
+    };
+
+    fn station(id: &str) -> CommuteStation {
+        CommuteStation {
+            id: id.to_string(),
+            name: id.to_string(),
+        }
+    }
+
+    fn commute(notifications: NotificationSettings) -> CommuteInput {
+        CommuteInput {
+            name: "Work".to_string(),
+            profile: ProfileId::Db,
+            origin: station("a"),
+            destination: station("b"),
+            products: vec![],
+            offset_minutes: 0,
+            walk_minutes: 0,
+            active_weekdays: vec![],
+            active_hours: None,
+            notifications,
+        }
+    }
+
+    fn journey() -> Journey {
+        crate::test::journey(vec![Leg {
+            trip_id: Some("trip".to_string()),
+            departure_platform: Some("1".to_string()),
+            planned_departure_platform: Some("1".to_string()),
+            ..leg(time(8, 0), time(8, 30), Some("RE 1"))
+        }])
+    }
+
+    fn bodies(notifications: Vec<Notification>) -> Vec<String> {
+        notifications.into_iter().map(|n| n.body).collect()
+    }
+
+    #[test]
+    fn notifies_once_delay_exceeds_threshold() {
+        let commute = commute(NotificationSettings::default());
+        let old = journey();
+        let mut new = old.clone();
+        new.legs[0].departure = Some(time(8, 4));
+        assert!(detect(&commute, &old, &new, time(7, 50)).is_empty());
+
+        let old = new.clone();
+        new.legs[0].departure = Some(time(8, 7));
+        assert_eq!(
+            bodies(detect(&commute, &old, &new, time(7, 50))),
+            vec!["RE 1 is delayed by 7 min"]
+        );
+
+        let old = new.clone();
+        new.legs[0].departure = Some(time(8, 9));
+        assert!(detect(&commute, &old, &new, time(7, 50)).is_empty());
+    }
+
+    #[test]
+    fn notifies_about_cancellations_platforms_and_status() {
+        let commute = commute(NotificationSettings::default());
+        let old = journey();
+        let mut new = old.clone();
+        new.legs[0].cancelled = true;
+        new.legs[0].departure_platform = Some("3".to_string());
+        new.legs[0].remarks.push(Remark {
+            code: "text.realtime".to_string(),
+            text: "Disruption".to_string(),
+            r#type: RemarkType::Status,
+            association: RemarkAssociation::None,
+            summary: None,
+            trip_id: None,
+        });
+        new.legs[0].remarks.push(Remark {
+            code: "FB".to_string(),
+            text: "Bicycles allowed".to_string(),
+            r#type: RemarkType::Hint,
+            association: RemarkAssociation::Bike,
+            summary: None,
+            trip_id: None,
+        });
+
+        assert_eq!(
+            bodies(detect(&commute, &old, &new, time(7, 50))),
+            vec![
+                "RE 1 now departs from platform 3",
+                "RE 1 is cancelled",
+                "RE 1: Disruption",
+            ]
+        );
+    }
+
+    #[test]
+    fn respects_quiet_hours() {
+        let commute = commute(NotificationSettings {
+            quiet_hours: Some(ActiveHours { from: 22, until: 6 }),
+            ..Default::default()
+        });
+        let old = journey();
+        let mut new = old.clone();
+        new.legs[0].cancelled = true;
+        assert!(detect(&commute, &old, &new, time(5, 0)).is_empty());
+        assert!(detect(&commute, &old, &new, time(23, 0)).is_empty());
+        assert_eq!(detect(&commute, &old, &new, time(7, 0)).len(), 1);
+    }
+}
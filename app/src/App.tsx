@@ -1,31 +1,160 @@
-import type { Journey } from "./bindings";
-import { rspc } from "./rspc";
+import type { AppError, Commute, Journey, Journeys } from "./bindings";
+import { useState } from "react";
+import { CommuteForm } from "./CommuteForm";
+import { HistoryView } from "./HistoryView";
+import { parseAppError, rspc } from "./rspc";
+
+const colors = ["green", "red"] as const;
 
 function App() {
-	const { data: dbInfo, isLoading: dbLoading } = rspc.useQuery(["db"]);
-	const { data: kvbInfo, isLoading: kvbLoading } = rspc.useQuery(["kvb"]);
+	const { data: commutes, isLoading } = rspc.useQuery(["commutes.active"]);
+	const [adding, setAdding] = useState(false);
+	const [showHistory, setShowHistory] = useState(false);
+
+	if (adding) {
+		return <CommuteForm onDone={() => setAdding(false)} />;
+	}
+	if (showHistory) {
+		return <HistoryView onClose={() => setShowHistory(false)} />;
+	}
 
 	return (
 		<div className="rounded-xl text-white">
-			{dbLoading || kvbLoading ? (
+			{isLoading || !commutes ? (
 				<div className="w-screen h-screen flex justify-center items-center">Loading...</div>
+			) : commutes.length === 0 ? (
+				<div className="w-screen h-screen flex justify-center items-center">
+					<button type="button" onClick={() => setAdding(true)}>
+						Add a commute
+					</button>
+				</div>
 			) : (
 				<div>
-					<TrainSegment journey={dbInfo} color="green" />
-					<TrainSegment journey={kvbInfo} color="red" />
+					{commutes.map((commute, i) => (
+						<CommuteSegment
+							key={commute.id}
+							commute={commute}
+							color={colors[i % colors.length]}
+						/>
+					))}
+					<button type="button" className="text-xs text-gray-400 mx-4" onClick={() => setAdding(true)}>
+						Add commute
+					</button>
+					<button type="button" className="text-xs text-gray-400 mx-4" onClick={() => setShowHistory(true)}>
+						History
+					</button>
 				</div>
 			)}
 		</div>
 	);
 }
 
-const TrainSegment: React.FC<{ journey: Journey; color: "green" | "red" }> = ({
-	journey,
+const CommuteSegment: React.FC<{ commute: Commute; color: "green" | "red" }> = ({
+	commute,
 	color,
 }) => {
-	const duration =
-		new Date(journey.planned_arrival).getTime() -
-		new Date(journey.planned_depature).getTime();
+	const { data, error } = rspc.useQuery(["journeys", commute.id]);
+	const { client, queryClient } = rspc.useContext();
+	// only changes are pushed, so the full search is not repeated
+	rspc.useSubscription(["journeys.live", commute.id], {
+		enabled: data !== undefined,
+		onData: (update) =>
+			queryClient.setQueryData<Journeys>(["journeys", commute.id], (old) =>
+				old && { ...old, journeys: [update.journey, ...old.journeys.slice(1)] },
+			),
+	});
+
+	const { data: alternatives } = rspc.useQuery(["journeys.alternatives", commute.id], {
+		enabled: data?.journeys[0]?.broken ?? false,
+	});
+
+	const loadLater = async () => {
+		if (!data?.later_ref) return;
+		const later = await client.query([
+			"journeys.later",
+			{ commute_id: commute.id, later_ref: data.later_ref },
+		]);
+		queryClient.setQueryData<Journeys>(["journeys", commute.id], (old) =>
+			old && { journeys: [...old.journeys, ...later.journeys], later_ref: later.later_ref },
+		);
+	};
+
+	if (!data) {
+		return (
+			<div className="rounded-lg text-white ring-1 ring-gray-700 shadow-md m-4 p-4">
+				<p>{commute.input.name}</p>
+				{error && (
+					<p className="text-sm text-red-400">{errorText(parseAppError(error))}</p>
+				)}
+			</div>
+		);
+	}
+	return (
+		<div>
+			{data.journeys.map((journey) => (
+				<TrainSegment
+					key={journey.planned_depature}
+					commuteId={commute.id}
+					journey={journey}
+					color={color}
+				/>
+			))}
+			{alternatives && alternatives.length > 0 && (
+				<div>
+					<p className="text-xs text-red-400 mx-4">Connection broken, alternatives:</p>
+					{alternatives.map((journey) => (
+						<TrainSegment
+							key={journey.planned_depature}
+							commuteId={commute.id}
+							journey={journey}
+							color={color}
+						/>
+					))}
+				</div>
+			)}
+			{data.later_ref && (
+				<button type="button" className="text-xs text-gray-400 mx-4" onClick={loadLater}>
+					Later
+				</button>
+			)}
+		</div>
+	);
+};
+
+function errorText(error: AppError): string {
+	switch (error.type) {
+		case "NoLocation":
+			return "Station not found";
+		case "NoJourney":
+			return "No connection found";
+		case "Provider":
+			return `Provider error: ${error.message}`;
+		case "MissingRealtime":
+			return "No real-time data, showing planned times";
+		case "Superseded":
+			return "Search superseded";
+	}
+}
+
+const TrainSegment: React.FC<{
+	commuteId: number;
+	journey: Journey;
+	color: "green" | "red";
+}> = ({ commuteId, journey, color }) => {
+	const { client } = rspc.useContext();
+
+	const addToCalendar = async () => {
+		const ics = await client.query([
+			"journeys.calendar",
+			{ commute_id: commuteId, journey_id: journey.id, per_leg: false },
+		]);
+		const link = document.createElement("a");
+		link.href = URL.createObjectURL(new Blob([ics], { type: "text/calendar" }));
+		link.download = "journey.ics";
+		link.click();
+		URL.revokeObjectURL(link.href);
+	};
+
 	const arrivalDelay = new Date(
 		new Date(journey.arrival).getTime() -
 			new Date(journey.planned_arrival).getTime(),
@@ -58,13 +187,32 @@ const TrainSegment: React.FC<{ journey: Journey; color: "green" | "red" }> = ({
 				<p className="text-xs max-w-24 text-ellipsis whitespace-nowrap overflow-hidden">
 					{journey.from}
 				</p>
+				{journey.leave_at && (
+					<p className="text-xs text-gray-400">
+						Leave at{" "}
+						{new Date(journey.leave_at).toLocaleTimeString("de-DE", {
+							hour: "2-digit",
+							minute: "2-digit",
+						})}
+					</p>
+				)}
 			</div>
 			<div
 				className={`h-0.5 flex-grow ${color === "green" ? "bg-green-700" : "bg-red-700"}`}
 			/>
 			<div className="p-4 text-center text-xs">
-				<p>{new Date(duration).getMinutes()}min </p>
-				<p>{journey.line}</p>
+				<p>{journey.duration_minutes}min </p>
+				<p>{journey.legs.map((leg) => (leg.walking ? "Walk" : leg.line)).join(" → ")}</p>
+				{journey.transfers > 0 && <p>{journey.transfers} transfers</p>}
+				{journey.cancelled && <p className="text-red-400">Cancelled</p>}
+				<button type="button" className="text-gray-400" onClick={addToCalendar}>
+					Add to calendar
+				</button>
+				{journey.warnings.map((warning) => (
+					<p key={warning.type} className="text-gray-400">
+						{errorText(warning)}
+					</p>
+				))}
 			</div>
 			<div
 				className={`h-0.5 flex-grow ${color === "green" ? "bg-green-700" : "bg-red-700"}`}
@@ -0,0 +1,80 @@
+import { useState } from "react";
+import type { CommuteStation, ProfileId } from "./bindings";
+import { rspc } from "./rspc";
+import { StationPicker } from "./StationPicker";
+
+export const CommuteForm: React.FC<{ onDone: () => void }> = ({ onDone }) => {
+	const [name, setName] = useState("");
+	const [profile, setProfile] = useState<ProfileId>("Db");
+	const [origin, setOrigin] = useState<CommuteStation | null>(null);
+	const [destination, setDestination] = useState<CommuteStation | null>(null);
+	const [walkMinutes, setWalkMinutes] = useState(0);
+	const { queryClient } = rspc.useContext();
+	const add = rspc.useMutation("commutes.add", {
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["commutes.active"] });
+			onDone();
+		},
+	});
+
+	return (
+		<form
+			className="m-4 flex flex-col gap-2 text-white"
+			onSubmit={(e) => {
+				e.preventDefault();
+				if (!origin || !destination) return;
+				add.mutate({
+					name,
+					profile,
+					origin,
+					destination,
+					products: [],
+					walk_minutes: walkMinutes,
+					offset_minutes: 0,
+					active_weekdays: [],
+					active_hours: null,
+				});
+			}}
+		>
+			<input
+				className="rounded bg-gray-800 px-2 py-1 text-sm"
+				placeholder="Name"
+				value={name}
+				onChange={(e) => setName(e.target.value)}
+			/>
+			<select
+				className="rounded bg-gray-800 px-2 py-1 text-sm"
+				value={profile}
+				onChange={(e) => setProfile(e.target.value as ProfileId)}
+			>
+				<option value="Db">DB</option>
+				<option value="Kvb">KVB</option>
+			</select>
+			<StationPicker profile={profile} value={origin} onChange={setOrigin} placeholder="From" />
+			<StationPicker
+				profile={profile}
+				value={destination}
+				onChange={setDestination}
+				placeholder="To"
+			/>
+			<label className="flex items-center gap-2 text-sm">
+				Walk to the station
+				<input
+					type="number"
+					min={0}
+					className="w-16 rounded bg-gray-800 px-2 py-1"
+					value={walkMinutes}
+					onChange={(e) => setWalkMinutes(Number(e.target.value))}
+				/>
+				min
+			</label>
+			<button
+				type="submit"
+				className="rounded bg-gray-700 px-2 py-1 text-sm"
+				disabled={!name || !origin || !destination}
+			>
+				Add commute
+			</button>
+		</form>
+	);
+};
@@ -0,0 +1,64 @@
+import { rspc } from "./rspc";
+
+const percent = (value: number) => `${Math.round(value * 100)}%`;
+const minutes = (value: number) => `${value.toFixed(1)}min`;
+
+export const HistoryView: React.FC<{ onClose: () => void }> = ({ onClose }) => {
+	const { data: stats } = rspc.useQuery([
+		"history.statistics",
+		{ commute_id: null, punctual_minutes: 6 },
+	]);
+	const { client } = rspc.useContext();
+
+	const exportCsv = async () => {
+		const csv = await client.query(["history.export_csv", null]);
+		const link = document.createElement("a");
+		link.href = URL.createObjectURL(new Blob([csv], { type: "text/csv" }));
+		link.download = "history.csv";
+		link.click();
+		URL.revokeObjectURL(link.href);
+	};
+
+	if (!stats) {
+		return <div className="m-4 text-white">Loading...</div>;
+	}
+	return (
+		<div className="m-4 flex flex-col gap-2 text-sm text-white">
+			<p>
+				{stats.legs} legs, {stats.cancelled} cancelled, {stats.platform_changes} platform
+				changes
+			</p>
+			{stats.punctuality !== null && <p>Punctuality: {percent(stats.punctuality)}</p>}
+			<div>
+				{stats.by_weekday.map((d) => (
+					<p key={d.weekday}>
+						{d.weekday}: {minutes(d.average_delay_minutes)}
+					</p>
+				))}
+			</div>
+			<div>
+				{stats.by_hour.map((h) => (
+					<p key={h.hour}>
+						{h.hour}:00: {minutes(h.average_delay_minutes)}
+					</p>
+				))}
+			</div>
+			<div>
+				<p>Worst days</p>
+				{stats.worst_days.map((d) => (
+					<p key={d.day}>
+						{d.day}: {minutes(d.average_delay_minutes)}, {d.cancelled} cancelled
+					</p>
+				))}
+			</div>
+			<div className="flex gap-2">
+				<button type="button" className="rounded bg-gray-700 px-2 py-1" onClick={exportCsv}>
+					Export CSV
+				</button>
+				<button type="button" className="rounded bg-gray-700 px-2 py-1" onClick={onClose}>
+					Back
+				</button>
+			</div>
+		</div>
+	);
+};
@@ -0,0 +1,47 @@
+import { useState } from "react";
+import type { CommuteStation, ProfileId } from "./bindings";
+import { rspc } from "./rspc";
+
+// Searching is debounced and cached by the backend, so every keystroke can be sent.
+// Superseded searches fail instead of returning no candidates, so they are neither cached nor retried.
+export const StationPicker: React.FC<{
+	profile: ProfileId;
+	value: CommuteStation | null;
+	onChange: (station: CommuteStation) => void;
+	placeholder: string;
+}> = ({ profile, value, onChange, placeholder }) => {
+	const [query, setQuery] = useState("");
+	const { data: candidates } = rspc.useQuery(["search_locations", { profile, query }], {
+		enabled: query.trim().length > 1,
+		retry: false,
+	});
+
+	return (
+		<div className="relative">
+			<input
+				className="w-full rounded bg-gray-800 px-2 py-1 text-sm"
+				placeholder={placeholder}
+				value={query || value?.name || ""}
+				onChange={(e) => setQuery(e.target.value)}
+			/>
+			{query && candidates && candidates.length > 0 && (
+				<ul className="absolute z-10 w-full rounded bg-gray-800 text-sm shadow-md">
+					{candidates.map((candidate) => (
+						<li key={candidate.id}>
+							<button
+								type="button"
+								className="w-full px-2 py-1 text-left hover:bg-gray-700"
+								onClick={() => {
+									onChange({ id: candidate.id, name: candidate.name });
+									setQuery("");
+								}}
+							>
+								{candidate.name}
+							</button>
+						</li>
+					))}
+				</ul>
+			)}
+		</div>
+	);
+};
@@ -2,12 +2,116 @@
 
 export type Procedures = {
     queries: 
-        { key: "db", input: never, result: Journey } | 
+        { key: "commutes.active", input: never, result: Commute[] } | 
+        { key: "commutes.list", input: never, result: Commute[] } | 
         { key: "greet", input: string, result: string } | 
-        { key: "kvb", input: never, result: Journey } | 
+        { key: "history.export_csv", input: number | null, result: string } | 
+        { key: "history.statistics", input: StatisticsQuery, result: Statistics } | 
+        { key: "journeys", input: number, result: Journeys } | 
+        { key: "journeys.alternatives", input: number, result: Journey[] } | 
+        { key: "journeys.calendar", input: CalendarExport, result: string } | 
+        { key: "journeys.later", input: LaterJourneys, result: Journeys } | 
+        { key: "search_locations", input: LocationQuery, result: StationCandidate[] } | 
+        { key: "settings.get", input: never, result: Settings } | 
         { key: "version", input: never, result: string },
-    mutations: never,
-    subscriptions: never
+    mutations: 
+        { key: "commutes.add", input: CommuteInput, result: Commute } | 
+        { key: "commutes.delete", input: number, result: null } | 
+        { key: "commutes.edit", input: Commute, result: null } | 
+        { key: "settings.set", input: Settings, result: null },
+    subscriptions: 
+        { key: "journeys.live", input: number, result: JourneyUpdate }
 };
 
-export type Journey = { from: string; to: string; departure: string; arrival: string; planned_depature: string; planned_arrival: string; line: string }
+/**
+ * A station of a commute, pinned by its provider-specific id.
+ */
+export type CommuteStation = { id: string; name: string }
+
+export type Commute = { id: number; input: CommuteInput }
+
+export type Journey = { id: string; from: string; to: string; departure: string; arrival: string; planned_depature: string; planned_arrival: string; line: string | null; leave_at: string | null; transfers: number; duration_minutes: number; cancelled: boolean; broken: boolean; legs: JourneyLeg[]; warnings: AppError[] }
+
+export type CalendarExport = { commute_id: number; journey_id: string; per_leg: boolean }
+
+/**
+ * Some journeys of a commute, with a reference to load later ones.
+ */
+export type Journeys = { journeys: Journey[]; later_ref: string | null }
+
+export type HourDelay = { hour: number; legs: number; average_delay_minutes: number }
+
+export type LocationQuery = { profile: ProfileId; query: string }
+
+/**
+ * A commute without its id, as given by the frontend when adding one.
+ */
+export type CommuteInput = { name: string; profile: ProfileId; origin: CommuteStation; destination: CommuteStation; products: TransportMode[]; walk_minutes?: number; offset_minutes: number; active_weekdays: Weekday[]; active_hours: ActiveHours | null; notifications?: NotificationSettings }
+
+export type JourneyLeg = { line: string | null; walking: boolean; departure: string; arrival: string; departure_delay_minutes: number | null; arrival_delay_minutes: number | null; cancelled: boolean }
+
+export type DayDelay = { day: string; legs: number; cancelled: number; average_delay_minutes: number }
+
+/**
+ * When to notify about changes of a commute.
+ */
+export type NotificationSettings = { enabled: boolean; delay_threshold_minutes: number; quiet_hours: ActiveHours | null; leave_reminder: boolean }
+
+/**
+ * Errors of the app, serialized as JSON into the message of the [`rspc::Error`] sent to the frontend.
+ */
+export type AppError = { type: "NoLocation" } | { type: "NoJourney" } | { type: "Provider"; message: string } | { type: "MissingRealtime" } | { type: "Superseded" }
+
+/**
+ * What to compute [`Statistics`] for.
+ */
+export type StatisticsQuery = { commute_id: number | null; punctual_minutes: number }
+
+/**
+ * What changed about a leg, delays are in minutes.
+ */
+export type ChangeKind = { type: "DepartureDelay"; value: number | null } | { type: "ArrivalDelay"; value: number | null } | { type: "DeparturePlatform"; value: string | null } | { type: "ArrivalPlatform"; value: string | null } | { type: "Cancelled" } | { type: "Reinstated" } | { type: "Remark"; value: string } | { type: "ConnectionLost" } | { type: "Rerouted" }
+
+/**
+ * A station found by [`LocationSearch::search`], to be pinned as a commute station by its id.
+ */
+export type StationCandidate = { id: string; name: string; products: TransportMode[]; latitude: number | null; longitude: number | null }
+
+/**
+ * The modes of transport a commute may use, mirroring [`Mode`].
+ */
+export type TransportMode = "HighSpeedTrain" | "RegionalTrain" | "SuburbanTrain" | "Subway" | "Tram" | "Bus" | "Ferry" | "Cablecar" | "OnDemand" | "Unknown"
+
+/**
+ * How reliable the recorded legs were.
+ * 
+ * Delays are arrival delays, falling back to departure delays, of legs that were not cancelled and have real-time data.
+ */
+export type Statistics = { legs: number; cancelled: number; platform_changes: number; punctuality: number | null; by_weekday: WeekdayDelay[]; by_hour: HourDelay[]; worst_days: DayDelay[] }
+
+export type Settings = { primary_commute: number | null; tray_format: string; journeys_per_commute: number }
+
+/**
+ * The HAFAS profiles compiled into the app.
+ */
+export type ProfileId = "Db" | "Kvb"
+
+export type WeekdayDelay = { weekday: Weekday; legs: number; average_delay_minutes: number }
+
+/**
+ * A range of hours of the day, `from` inclusive and `until` exclusive.
+ * 
+ * Wraps around midnight if `from` is after `until`, e.g. from 22 until 6.
+ */
+export type ActiveHours = { from: number; until: number }
+
+/**
+ * Sent whenever a refresh changed the tracked journey of a commute.
+ */
+export type JourneyUpdate = { commute_id: number; journey: Journey; changes: JourneyChange[] }
+
+export type LaterJourneys = { commute_id: number; later_ref: string }
+
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun"
+
+export type JourneyChange = { line: string | null; kind: ChangeKind }
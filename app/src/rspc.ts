@@ -1,8 +1,9 @@
 import { createClient } from "@rspc/client";
 import { TauriTransport } from "@rspc/tauri";
-import type { Procedures } from "./bindings"; // These were the bindings exported from your Rust code!
+import type { AppError, Procedures } from "./bindings"; // These were the bindings exported from your Rust code!
 import { QueryClient } from "@tanstack/react-query";
 import { createReactQueryHooks } from "@rspc/react-query";
+import type { RSPCError } from "@rspc/client";
 
 
 export const client = createClient<Procedures>({
@@ -10,4 +11,13 @@ export const client = createClient<Procedures>({
 });
 
 export const queryClient = new QueryClient();
-export const rspc = createReactQueryHooks<Procedures>();
\ No newline at end of file
+export const rspc = createReactQueryHooks<Procedures>();
+
+// Errors of the backend carry a serialized `AppError` as their message.
+export function parseAppError(error: RSPCError): AppError {
+    try {
+        return JSON.parse(error.message) as AppError;
+    } catch {
+        return { type: "Provider", message: error.message };
+    }
+}
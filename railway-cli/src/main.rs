@@ -0,0 +1,291 @@
+mod output;
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, Subcommand};
+use hafas::client::HafasClient;
+use rcore::{
+    HyperRustlsRequesterBuilder, Journey, JourneysOptions, LocationsOptions, Place, Provider,
+    RecordingRequesterBuilder, RefreshJourneyOptions, ReplayRequester, Requester, RequesterBuilder,
+    Station,
+};
+use serde::Deserialize;
+
+use output::Format;
+
+/// Query public transport providers.
+#[derive(Debug, Parser)]
+#[command(name = "railway", version)]
+struct Cli {
+    /// The HAFAS profile to query.
+    #[arg(short, long, value_parser = PossibleValuesParser::new(hafas::profile::names()))]
+    profile: String,
+    /// How to print results.
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Store the responses of the provider in this directory.
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    /// Answer requests with the responses stored in this directory by `--record` instead of querying the provider.
+    #[arg(long)]
+    replay: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Search stations, addresses and points of interest.
+    Locations {
+        query: String,
+        #[arg(short, long, default_value_t = 10)]
+        results: u64,
+    },
+    /// Search journeys between two stations, given by their ids.
+    Journeys {
+        from: String,
+        to: String,
+        /// Depart at or after this local time, e.g. `2024-09-01T10:00`. Defaults to now.
+        #[arg(short, long, value_parser = parse_time, conflicts_with = "arrival")]
+        departure: Option<NaiveDateTime>,
+        /// Arrive at or before this local time.
+        #[arg(short, long, value_parser = parse_time)]
+        arrival: Option<NaiveDateTime>,
+        #[arg(short, long, default_value_t = 5)]
+        results: u64,
+    },
+    /// Refresh the real-time data of journeys printed by `journeys --format json`.
+    Refresh {
+        /// The file to read the journeys from, `-` for standard input.
+        file: PathBuf,
+    },
+}
+
+/// The input of [`Command::Refresh`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Journeys {
+    One(Box<Journey>),
+    Many(Vec<Journey>),
+}
+
+fn parse_time(s: &str) -> Result<NaiveDateTime, chrono::ParseError> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+}
+
+fn localize(time: Option<NaiveDateTime>, timezone: Tz) -> Option<DateTime<Tz>> {
+    timezone.from_local_datetime(&time?).earliest()
+}
+
+fn station(id: String) -> Place {
+    Place::Station(Station {
+        id,
+        ..Default::default()
+    })
+}
+
+async fn run<R: Requester>(
+    client: HafasClient<R>,
+    timezone: Tz,
+    format: Format,
+    command: Command,
+) -> Result<String, Box<dyn Error>>
+where
+    R::Error: 'static,
+{
+    match command {
+        Command::Locations { query, results } => {
+            let places = client
+                .locations(LocationsOptions {
+                    query,
+                    results,
+                    ..Default::default()
+                })
+                .await?;
+            Ok(output::places(format, &places)?)
+        }
+        Command::Journeys {
+            from,
+            to,
+            departure,
+            arrival,
+            results,
+        } => {
+            let departure = localize(departure, timezone).or_else(|| {
+                arrival
+                    .is_none()
+                    .then(|| chrono::Utc::now().with_timezone(&timezone))
+            });
+            let mut response = client
+                .journeys(
+                    station(from),
+                    station(to),
+                    JourneysOptions {
+                        departure,
+                        arrival: localize(arrival, timezone),
+                        results,
+                        polylines: format == Format::Geojson,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+            response.journeys.truncate(results as usize);
+            Ok(output::journeys(format, &response.journeys)?)
+        }
+        Command::Refresh { file } => {
+            let input = if file.as_os_str() == "-" {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                std::fs::read_to_string(file)?
+            };
+            let journeys = match serde_json::from_str(&input)? {
+                Journeys::One(journey) => vec![*journey],
+                Journeys::Many(journeys) => journeys,
+            };
+            let mut refreshed = Vec::with_capacity(journeys.len());
+            for journey in &journeys {
+                refreshed.push(
+                    client
+                        .refresh_journey(
+                            journey,
+                            RefreshJourneyOptions {
+                                polylines: format == Format::Geojson,
+                                ..Default::default()
+                            },
+                        )
+                        .await?,
+                );
+            }
+            Ok(output::journeys(format, &refreshed)?)
+        }
+    }
+}
+
+async fn run_with<RB: RequesterBuilder>(cli: Cli, requester: RB) -> Result<String, Box<dyn Error>>
+where
+    <RB::Requester as Requester>::Error: 'static,
+{
+    let profile =
+        hafas::profile::by_name(&cli.profile).expect("profile names are validated by clap");
+    let timezone = profile.timezone();
+    run(
+        HafasClient::new(profile, requester),
+        timezone,
+        cli.format,
+        cli.command,
+    )
+    .await
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+    let result = match (cli.replay.take(), cli.record.take()) {
+        (Some(dir), _) => run_with(cli, ReplayRequester::new(dir)).await,
+        (None, Some(dir)) => {
+            run_with(
+                cli,
+                RecordingRequesterBuilder::new(HyperRustlsRequesterBuilder::default(), dir),
+            )
+            .await
+        }
+        (None, None) => run_with(cli, HyperRustlsRequesterBuilder::default()).await,
+    };
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Run the CLI with the responses recorded in `fixtures`.
+    async fn replay(args: &[&str]) -> Result<String, Box<dyn Error>> {
+        // Shared with the HAFAS crate and the other clients of the workspace.
+        let fixtures = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../railway-provider-hafas/fixtures/db"
+        );
+        let cli = Cli::try_parse_from(["railway", "--profile", "db"].iter().chain(args))?;
+        run_with(cli, ReplayRequester::new(fixtures)).await
+    }
+
+    #[tokio::test]
+    async fn replays_locations() {
+        let table = replay(&["locations", "Köln Hbf"]).await.unwrap();
+        assert_eq!(
+            table
+                .lines()
+                .map(|l| l.split("  ").next().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["ID", "8000207", "8003368"]
+        );
+
+        let geojson: serde_json::Value = serde_json::from_str(
+            &replay(&["--format", "geojson", "locations", "Köln Hbf"])
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(geojson["features"][1]["properties"]["id"], "8003368");
+
+        // Only the query "Köln Hbf" was recorded.
+        assert!(replay(&["locations", "Köln"]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn replays_journeys() {
+        let journeys = ["journeys", "8000207", "8000044", "-d", "2024-09-01T10:00"];
+        let table = replay(&journeys).await.unwrap();
+        assert_eq!(table.lines().count(), 2);
+        assert!(table.lines().nth(1).unwrap().contains("RE 5"));
+
+        let json = replay(&[&["--format", "json"], &journeys[..]].concat())
+            .await
+            .unwrap();
+        let journeys: Vec<Journey> = serde_json::from_str(&json).unwrap();
+        assert_eq!(journeys.len(), 1);
+        assert_eq!(journeys[0].legs[0].departure_platform.as_deref(), Some("6"));
+    }
+
+    #[tokio::test]
+    async fn replays_refresh() {
+        let json = replay(&[
+            "--format",
+            "json",
+            "journeys",
+            "8000207",
+            "8000044",
+            "-d",
+            "2024-09-01T10:00",
+        ])
+        .await
+        .unwrap();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &json).unwrap();
+
+        let refreshed = replay(&["--format", "json", "refresh", file.path().to_str().unwrap()])
+            .await
+            .unwrap();
+        let journeys: Vec<Journey> = serde_json::from_str(&json).unwrap();
+        let refreshed: Vec<Journey> = serde_json::from_str(&refreshed).unwrap();
+        assert_eq!(refreshed.len(), 1);
+        assert_eq!(refreshed[0].id, journeys[0].id);
+        // The recorded refresh has a later arrival.
+        assert!(refreshed[0].legs[0].arrival > journeys[0].legs[0].arrival);
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use geojson::FeatureCollection;
+use rcore::{Journey, Leg, Location, Place};
+use serde_json::{json, Value};
+
+/// How to print results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A human-readable table.
+    Table,
+    /// The `railway-core` types as JSON.
+    Json,
+    /// A GeoJSON `FeatureCollection`.
+    Geojson,
+}
+
+fn name(place: &Place) -> &str {
+    match place {
+        Place::Station(station) => station.name.as_deref().unwrap_or(&station.id),
+        Place::Location(Location::Address { address, .. }) => address,
+        Place::Location(Location::Point { id, name, .. }) => {
+            name.as_deref().or(id.as_deref()).unwrap_or_default()
+        }
+    }
+}
+
+fn id(place: &Place) -> Option<&str> {
+    match place {
+        Place::Station(station) => Some(&station.id),
+        Place::Location(Location::Address { .. }) => None,
+        Place::Location(Location::Point { id, .. }) => id.as_deref(),
+    }
+}
+
+/// The latitude and longitude of a place.
+fn coordinates(place: &Place) -> Option<(f32, f32)> {
+    let location = match place {
+        Place::Station(station) => station.location.as_ref()?,
+        Place::Location(location) => location,
+    };
+    match location {
+        Location::Address {
+            latitude,
+            longitude,
+            ..
+        }
+        | Location::Point {
+            latitude,
+            longitude,
+            ..
+        } => Some((*latitude, *longitude)),
+    }
+}
+
+/// A GeoJSON position, which is longitude first.
+fn position(place: &Place) -> Option<Value> {
+    coordinates(place).map(|(latitude, longitude)| json!([longitude, latitude]))
+}
+
+/// The real-time time with the delay, or the planned time.
+fn time(time: Option<DateTime<Tz>>, planned: Option<DateTime<Tz>>) -> String {
+    match (time, planned) {
+        (Some(time), Some(planned)) if time != planned => {
+            format!(
+                "{} ({:+})",
+                time.format("%H:%M"),
+                (time - planned).num_minutes()
+            )
+        }
+        (time, planned) => time
+            .or(planned)
+            .map(|t| t.format("%H:%M").to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn line(leg: &Leg) -> String {
+    if leg.walking || leg.transfer {
+        return "walk".to_string();
+    }
+    let name = leg
+        .line
+        .as_ref()
+        .and_then(|l| l.name.clone())
+        .unwrap_or_default();
+    if leg.cancelled {
+        format!("{} (cancelled)", name)
+    } else {
+        name
+    }
+}
+
+/// Align the cells in columns, separated by two spaces.
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+    std::iter::once(&header)
+        .chain(rows)
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format the places returned by [`Provider::locations`](rcore::Provider::locations).
+pub fn places(format: Format, places: &[Place]) -> Result<String, serde_json::Error> {
+    match format {
+        Format::Table => Ok(table(
+            &["ID", "Name", "Latitude", "Longitude"],
+            &places
+                .iter()
+                .map(|place| {
+                    let coordinates = coordinates(place);
+                    vec![
+                        id(place).unwrap_or_default().to_string(),
+                        name(place).to_string(),
+                        coordinates.map(|c| c.0.to_string()).unwrap_or_default(),
+                        coordinates.map(|c| c.1.to_string()).unwrap_or_default(),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        )),
+        Format::Json => serde_json::to_string_pretty(places),
+        Format::Geojson => serde_json::to_string_pretty(&json!({
+            "type": "FeatureCollection",
+            "features": places
+                .iter()
+                .filter_map(|place| {
+                    Some(json!({
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": position(place)? },
+                        "properties": { "id": id(place), "name": name(place) },
+                    }))
+                })
+                .collect::<Vec<_>>(),
+        })),
+    }
+}
+
+/// Format journeys, one table of legs per journey.
+///
+/// In GeoJSON, the features of all journeys are combined, see [`rcore::journey_to_geojson`].
+pub fn journeys(format: Format, journeys: &[Journey]) -> Result<String, serde_json::Error> {
+    match format {
+        Format::Table => Ok(journeys
+            .iter()
+            .map(|journey| {
+                table(
+                    &["Departure", "Arrival", "Line", "From", "Platform", "To"],
+                    &journey
+                        .legs
+                        .iter()
+                        .map(|leg| {
+                            vec![
+                                time(leg.departure, leg.planned_departure),
+                                time(leg.arrival, leg.planned_arrival),
+                                line(leg),
+                                name(&leg.origin).to_string(),
+                                leg.departure_platform
+                                    .clone()
+                                    .or_else(|| leg.planned_departure_platform.clone())
+                                    .unwrap_or_default(),
+                                name(&leg.destination).to_string(),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")),
+        Format::Json => serde_json::to_string_pretty(journeys),
+        Format::Geojson => serde_json::to_string_pretty(&FeatureCollection {
+            features: journeys
+                .iter()
+                .flat_map(|journey| rcore::journey_to_geojson(journey).features)
+                .collect(),
+            bbox: None,
+            foreign_members: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aligns_tables() {
+        assert_eq!(
+            table(
+                &["ID", "Name"],
+                &[
+                    vec!["1".to_string(), "Köln Hbf".to_string()],
+                    vec!["12345".to_string(), "".to_string()],
+                ]
+            ),
+            "ID     Name\n1      Köln Hbf\n12345"
+        );
+    }
+}
@@ -1,18 +1,28 @@
 use chrono::{DateTime, Duration};
 use chrono_tz::Tz;
 
-use crate::{Accessibility, Age, Journey, LoyaltyCard, Place, ProductsSelection, TariffClass};
+#[cfg(feature = "polylines")]
+use crate::PolylineFormat;
+use crate::{
+    Accessibility, Age, Journey, LoyaltyCard, Place, ProductsSelection, TariffClass, WalkingSpeed,
+};
 
 #[derive(Debug, Clone)]
 /// The options for [`Provider::journeys`](crate::Provider::journeys)
 ///
 /// A provider can also ignore some of the options if this is not supported by the API.
 pub struct JourneysOptions {
-    /// Intermediate place to route with.
-    pub via: Vec<Place>,
+    /// Intermediate places to route with.
+    pub via: Vec<Via>,
+    /// Places the journey must not pass.
+    pub avoid: Vec<Place>,
     /// Specify to route earlier than, from [`JourneysResponse::earlier_ref`].
+    ///
+    /// Mutually exclusive with [`JourneysOptions::later_than`], see also [`JourneysPager`](crate::JourneysPager).
     pub earlier_than: Option<String>,
-    /// Specify to route earlierthan , from [`JourneysResponse::later_ref`].
+    /// Specify to route later than, from [`JourneysResponse::later_ref`].
+    ///
+    /// Mutually exclusive with [`JourneysOptions::earlier_than`], see also [`JourneysPager`](crate::JourneysPager).
     pub later_than: Option<String>,
     /// How many results to include.
     pub results: u64,
@@ -21,6 +31,9 @@ pub struct JourneysOptions {
     /// Whether to include polylines.
     #[cfg(feature = "polylines")]
     pub polylines: bool,
+    /// How the polylines are shaped.
+    #[cfg(feature = "polylines")]
+    pub polyline_format: PolylineFormat,
     /// Route in a bike-friendly way.
     pub bike_friendly: bool,
     /// Include tickets.
@@ -29,6 +42,14 @@ pub struct JourneysOptions {
     pub start_with_walking: bool,
     /// How accessible the journey must be.
     pub accessibility: Accessibility,
+    /// How fast the passenger walks.
+    pub walking_speed: WalkingSpeed,
+    /// How far the passenger is willing to walk, in meters.
+    pub max_walking_distance: Option<u64>,
+    /// Avoid transfers using stairs.
+    pub avoid_stairs: bool,
+    /// Avoid transfers using escalators.
+    pub avoid_escalators: bool,
     /// How often it is allowed to transfer.
     pub transfers: TransferOptions,
     /// How long must the transfers be.
@@ -49,6 +70,27 @@ pub struct JourneysOptions {
     pub passenger_age: Option<Age>,
 }
 
+/// An intermediate place to route with, see [`JourneysOptions::via`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Via {
+    /// The place to route with.
+    pub place: Place,
+    /// How long the journey must at least stay at the place.
+    pub min_dwell_time: Option<Duration>,
+    /// Whether the journey may also just pass through the place without stopping there.
+    pub pass_through: bool,
+}
+
+impl From<Place> for Via {
+    fn from(place: Place) -> Self {
+        Self {
+            place,
+            min_dwell_time: None,
+            pass_through: false,
+        }
+    }
+}
+
 /// How often is a journey allowed to transfer.
 #[derive(Debug, Clone, Default)]
 pub enum TransferOptions {
@@ -63,16 +105,23 @@ impl Default for JourneysOptions {
     fn default() -> Self {
         Self {
             via: Default::default(),
+            avoid: Default::default(),
             earlier_than: Default::default(),
             later_than: Default::default(),
             results: 5,
             stopovers: Default::default(),
             #[cfg(feature = "polylines")]
             polylines: Default::default(),
+            #[cfg(feature = "polylines")]
+            polyline_format: Default::default(),
             bike_friendly: Default::default(),
             tickets: true,
             start_with_walking: true,
             accessibility: Default::default(),
+            walking_speed: Default::default(),
+            max_walking_distance: Default::default(),
+            avoid_stairs: Default::default(),
+            avoid_escalators: Default::default(),
             transfers: TransferOptions::default(),
             transfer_time: Duration::zero(),
             arrival: Default::default(),
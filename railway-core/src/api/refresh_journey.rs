@@ -1,3 +1,5 @@
+#[cfg(feature = "polylines")]
+use crate::PolylineFormat;
 use crate::{Journey, TariffClass};
 
 #[derive(Debug, Default)]
@@ -10,6 +12,9 @@ pub struct RefreshJourneyOptions {
     #[cfg(feature = "polylines")]
     /// Whether to include polylines.
     pub polylines: bool,
+    #[cfg(feature = "polylines")]
+    /// How the polylines are shaped.
+    pub polyline_format: PolylineFormat,
     /// Include tickets.
     pub tickets: bool,
     /// What class to use.
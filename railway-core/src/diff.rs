@@ -0,0 +1,292 @@
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Journey, Leg, Remark, TransferStatus, TransferTimes};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+/// What changed between two versions of the same [`Journey`], e.g. before and after [`Provider::refresh_journey`](crate::Provider::refresh_journey).
+///
+/// Legs are matched using [`Leg::id`].
+pub struct JourneyDiff {
+    /// The changes of legs contained in both journeys. Legs without changes are omitted.
+    pub legs: Vec<LegDiff>,
+    /// Legs only contained in the new journey.
+    pub added_legs: Vec<Leg>,
+    /// Legs only contained in the old journey.
+    pub removed_legs: Vec<Leg>,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+/// The changes of a single [`Leg`].
+pub struct LegDiff {
+    /// The [`Leg::id`] of the changed leg.
+    pub leg_id: String,
+    /// What changed.
+    pub changes: Vec<LegChange>,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+/// A single change of a [`Leg`].
+pub enum LegChange {
+    /// The departure delay changed.
+    DepartureDelay {
+        /// The previous delay, if known.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
+        previous: Option<Duration>,
+        /// The current delay, if known.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
+        current: Option<Duration>,
+    },
+    /// The arrival delay changed.
+    ArrivalDelay {
+        /// The previous delay, if known.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
+        previous: Option<Duration>,
+        /// The current delay, if known.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
+        current: Option<Duration>,
+    },
+    /// The real-time departure platform changed.
+    DeparturePlatform {
+        /// The previous platform.
+        previous: Option<String>,
+        /// The current platform.
+        current: Option<String>,
+    },
+    /// The real-time arrival platform changed.
+    ArrivalPlatform {
+        /// The previous platform.
+        previous: Option<String>,
+        /// The current platform.
+        current: Option<String>,
+    },
+    /// The leg got cancelled.
+    Cancelled,
+    /// The leg is no longer cancelled.
+    Reinstated,
+    /// A remark was added to the leg.
+    RemarkAdded(Remark),
+    /// A remark was removed from the leg.
+    RemarkRemoved(Remark),
+    /// The transfer to this leg is no longer possible.
+    ConnectionLost {
+        /// The time left to transfer from the previous leg, if known.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
+        transfer_time: Option<Duration>,
+    },
+}
+
+impl JourneyDiff {
+    /// Compare two versions of the same journey.
+    ///
+    /// A connection counts as lost if the transfer to a leg was possible in the old journey, but is [broken](TransferStatus::Broken) in the new one.
+    pub fn new(old: &Journey, new: &Journey, transfer_times: &TransferTimes) -> Self {
+        let old_transfers = old.transfers(transfer_times);
+        let new_transfers = new.transfers(transfer_times);
+
+        let mut diff = JourneyDiff::default();
+        for (new_index, new_leg) in new.legs.iter().enumerate() {
+            let id = new_leg.id();
+            let Some((old_index, old_leg)) =
+                old.legs.iter().enumerate().find(|(_, l)| l.id() == id)
+            else {
+                diff.added_legs.push(new_leg.clone());
+                continue;
+            };
+
+            let mut changes = leg_changes(old_leg, new_leg);
+            let old_transfer = old_transfers.iter().find(|t| t.to_leg == old_index);
+            let new_transfer = new_transfers.iter().find(|t| t.to_leg == new_index);
+            if let (Some(old_transfer), Some(new_transfer)) = (old_transfer, new_transfer) {
+                if old_transfer.status != TransferStatus::Broken
+                    && new_transfer.status == TransferStatus::Broken
+                {
+                    changes.push(LegChange::ConnectionLost {
+                        transfer_time: new_transfer.available,
+                    });
+                }
+            }
+            if !changes.is_empty() {
+                diff.legs.push(LegDiff {
+                    leg_id: id,
+                    changes,
+                });
+            }
+        }
+        diff.removed_legs = old
+            .legs
+            .iter()
+            .filter(|o| !new.legs.iter().any(|n| n.id() == o.id()))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.legs.is_empty() && self.added_legs.is_empty() && self.removed_legs.is_empty()
+    }
+}
+
+fn delay(time: Option<DateTime<Tz>>, planned: Option<DateTime<Tz>>) -> Option<Duration> {
+    Some(time? - planned?)
+}
+
+fn leg_changes(old: &Leg, new: &Leg) -> Vec<LegChange> {
+    let mut changes = vec![];
+
+    let (previous, current) = (
+        delay(old.departure, old.planned_departure),
+        delay(new.departure, new.planned_departure),
+    );
+    if previous != current {
+        changes.push(LegChange::DepartureDelay { previous, current });
+    }
+    let (previous, current) = (
+        delay(old.arrival, old.planned_arrival),
+        delay(new.arrival, new.planned_arrival),
+    );
+    if previous != current {
+        changes.push(LegChange::ArrivalDelay { previous, current });
+    }
+    if old.departure_platform != new.departure_platform {
+        changes.push(LegChange::DeparturePlatform {
+            previous: old.departure_platform.clone(),
+            current: new.departure_platform.clone(),
+        });
+    }
+    if old.arrival_platform != new.arrival_platform {
+        changes.push(LegChange::ArrivalPlatform {
+            previous: old.arrival_platform.clone(),
+            current: new.arrival_platform.clone(),
+        });
+    }
+    match (old.cancelled, new.cancelled) {
+        (false, true) => changes.push(LegChange::Cancelled),
+        (true, false) => changes.push(LegChange::Reinstated),
+        _ => {}
+    }
+    changes.extend(
+        new.remarks
+            .iter()
+            .filter(|r| !old.remarks.contains(r))
+            .cloned()
+            .map(LegChange::RemarkAdded),
+    );
+    changes.extend(
+        old.remarks
+            .iter()
+            .filter(|r| !new.remarks.contains(r))
+            .cloned()
+            .map(LegChange::RemarkRemoved),
+    );
+
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{journey, time};
+    use crate::{RemarkAssociation, RemarkType};
+
+    fn leg(trip_id: &str, departure: (u32, u32), arrival: (u32, u32)) -> Leg {
+        Leg {
+            trip_id: Some(trip_id.to_string()),
+            arrival_platform: Some("1".to_string()),
+            planned_arrival_platform: Some("1".to_string()),
+            departure_platform: Some("2".to_string()),
+            planned_departure_platform: Some("2".to_string()),
+            ..crate::test::leg("a", "b", departure, arrival)
+        }
+    }
+
+    #[test]
+    fn unchanged_journey_has_empty_diff() {
+        let j = journey(
+            "journey",
+            vec![leg("1", (10, 0), (10, 30)), leg("2", (10, 40), (11, 0))],
+        );
+        assert!(JourneyDiff::new(&j, &j, &Duration::minutes(5).into()).is_empty());
+    }
+
+    #[test]
+    fn detects_leg_changes() {
+        let old = journey(
+            "journey",
+            vec![leg("1", (10, 0), (10, 30)), leg("2", (10, 40), (11, 0))],
+        );
+        let mut new = old.clone();
+        new.legs[0].departure = Some(time(10, 3));
+        new.legs[0].arrival = Some(time(10, 38));
+        new.legs[1].departure_platform = Some("5".to_string());
+        new.legs[1].cancelled = true;
+        let remark = Remark {
+            code: "text.realtime".to_string(),
+            text: "Delay due to a technical issue".to_string(),
+            r#type: RemarkType::Status,
+            association: RemarkAssociation::None,
+            summary: None,
+            trip_id: None,
+        };
+        new.legs[1].remarks.push(remark.clone());
+
+        let diff = JourneyDiff::new(&old, &new, &Duration::minutes(5).into());
+        assert_eq!(
+            diff.legs,
+            vec![
+                LegDiff {
+                    leg_id: old.legs[0].id(),
+                    changes: vec![
+                        LegChange::DepartureDelay {
+                            previous: Some(Duration::zero()),
+                            current: Some(Duration::minutes(3)),
+                        },
+                        LegChange::ArrivalDelay {
+                            previous: Some(Duration::zero()),
+                            current: Some(Duration::minutes(8)),
+                        },
+                    ],
+                },
+                LegDiff {
+                    leg_id: old.legs[1].id(),
+                    changes: vec![
+                        LegChange::DeparturePlatform {
+                            previous: Some("2".to_string()),
+                            current: Some("5".to_string()),
+                        },
+                        LegChange::Cancelled,
+                        LegChange::RemarkAdded(remark),
+                        LegChange::ConnectionLost {
+                            transfer_time: Some(Duration::minutes(2)),
+                        },
+                    ],
+                },
+            ]
+        );
+        assert!(diff.added_legs.is_empty());
+        assert!(diff.removed_legs.is_empty());
+    }
+
+    #[test]
+    fn detects_replaced_legs() {
+        let old = journey(
+            "journey",
+            vec![leg("1", (10, 0), (10, 30)), leg("2", (10, 40), (11, 0))],
+        );
+        let new = journey(
+            "journey",
+            vec![leg("1", (10, 0), (10, 30)), leg("3", (10, 50), (11, 10))],
+        );
+
+        let diff = JourneyDiff::new(&old, &new, &Duration::minutes(5).into());
+        assert!(diff.legs.is_empty());
+        assert_eq!(diff.added_legs, vec![new.legs[1].clone()]);
+        assert_eq!(diff.removed_legs, vec![old.legs[1].clone()]);
+    }
+}
@@ -20,3 +20,55 @@ impl<R: Display, P: Display> Display for Error<R, P> {
 }
 
 impl<R: StdError, P: StdError> StdError for Error<R, P> {}
+
+/// An error parsing the response of an API.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The response is not as expected.
+    InvalidData {
+        info: String,
+    },
+    Chrono {
+        source: chrono::ParseError,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::InvalidData { info } => write!(f, "{}", info),
+            Self::Chrono { source } => write!(f, "{}", source),
+        }
+    }
+}
+
+impl StdError for ParseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::InvalidData { .. } => None,
+            Self::Chrono { source } => Some(source),
+        }
+    }
+}
+
+impl From<String> for ParseError {
+    fn from(info: String) -> ParseError {
+        ParseError::InvalidData { info }
+    }
+}
+
+impl From<&str> for ParseError {
+    fn from(info: &str) -> ParseError {
+        ParseError::InvalidData {
+            info: info.to_string(),
+        }
+    }
+}
+
+impl From<chrono::ParseError> for ParseError {
+    fn from(source: chrono::ParseError) -> ParseError {
+        ParseError::Chrono { source }
+    }
+}
+
+pub type ParseResult<T> = std::result::Result<T, ParseError>;
@@ -0,0 +1,842 @@
+//! Conversions between the core types and the [Friendly Public Transport Format](https://github.com/public-transport/friendly-public-transport-format) (FPTF), shaped like the output of [hafas-client](https://github.com/public-transport/hafas-client).
+//!
+//! Times are ISO 8601 with an offset, delays and durations are seconds. Information FPTF has no field for is stored in additional fields, so converting to FPTF and back is lossless:
+//!
+//! - `subMode`: The [`Mode`] of a [`Line`] or [`Product`], e.g. `high-speed-train`.
+//! - `productDetails`: The [`Product`]s of a [`Station`] or [`Line`], next to the hafas-client-style `products` and `product`.
+//! - `timezone`: The timezone of the times of a [`Leg`] or [`Stop`]. All times of a leg or stop are expected to share one timezone. Without it, times are imported in UTC.
+//! - `accessibility`: The [`StationAccessibility`] of a [`Station`] or [`Stop`].
+//! - `association`: The [`RemarkAssociation`] of a [`Remark`].
+//! - Railways passed by a leg are part of its `stopovers`, with type `railway` and the railway as `place`.
+
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    Frequency, IntermediateLocation, Journey, Leg, Line, LoadFactor, Location, Mode, Operator,
+    Place, Price, Product, Remark, RemarkAssociation, RemarkType, Station, StationAccessibility,
+    Stop,
+};
+
+/// An error importing FPTF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FptfError {
+    /// A required field is missing.
+    Missing(&'static str),
+    /// A field has an unexpected type or value.
+    Invalid(&'static str),
+}
+
+impl Display for FptfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Missing(field) => write!(f, "missing FPTF field: {}", field),
+            Self::Invalid(field) => write!(f, "invalid FPTF field: {}", field),
+        }
+    }
+}
+
+impl StdError for FptfError {}
+
+/// Conversion from and to FPTF JSON.
+pub trait Fptf: Sized {
+    /// Convert to FPTF.
+    fn to_fptf(&self) -> Value;
+    /// Convert from FPTF, e.g. the output of hafas-client.
+    fn from_fptf(value: &Value) -> Result<Self, FptfError>;
+}
+
+type Object = Map<String, Value>;
+
+fn object<'a>(value: &'a Value, name: &'static str) -> Result<&'a Object, FptfError> {
+    value.as_object().ok_or(FptfError::Invalid(name))
+}
+
+/// A field of the object, `None` if missing or `null`.
+fn field<'a>(object: &'a Object, name: &str) -> Option<&'a Value> {
+    object.get(name).filter(|v| !v.is_null())
+}
+
+fn string(object: &Object, name: &'static str) -> Result<Option<String>, FptfError> {
+    field(object, name)
+        .map(|v| {
+            v.as_str()
+                .map(str::to_owned)
+                .ok_or(FptfError::Invalid(name))
+        })
+        .transpose()
+}
+
+fn required_string(object: &Object, name: &'static str) -> Result<String, FptfError> {
+    string(object, name)?.ok_or(FptfError::Missing(name))
+}
+
+fn boolean(object: &Object, name: &'static str) -> Result<Option<bool>, FptfError> {
+    field(object, name)
+        .map(|v| v.as_bool().ok_or(FptfError::Invalid(name)))
+        .transpose()
+}
+
+fn unsigned(object: &Object, name: &'static str) -> Result<Option<u64>, FptfError> {
+    field(object, name)
+        .map(|v| v.as_u64().ok_or(FptfError::Invalid(name)))
+        .transpose()
+}
+
+fn seconds(object: &Object, name: &'static str) -> Result<Option<Duration>, FptfError> {
+    field(object, name)
+        .map(|v| {
+            v.as_i64()
+                .map(Duration::seconds)
+                .ok_or(FptfError::Invalid(name))
+        })
+        .transpose()
+}
+
+fn list<T>(
+    object: &Object,
+    name: &'static str,
+    parse: impl Fn(&Value) -> Result<T, FptfError>,
+) -> Result<Vec<T>, FptfError> {
+    match field(object, name) {
+        Some(v) => v
+            .as_array()
+            .ok_or(FptfError::Invalid(name))?
+            .iter()
+            .map(parse)
+            .collect(),
+        None => Ok(vec![]),
+    }
+}
+
+/// The shortest decimal representing the coordinate, instead of the exact value of the `f32`.
+fn coordinate(c: f32) -> Value {
+    json!(c.to_string().parse::<f64>().unwrap_or(f64::from(c)))
+}
+
+fn required_coordinate(object: &Object, name: &'static str) -> Result<f32, FptfError> {
+    field(object, name)
+        .ok_or(FptfError::Missing(name))?
+        .as_f64()
+        .map(|c| c as f32)
+        .ok_or(FptfError::Invalid(name))
+}
+
+/// The timezone of the first given time.
+fn timezone(times: &[&Option<DateTime<Tz>>]) -> Value {
+    times
+        .iter()
+        .find_map(|t| t.as_ref())
+        .map(|t| json!(t.timezone().name()))
+        .unwrap_or(Value::Null)
+}
+
+fn parse_timezone(object: &Object) -> Result<Tz, FptfError> {
+    string(object, "timezone")?
+        .map(|tz| tz.parse().map_err(|_| FptfError::Invalid("timezone")))
+        .unwrap_or(Ok(Tz::UTC))
+}
+
+fn time(time: &Option<DateTime<Tz>>) -> Value {
+    json!(time.map(|t| t.to_rfc3339()))
+}
+
+fn parse_time(
+    object: &Object,
+    name: &'static str,
+    timezone: Tz,
+) -> Result<Option<DateTime<Tz>>, FptfError> {
+    string(object, name)?
+        .map(|t| {
+            DateTime::parse_from_rfc3339(&t)
+                .map(|t| t.with_timezone(&timezone))
+                .map_err(|_| FptfError::Invalid(name))
+        })
+        .transpose()
+}
+
+/// The delay in seconds, derived from the real-time and scheduled time.
+fn delay(time: &Option<DateTime<Tz>>, planned: &Option<DateTime<Tz>>) -> Value {
+    match (time, planned) {
+        (Some(time), Some(planned)) => json!((*time - *planned).num_seconds()),
+        _ => Value::Null,
+    }
+}
+
+fn fptf_mode(mode: &Mode) -> Value {
+    match mode {
+        Mode::HighSpeedTrain
+        | Mode::RegionalTrain
+        | Mode::SuburbanTrain
+        | Mode::Subway
+        | Mode::Tram => json!("train"),
+        Mode::Bus => json!("bus"),
+        Mode::Ferry => json!("watercraft"),
+        Mode::Cablecar => json!("gondola"),
+        Mode::OnDemand => json!("taxi"),
+        Mode::Unknown => Value::Null,
+    }
+}
+
+/// The [`Mode`] from `subMode`, or the less specific FPTF `mode`.
+fn parse_mode(object: &Object) -> Result<Mode, FptfError> {
+    if let Some(sub_mode) = string(object, "subMode")? {
+        return Mode::from_id(&sub_mode).ok_or(FptfError::Invalid("subMode"));
+    }
+    Ok(match string(object, "mode")?.as_deref() {
+        Some("bus") => Mode::Bus,
+        Some("watercraft") => Mode::Ferry,
+        Some("gondola") => Mode::Cablecar,
+        Some("taxi") => Mode::OnDemand,
+        _ => Mode::Unknown,
+    })
+}
+
+fn product(product: &Product) -> Value {
+    json!({
+        "type": "product",
+        "name": product.name,
+        "short": product.short,
+        "mode": fptf_mode(&product.mode),
+        "subMode": product.mode.id(),
+    })
+}
+
+fn parse_product(value: &Value) -> Result<Product, FptfError> {
+    let object = object(value, "productDetails")?;
+    Ok(Product {
+        mode: parse_mode(object)?,
+        name: Cow::Owned(required_string(object, "name")?),
+        short: Cow::Owned(required_string(object, "short")?),
+    })
+}
+
+fn accessibility(accessibility: &Option<StationAccessibility>) -> Value {
+    match accessibility {
+        Some(a) => json!({ "stepFree": a.step_free, "elevator": a.elevator }),
+        None => Value::Null,
+    }
+}
+
+fn parse_accessibility(object: &Object) -> Result<Option<StationAccessibility>, FptfError> {
+    field(object, "accessibility")
+        .map(|v| {
+            let a = self::object(v, "accessibility")?;
+            Ok(StationAccessibility {
+                step_free: boolean(a, "stepFree")?,
+                elevator: boolean(a, "elevator")?,
+            })
+        })
+        .transpose()
+}
+
+fn remark(remark: &Remark) -> Value {
+    json!({
+        "type": match remark.r#type {
+            RemarkType::Hint => "hint",
+            RemarkType::Status => "status",
+        },
+        "code": remark.code,
+        "text": remark.text,
+        "summary": remark.summary,
+        "tripId": remark.trip_id,
+        "association": match remark.association {
+            RemarkAssociation::Bike => "bike",
+            RemarkAssociation::Accessibility => "accessibility",
+            RemarkAssociation::Ticket => "ticket",
+            RemarkAssociation::Power => "power",
+            RemarkAssociation::AirConditioning => "air-conditioning",
+            RemarkAssociation::WiFi => "wifi",
+            RemarkAssociation::OnlySecondClass => "only-second-class",
+            RemarkAssociation::Unknown => "unknown",
+            RemarkAssociation::None => "none",
+        },
+    })
+}
+
+fn parse_remark(value: &Value) -> Result<Remark, FptfError> {
+    let object = object(value, "remarks")?;
+    Ok(Remark {
+        code: string(object, "code")?.unwrap_or_default(),
+        text: string(object, "text")?.unwrap_or_default(),
+        r#type: match string(object, "type")?.as_deref() {
+            Some("status") | Some("warning") => RemarkType::Status,
+            _ => RemarkType::Hint,
+        },
+        association: match string(object, "association")?.as_deref() {
+            Some("bike") => RemarkAssociation::Bike,
+            Some("accessibility") => RemarkAssociation::Accessibility,
+            Some("ticket") => RemarkAssociation::Ticket,
+            Some("power") => RemarkAssociation::Power,
+            Some("air-conditioning") => RemarkAssociation::AirConditioning,
+            Some("wifi") => RemarkAssociation::WiFi,
+            Some("only-second-class") => RemarkAssociation::OnlySecondClass,
+            Some("none") => RemarkAssociation::None,
+            _ => RemarkAssociation::Unknown,
+        },
+        summary: string(object, "summary")?,
+        trip_id: string(object, "tripId")?,
+    })
+}
+
+fn load_factor(load_factor: &Option<LoadFactor>) -> Value {
+    match load_factor {
+        Some(LoadFactor::LowToMedium) => json!("low-to-medium"),
+        Some(LoadFactor::High) => json!("high"),
+        Some(LoadFactor::VeryHigh) => json!("very-high"),
+        Some(LoadFactor::ExceptionallyHigh) => json!("exceptionally-high"),
+        None => Value::Null,
+    }
+}
+
+fn parse_load_factor(object: &Object) -> Result<Option<LoadFactor>, FptfError> {
+    Ok(match string(object, "loadFactor")?.as_deref() {
+        Some("low-to-medium") => Some(LoadFactor::LowToMedium),
+        Some("high") => Some(LoadFactor::High),
+        Some("very-high") => Some(LoadFactor::VeryHigh),
+        Some("exceptionally-high") => Some(LoadFactor::ExceptionallyHigh),
+        Some(_) => return Err(FptfError::Invalid("loadFactor")),
+        None => None,
+    })
+}
+
+fn cycle(frequency: &Option<Frequency>) -> Value {
+    match frequency {
+        Some(f) => json!({
+            "min": f.minimum.map(|d| d.num_seconds()),
+            "max": f.maximum.map(|d| d.num_seconds()),
+            "nr": f.iterations,
+        }),
+        None => Value::Null,
+    }
+}
+
+fn parse_cycle(object: &Object) -> Result<Option<Frequency>, FptfError> {
+    field(object, "cycle")
+        .map(|v| {
+            let cycle = self::object(v, "cycle")?;
+            Ok(Frequency {
+                minimum: seconds(cycle, "min")?,
+                maximum: seconds(cycle, "max")?,
+                iterations: unsigned(cycle, "nr")?,
+            })
+        })
+        .transpose()
+}
+
+impl Fptf for Location {
+    fn to_fptf(&self) -> Value {
+        match self {
+            Location::Address {
+                address,
+                latitude,
+                longitude,
+            } => json!({
+                "type": "location",
+                "address": address,
+                "latitude": coordinate(*latitude),
+                "longitude": coordinate(*longitude),
+            }),
+            Location::Point {
+                id,
+                name,
+                poi,
+                latitude,
+                longitude,
+            } => json!({
+                "type": "location",
+                "id": id,
+                "name": name,
+                "poi": poi,
+                "latitude": coordinate(*latitude),
+                "longitude": coordinate(*longitude),
+            }),
+        }
+    }
+
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "location")?;
+        let latitude = required_coordinate(object, "latitude")?;
+        let longitude = required_coordinate(object, "longitude")?;
+        Ok(match string(object, "address")? {
+            Some(address) => Location::Address {
+                address,
+                latitude,
+                longitude,
+            },
+            None => Location::Point {
+                id: string(object, "id")?,
+                name: string(object, "name")?,
+                poi: boolean(object, "poi")?,
+                latitude,
+                longitude,
+            },
+        })
+    }
+}
+
+impl Fptf for Station {
+    fn to_fptf(&self) -> Value {
+        let products: Object = self
+            .products
+            .iter()
+            .map(|p| (p.name.to_string(), Value::Bool(true)))
+            .collect();
+        json!({
+            "type": "station",
+            "id": self.id,
+            "name": self.name,
+            "location": self.location.as_ref().map(Fptf::to_fptf),
+            "products": products,
+            "productDetails": self.products.iter().map(product).collect::<Vec<_>>(),
+            "accessibility": accessibility(&self.accessibility),
+        })
+    }
+
+    /// Also accepts the stops of hafas-client, whose products are lost without `productDetails`.
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "station")?;
+        Ok(Station {
+            id: required_string(object, "id")?,
+            name: string(object, "name")?,
+            location: field(object, "location")
+                .map(Location::from_fptf)
+                .transpose()?,
+            products: list(object, "productDetails", parse_product)?,
+            accessibility: parse_accessibility(object)?,
+        })
+    }
+}
+
+impl Fptf for Place {
+    fn to_fptf(&self) -> Value {
+        match self {
+            Place::Station(station) => station.to_fptf(),
+            Place::Location(location) => location.to_fptf(),
+        }
+    }
+
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "place")?;
+        match string(object, "type")?.as_deref() {
+            Some("station") | Some("stop") => Ok(Place::Station(Station::from_fptf(value)?)),
+            Some("location") => Ok(Place::Location(Location::from_fptf(value)?)),
+            Some(_) => Err(FptfError::Invalid("type")),
+            None => Err(FptfError::Missing("type")),
+        }
+    }
+}
+
+impl Fptf for Line {
+    fn to_fptf(&self) -> Value {
+        json!({
+            "type": "line",
+            "id": self.name.as_ref().map(|n| n.to_lowercase().replace(' ', "-")),
+            "name": self.name,
+            "fahrtNr": self.fahrt_nr,
+            "mode": fptf_mode(&self.mode),
+            "subMode": self.mode.id(),
+            "product": self.product.name,
+            "productName": self.product_name,
+            "productDetails": product(&self.product),
+            "operator": self.operator.as_ref().map(|o| json!({
+                "type": "operator",
+                "id": o.id,
+                "name": o.name,
+            })),
+        })
+    }
+
+    /// Without `productDetails`, the product is named after the hafas-client `product`.
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "line")?;
+        let mode = parse_mode(object)?;
+        let product = match field(object, "productDetails") {
+            Some(p) => parse_product(p)?,
+            None => match string(object, "product")? {
+                Some(name) => Product {
+                    mode: mode.clone(),
+                    name: Cow::Owned(name.clone()),
+                    short: Cow::Owned(name),
+                },
+                None => Product::unknown(),
+            },
+        };
+        let operator = field(object, "operator")
+            .map(|v| {
+                let operator = self::object(v, "operator")?;
+                Ok(Operator {
+                    id: required_string(operator, "id")?,
+                    name: required_string(operator, "name")?,
+                })
+            })
+            .transpose()?;
+        Ok(Line {
+            name: string(object, "name")?,
+            fahrt_nr: string(object, "fahrtNr")?,
+            mode,
+            product,
+            operator,
+            product_name: string(object, "productName")?,
+        })
+    }
+}
+
+impl Fptf for Stop {
+    fn to_fptf(&self) -> Value {
+        json!({
+            "type": "stopover",
+            "stop": self.place.to_fptf(),
+            "arrival": time(&self.arrival),
+            "plannedArrival": time(&self.planned_arrival),
+            "arrivalDelay": delay(&self.arrival, &self.planned_arrival),
+            "arrivalPlatform": self.arrival_platform,
+            "plannedArrivalPlatform": self.planned_arrival_platform,
+            "departure": time(&self.departure),
+            "plannedDeparture": time(&self.planned_departure),
+            "departureDelay": delay(&self.departure, &self.planned_departure),
+            "departurePlatform": self.departure_platform,
+            "plannedDeparturePlatform": self.planned_departure_platform,
+            "cancelled": self.cancelled,
+            "remarks": self.remarks.iter().map(remark).collect::<Vec<_>>(),
+            "accessibility": accessibility(&self.accessibility),
+            "timezone": timezone(&[
+                &self.arrival,
+                &self.planned_arrival,
+                &self.departure,
+                &self.planned_departure,
+            ]),
+        })
+    }
+
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "stopover")?;
+        let timezone = parse_timezone(object)?;
+        Ok(Stop {
+            place: Place::from_fptf(field(object, "stop").ok_or(FptfError::Missing("stop"))?)?,
+            departure: parse_time(object, "departure", timezone)?,
+            planned_departure: parse_time(object, "plannedDeparture", timezone)?,
+            arrival: parse_time(object, "arrival", timezone)?,
+            planned_arrival: parse_time(object, "plannedArrival", timezone)?,
+            arrival_platform: string(object, "arrivalPlatform")?,
+            planned_arrival_platform: string(object, "plannedArrivalPlatform")?,
+            departure_platform: string(object, "departurePlatform")?,
+            planned_departure_platform: string(object, "plannedDeparturePlatform")?,
+            cancelled: boolean(object, "cancelled")?.unwrap_or_default(),
+            remarks: list(object, "remarks", parse_remark)?,
+            accessibility: parse_accessibility(object)?,
+        })
+    }
+}
+
+fn intermediate_location(location: &IntermediateLocation) -> Value {
+    match location {
+        IntermediateLocation::Stop(stop) => stop.to_fptf(),
+        IntermediateLocation::Railway(place) => json!({
+            "type": "railway",
+            "place": place.to_fptf(),
+        }),
+    }
+}
+
+fn parse_intermediate_location(value: &Value) -> Result<IntermediateLocation, FptfError> {
+    let object = object(value, "stopovers")?;
+    if string(object, "type")?.as_deref() == Some("railway") {
+        let place = field(object, "place").ok_or(FptfError::Missing("place"))?;
+        Ok(IntermediateLocation::Railway(Place::from_fptf(place)?))
+    } else {
+        Ok(IntermediateLocation::Stop(Stop::from_fptf(value)?))
+    }
+}
+
+impl Fptf for Leg {
+    fn to_fptf(&self) -> Value {
+        #[allow(unused_mut)]
+        let mut leg = json!({
+            "origin": self.origin.to_fptf(),
+            "destination": self.destination.to_fptf(),
+            "departure": time(&self.departure),
+            "plannedDeparture": time(&self.planned_departure),
+            "departureDelay": delay(&self.departure, &self.planned_departure),
+            "departurePlatform": self.departure_platform,
+            "plannedDeparturePlatform": self.planned_departure_platform,
+            "arrival": time(&self.arrival),
+            "plannedArrival": time(&self.planned_arrival),
+            "arrivalDelay": delay(&self.arrival, &self.planned_arrival),
+            "arrivalPlatform": self.arrival_platform,
+            "plannedArrivalPlatform": self.planned_arrival_platform,
+            "reachable": self.reachable,
+            "tripId": self.trip_id,
+            "line": self.line.as_ref().map(Fptf::to_fptf),
+            "direction": self.direction,
+            "cycle": cycle(&self.frequency),
+            "cancelled": self.cancelled,
+            "stopovers": self
+                .intermediate_locations
+                .iter()
+                .map(intermediate_location)
+                .collect::<Vec<_>>(),
+            "loadFactor": load_factor(&self.load_factor),
+            "remarks": self.remarks.iter().map(remark).collect::<Vec<_>>(),
+            "walking": self.walking,
+            "transfer": self.transfer,
+            "distance": self.distance,
+            "timezone": timezone(&[
+                &self.departure,
+                &self.planned_departure,
+                &self.arrival,
+                &self.planned_arrival,
+            ]),
+        });
+        #[cfg(feature = "polylines")]
+        {
+            leg["polyline"] = self
+                .polyline
+                .as_ref()
+                .and_then(|p| serde_json::to_value(p).ok())
+                .unwrap_or(Value::Null);
+        }
+        leg
+    }
+
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "leg")?;
+        let timezone = parse_timezone(object)?;
+        let place = |name: &'static str| {
+            Place::from_fptf(field(object, name).ok_or(FptfError::Missing(name))?)
+        };
+        Ok(Leg {
+            origin: place("origin")?,
+            destination: place("destination")?,
+            departure: parse_time(object, "departure", timezone)?,
+            planned_departure: parse_time(object, "plannedDeparture", timezone)?,
+            arrival: parse_time(object, "arrival", timezone)?,
+            planned_arrival: parse_time(object, "plannedArrival", timezone)?,
+            reachable: boolean(object, "reachable")?.unwrap_or(true),
+            trip_id: string(object, "tripId")?,
+            line: field(object, "line").map(Line::from_fptf).transpose()?,
+            direction: string(object, "direction")?,
+            arrival_platform: string(object, "arrivalPlatform")?,
+            planned_arrival_platform: string(object, "plannedArrivalPlatform")?,
+            departure_platform: string(object, "departurePlatform")?,
+            planned_departure_platform: string(object, "plannedDeparturePlatform")?,
+            frequency: parse_cycle(object)?,
+            cancelled: boolean(object, "cancelled")?.unwrap_or_default(),
+            intermediate_locations: list(object, "stopovers", parse_intermediate_location)?,
+            load_factor: parse_load_factor(object)?,
+            remarks: list(object, "remarks", parse_remark)?,
+            #[cfg(feature = "polylines")]
+            polyline: field(object, "polyline")
+                .map(|p| {
+                    serde_json::from_value(p.clone()).map_err(|_| FptfError::Invalid("polyline"))
+                })
+                .transpose()?,
+            walking: boolean(object, "walking")?.unwrap_or_default(),
+            transfer: boolean(object, "transfer")?.unwrap_or_default(),
+            distance: unsigned(object, "distance")?,
+        })
+    }
+}
+
+impl Fptf for Journey {
+    fn to_fptf(&self) -> Value {
+        json!({
+            "type": "journey",
+            "id": self.id,
+            "refreshToken": self.id,
+            "legs": self.legs.iter().map(Fptf::to_fptf).collect::<Vec<_>>(),
+            "price": self.price.as_ref().map(|p| json!({
+                "amount": p.amount,
+                "currency": p.currency,
+            })),
+        })
+    }
+
+    /// The id is the `refreshToken`, as with hafas-client it is the one that can be refreshed, or the `id` otherwise.
+    fn from_fptf(value: &Value) -> Result<Self, FptfError> {
+        let object = object(value, "journey")?;
+        let price = field(object, "price")
+            .map(|v| {
+                let price = self::object(v, "price")?;
+                Ok(Price {
+                    amount: field(price, "amount")
+                        .ok_or(FptfError::Missing("amount"))?
+                        .as_f64()
+                        .ok_or(FptfError::Invalid("amount"))?,
+                    currency: required_string(price, "currency")?,
+                })
+            })
+            .transpose()?;
+        Ok(Journey {
+            id: match string(object, "refreshToken")? {
+                Some(token) => token,
+                None => string(object, "id")?.unwrap_or_default(),
+            },
+            legs: list(object, "legs", Leg::from_fptf)?,
+            price,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::time;
+
+    fn station(id: &str, name: &str) -> Place {
+        Place::Station(Station {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            location: Some(Location::Point {
+                id: Some(id.to_string()),
+                name: None,
+                poi: Some(false),
+                latitude: 50.943027,
+                longitude: 6.958729,
+            }),
+            products: vec![Product {
+                mode: Mode::HighSpeedTrain,
+                name: Cow::Borrowed("InterCityExpress"),
+                short: Cow::Borrowed("ICE"),
+            }],
+            accessibility: Some(StationAccessibility {
+                step_free: Some(true),
+                elevator: None,
+            }),
+        })
+    }
+
+    fn journey() -> Journey {
+        let remark = Remark {
+            code: "FB".to_string(),
+            text: "Bicycles allowed".to_string(),
+            r#type: RemarkType::Hint,
+            association: RemarkAssociation::Bike,
+            summary: None,
+            trip_id: None,
+        };
+        Journey {
+            id: "¶HKI¶T$A=1@O=Köln Hbf".to_string(),
+            legs: vec![Leg {
+                origin: station("8000207", "Köln Hbf"),
+                destination: Place::Location(Location::Address {
+                    address: "Kaiserstraße 1, Frankfurt".to_string(),
+                    latitude: 50.107145,
+                    longitude: 8.663789,
+                }),
+                departure: Some(time(10, 2)),
+                reachable: false,
+                trip_id: Some("1|123|0|80|1092024".to_string()),
+                line: Some(Line {
+                    name: Some("ICE 123".to_string()),
+                    fahrt_nr: Some("123".to_string()),
+                    mode: Mode::HighSpeedTrain,
+                    product: Product {
+                        mode: Mode::HighSpeedTrain,
+                        name: Cow::Borrowed("InterCityExpress"),
+                        short: Cow::Borrowed("ICE"),
+                    },
+                    operator: Some(Operator {
+                        id: "db-fernverkehr-ag".to_string(),
+                        name: "DB Fernverkehr AG".to_string(),
+                    }),
+                    product_name: Some("ICE".to_string()),
+                }),
+                direction: Some("Frankfurt(Main)Hbf".to_string()),
+                planned_arrival_platform: Some("7".to_string()),
+                departure_platform: Some("5".to_string()),
+                planned_departure_platform: Some("4".to_string()),
+                frequency: Some(Frequency {
+                    minimum: Some(Duration::minutes(30)),
+                    maximum: None,
+                    iterations: Some(4),
+                }),
+                intermediate_locations: vec![
+                    IntermediateLocation::Stop(Stop {
+                        place: station("8000105", "Frankfurt(Main)Hbf"),
+                        departure: None,
+                        planned_departure: None,
+                        arrival: Some(time(10, 50)),
+                        planned_arrival: Some(time(10, 45)),
+                        arrival_platform: None,
+                        planned_arrival_platform: None,
+                        departure_platform: None,
+                        planned_departure_platform: None,
+                        cancelled: true,
+                        remarks: vec![remark.clone()],
+                        accessibility: None,
+                    }),
+                    IntermediateLocation::Railway(Place::Location(Location::Point {
+                        id: None,
+                        name: Some("Schnellfahrstrecke Köln-Rhein/Main".to_string()),
+                        poi: None,
+                        latitude: 50.5,
+                        longitude: 7.5,
+                    })),
+                ],
+                load_factor: Some(LoadFactor::VeryHigh),
+                remarks: vec![remark],
+                distance: Some(180000),
+                ..crate::test::leg("8000207", "8000105", (10, 0), (11, 5))
+            }],
+            price: Some(Price {
+                amount: 49.9,
+                currency: "EUR".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn converts_losslessly() {
+        let journey = journey();
+        let fptf = journey.to_fptf();
+
+        assert_eq!(fptf["legs"][0]["departure"], "2024-09-01T10:02:00+02:00");
+        assert_eq!(fptf["legs"][0]["departureDelay"], 120);
+        assert_eq!(fptf["legs"][0]["origin"]["location"]["latitude"], 50.943027);
+        assert_eq!(fptf["legs"][0]["line"]["mode"], "train");
+        assert_eq!(fptf["legs"][0]["line"]["product"], "InterCityExpress");
+        assert_eq!(fptf["legs"][0]["cycle"]["min"], 1800);
+
+        // Compare the debug output, as `PartialEq` only compares ids of stations.
+        assert_eq!(
+            format!("{:?}", Journey::from_fptf(&fptf).unwrap()),
+            format!("{:?}", journey)
+        );
+    }
+
+    #[test]
+    fn imports_hafas_client() {
+        let leg = serde_json::json!({
+            "origin": { "type": "stop", "id": "8000207", "name": "Köln Hbf", "location": { "type": "location", "id": "8000207", "latitude": 50.943029, "longitude": 6.958729 }, "products": { "nationalExpress": true } },
+            "destination": { "type": "stop", "id": "8000105", "name": "Frankfurt(Main)Hbf" },
+            "departure": "2024-09-01T10:02:00+02:00",
+            "plannedDeparture": "2024-09-01T10:00:00+02:00",
+            "departureDelay": 120,
+            "line": { "type": "line", "id": "ice-123", "fahrtNr": "123", "name": "ICE 123", "mode": "train", "product": "nationalExpress" },
+            "remarks": [{ "type": "hint", "code": "FB", "text": "Bicycles allowed" }]
+        });
+
+        let leg = Leg::from_fptf(&leg).unwrap();
+        assert_eq!(leg.departure.unwrap().timezone(), Tz::UTC);
+        assert_eq!(
+            leg.departure.unwrap() - leg.planned_departure.unwrap(),
+            Duration::minutes(2)
+        );
+        assert!(leg.reachable);
+        let line = leg.line.unwrap();
+        assert_eq!(line.mode, Mode::Unknown);
+        assert_eq!(line.product.name, "nationalExpress");
+        assert_eq!(leg.remarks[0].association, RemarkAssociation::Unknown);
+
+        assert_eq!(
+            Leg::from_fptf(&serde_json::json!({ "origin": {} })),
+            Err(FptfError::Missing("type"))
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use geojson::{Feature, FeatureCollection, Geometry, JsonValue, Position, Value};
+
+use crate::{IntermediateLocation, Journey, Leg, Location, Place};
+
+/// The GeoJSON position of a place, which is longitude first.
+fn position(place: &Place) -> Option<Position> {
+    let location = match place {
+        Place::Station(station) => station.location.as_ref()?,
+        Place::Location(location) => location,
+    };
+    let (Location::Address {
+        latitude,
+        longitude,
+        ..
+    }
+    | Location::Point {
+        latitude,
+        longitude,
+        ..
+    }) = location;
+    // The shortest decimal representing the coordinate, instead of the exact value of the `f32`.
+    let coordinate = |c: f32| c.to_string().parse().unwrap_or(f64::from(c));
+    Some(vec![coordinate(*longitude), coordinate(*latitude)])
+}
+
+/// The positions of a polyline, from `Point` or `LineString` features.
+fn positions(polyline: &FeatureCollection) -> Vec<Position> {
+    polyline
+        .features
+        .iter()
+        .filter_map(|f| f.geometry.as_ref())
+        .flat_map(|g| match &g.value {
+            Value::Point(position) => vec![position.clone()],
+            Value::LineString(positions) => positions.clone(),
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Merge the features of a polyline, e.g. one `Point` per coordinate, into a single `LineString` feature.
+pub fn polyline_to_line_string(polyline: &FeatureCollection) -> FeatureCollection {
+    FeatureCollection {
+        features: vec![Feature::from(Geometry::new(Value::LineString(positions(
+            polyline,
+        ))))],
+        bbox: None,
+        foreign_members: None,
+    }
+}
+
+fn time(time: &Option<DateTime<Tz>>) -> JsonValue {
+    time.map(|t| t.to_rfc3339()).into()
+}
+
+/// The delay in seconds, derived from the real-time and scheduled time.
+fn delay(time: &Option<DateTime<Tz>>, planned: &Option<DateTime<Tz>>) -> JsonValue {
+    match (time, planned) {
+        (Some(time), Some(planned)) => (*time - *planned).num_seconds().into(),
+        _ => JsonValue::Null,
+    }
+}
+
+fn set_departure(
+    feature: &mut Feature,
+    departure: &Option<DateTime<Tz>>,
+    planned: &Option<DateTime<Tz>>,
+    platform: Option<&String>,
+) {
+    feature.set_property("departure", time(departure));
+    feature.set_property("plannedDeparture", time(planned));
+    feature.set_property("departureDelay", delay(departure, planned));
+    feature.set_property("departurePlatform", platform.cloned());
+}
+
+fn set_arrival(
+    feature: &mut Feature,
+    arrival: &Option<DateTime<Tz>>,
+    planned: &Option<DateTime<Tz>>,
+    platform: Option<&String>,
+) {
+    feature.set_property("arrival", time(arrival));
+    feature.set_property("plannedArrival", time(planned));
+    feature.set_property("arrivalDelay", delay(arrival, planned));
+    feature.set_property("arrivalPlatform", platform.cloned());
+}
+
+/// A `Point` feature of a place, without geometry if its location is unknown.
+fn point(journey: &Journey, place: &Place, kind: &str) -> Feature {
+    let mut feature = Feature {
+        geometry: position(place).map(|p| Geometry::new(Value::Point(p))),
+        ..Default::default()
+    };
+    let (id, name) = match place {
+        Place::Station(station) => (Some(station.id.clone()), station.name.clone()),
+        Place::Location(Location::Address { address, .. }) => (None, Some(address.clone())),
+        Place::Location(Location::Point { id, name, .. }) => (id.clone(), name.clone()),
+    };
+    feature.set_property("kind", kind);
+    feature.set_property("journey", journey.id.clone());
+    feature.set_property("id", id);
+    feature.set_property("name", name);
+    feature
+}
+
+/// A `LineString` feature of a leg, following its polyline or otherwise its stops.
+fn line(journey: &Journey, leg: &Leg) -> Feature {
+    let mut positions = leg.polyline.as_ref().map(positions).unwrap_or_default();
+    if positions.len() < 2 {
+        let stops = leg.intermediate_locations.iter().filter_map(|l| match l {
+            IntermediateLocation::Stop(stop) => Some(&stop.place),
+            IntermediateLocation::Railway(_) => None,
+        });
+        positions = std::iter::once(&leg.origin)
+            .chain(stops)
+            .chain(std::iter::once(&leg.destination))
+            .filter_map(position)
+            .collect();
+    }
+
+    let mut feature = Feature {
+        geometry: (positions.len() >= 2).then(|| Geometry::new(Value::LineString(positions))),
+        ..Default::default()
+    };
+    let mode = match &leg.line {
+        _ if leg.walking || leg.transfer => Some("walking"),
+        Some(line) => Some(line.mode.id()),
+        None => None,
+    };
+    feature.set_property("kind", "leg");
+    feature.set_property("journey", journey.id.clone());
+    feature.set_property("line", leg.line.as_ref().and_then(|l| l.name.clone()));
+    feature.set_property("mode", mode);
+    feature.set_property("tripId", leg.trip_id.clone());
+    feature.set_property("direction", leg.direction.clone());
+    feature.set_property("cancelled", leg.cancelled);
+    set_departure(
+        &mut feature,
+        &leg.departure,
+        &leg.planned_departure,
+        leg.departure_platform
+            .as_ref()
+            .or(leg.planned_departure_platform.as_ref()),
+    );
+    set_arrival(
+        &mut feature,
+        &leg.arrival,
+        &leg.planned_arrival,
+        leg.arrival_platform
+            .as_ref()
+            .or(leg.planned_arrival_platform.as_ref()),
+    );
+    feature
+}
+
+/// Export a journey as GeoJSON.
+///
+/// Every leg is a `LineString` feature following its [`Leg::polyline`], or through its stops with known locations if there is none.
+/// Its properties contain the `line`, the `mode` (see [`Mode::id`](crate::Mode::id), `walking` for walking legs) and the times with delays in seconds.
+///
+/// The places are `Point` features, with the `kind` `origin`, `destination`, `transfer` (between two legs) or `stopover` (an intermediate [`Stop`](crate::Stop)).
+/// Places without a known location have no geometry.
+///
+/// All features have the [`Journey::id`] as property `journey`, so the features of multiple journeys can be combined.
+pub fn journey_to_geojson(journey: &Journey) -> FeatureCollection {
+    let mut features: Vec<_> = journey.legs.iter().map(|l| line(journey, l)).collect();
+
+    for (i, leg) in journey.legs.iter().enumerate() {
+        if i == 0 {
+            let mut origin = point(journey, &leg.origin, "origin");
+            set_departure(
+                &mut origin,
+                &leg.departure,
+                &leg.planned_departure,
+                leg.departure_platform
+                    .as_ref()
+                    .or(leg.planned_departure_platform.as_ref()),
+            );
+            features.push(origin);
+        }
+
+        for location in &leg.intermediate_locations {
+            if let IntermediateLocation::Stop(stop) = location {
+                let mut feature = point(journey, &stop.place, "stopover");
+                set_arrival(
+                    &mut feature,
+                    &stop.arrival,
+                    &stop.planned_arrival,
+                    stop.arrival_platform
+                        .as_ref()
+                        .or(stop.planned_arrival_platform.as_ref()),
+                );
+                set_departure(
+                    &mut feature,
+                    &stop.departure,
+                    &stop.planned_departure,
+                    stop.departure_platform
+                        .as_ref()
+                        .or(stop.planned_departure_platform.as_ref()),
+                );
+                feature.set_property("cancelled", stop.cancelled);
+                features.push(feature);
+            }
+        }
+
+        let next = journey.legs.get(i + 1);
+        let kind = if next.is_some() {
+            "transfer"
+        } else {
+            "destination"
+        };
+        let mut destination = point(journey, &leg.destination, kind);
+        set_arrival(
+            &mut destination,
+            &leg.arrival,
+            &leg.planned_arrival,
+            leg.arrival_platform
+                .as_ref()
+                .or(leg.planned_arrival_platform.as_ref()),
+        );
+        if let Some(next) = next {
+            set_departure(
+                &mut destination,
+                &next.departure,
+                &next.planned_departure,
+                next.departure_platform
+                    .as_ref()
+                    .or(next.planned_departure_platform.as_ref()),
+            );
+        }
+        features.push(destination);
+    }
+
+    FeatureCollection {
+        features,
+        bbox: None,
+        foreign_members: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{journey, time};
+    use crate::{Station, Stop};
+
+    fn station(id: &str, latitude: f32, longitude: f32) -> Place {
+        Place::Station(Station {
+            id: id.to_string(),
+            name: Some(id.to_string()),
+            location: Some(Location::Point {
+                id: Some(id.to_string()),
+                name: None,
+                poi: None,
+                latitude,
+                longitude,
+            }),
+            ..Default::default()
+        })
+    }
+
+    fn leg(origin: Place, destination: Place) -> Leg {
+        Leg {
+            origin,
+            destination,
+            departure: Some(time(10, 2)),
+            planned_departure_platform: Some("4".to_string()),
+            ..crate::test::leg("a", "b", (10, 0), (10, 30))
+        }
+    }
+
+    fn points(positions: &[[f64; 2]]) -> FeatureCollection {
+        FeatureCollection {
+            features: positions
+                .iter()
+                .map(|p| Feature::from(Geometry::new(Value::Point(p.to_vec()))))
+                .collect(),
+            bbox: None,
+            foreign_members: None,
+        }
+    }
+
+    #[test]
+    fn exports_journey() {
+        let mut first = leg(station("A", 50.9, 6.9), station("B", 50.7, 7.1));
+        first.polyline = Some(points(&[[6.9, 50.9], [7.0, 50.8], [7.1, 50.7]]));
+        let mut second = leg(station("B", 50.7, 7.1), station("D", 50.5, 7.3));
+        second.walking = true;
+        second.intermediate_locations = vec![IntermediateLocation::Stop(Stop {
+            place: station("C", 50.6, 7.2),
+            departure: None,
+            planned_departure: None,
+            arrival: None,
+            planned_arrival: None,
+            arrival_platform: None,
+            planned_arrival_platform: None,
+            departure_platform: None,
+            planned_departure_platform: None,
+            cancelled: false,
+            remarks: vec![],
+            accessibility: None,
+        })];
+        let journey = journey("journey", vec![first, second]);
+
+        let features = journey_to_geojson(&journey).features;
+        let kinds: Vec<_> = features
+            .iter()
+            .map(|f| f.property("kind").and_then(|k| k.as_str()).unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "leg",
+                "leg",
+                "origin",
+                "transfer",
+                "stopover",
+                "destination"
+            ]
+        );
+
+        let line = |f: &Feature| match &f.geometry.as_ref().unwrap().value {
+            Value::LineString(positions) => positions.clone(),
+            _ => panic!("not a LineString"),
+        };
+        assert_eq!(line(&features[0]).len(), 3);
+        // Without a polyline, the leg follows its stops.
+        assert_eq!(
+            line(&features[1]),
+            [vec![7.1, 50.7], vec![7.2, 50.6], vec![7.3, 50.5]]
+        );
+        assert_eq!(features[1].property("mode").unwrap(), "walking");
+        assert_eq!(features[0].property("departureDelay").unwrap(), 120);
+        assert_eq!(features[3].property("name").unwrap(), "B");
+        assert_eq!(features[3].property("departurePlatform").unwrap(), "4");
+    }
+
+    #[test]
+    fn merges_polyline() {
+        let polyline = polyline_to_line_string(&points(&[[6.9, 50.9], [7.0, 50.8]]));
+        assert_eq!(polyline.features.len(), 1);
+        assert_eq!(
+            polyline.features[0].geometry.as_ref().unwrap().value,
+            Value::LineString(vec![vec![6.9, 50.9], vec![7.0, 50.8]])
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::hash::fnv1a;
+use crate::{Journey, Leg, Location, Place};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// What a calendar event is created for, see [`to_icalendar`].
+pub enum CalendarEvents {
+    /// One event from the departure of the first to the arrival of the last leg.
+    #[default]
+    Journey,
+    /// One event per leg.
+    Legs,
+}
+
+/// Serialize journeys into an iCalendar (RFC 5545) calendar.
+///
+/// Events use the real-time times, or the planned ones if there is no real-time data, in UTC, as a `TZID` would require a `VTIMEZONE` for the zone.
+/// Times in descriptions are local to the stop.
+/// The `UID` of an event is derived from [`Journey::id`], so importing a refreshed journey again updates the event instead of duplicating it.
+/// Legs without times are skipped.
+pub fn to_icalendar(journeys: &[Journey], events: CalendarEvents) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//schmiddi-on-mobile//railway-backend//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for journey in journeys {
+        let uid = format!("{:016x}", fnv1a(journey.id.as_bytes()));
+        match events {
+            CalendarEvents::Journey => {
+                if let Some(event) = journey_event(journey) {
+                    lines.extend(event.lines(&format!("{}@railway-backend", uid), &stamp));
+                }
+            }
+            CalendarEvents::Legs => {
+                for (i, leg) in journey.legs.iter().enumerate() {
+                    if let Some(event) = leg_event(leg) {
+                        lines
+                            .extend(event.lines(&format!("{}-{}@railway-backend", uid, i), &stamp));
+                    }
+                }
+            }
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|l| fold(l)).map(|l| l + "\r\n").collect()
+}
+
+struct Event {
+    start: DateTime<Tz>,
+    end: DateTime<Tz>,
+    summary: String,
+    location: String,
+    description: Vec<String>,
+    cancelled: bool,
+}
+
+impl Event {
+    fn lines(&self, uid: &str, stamp: &str) -> Vec<String> {
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", uid),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART:{}", time(&self.start)),
+            format!("DTEND:{}", time(&self.end)),
+            format!("SUMMARY:{}", escape(&self.summary)),
+            format!("LOCATION:{}", escape(&self.location)),
+        ];
+        if !self.description.is_empty() {
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                escape(&self.description.join("\n"))
+            ));
+        }
+        if self.cancelled {
+            lines.push("STATUS:CANCELLED".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+        lines
+    }
+}
+
+/// The value of a time in UTC, e.g. `20240901T080000Z`.
+fn time(time: &DateTime<Tz>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escape a text value, see RFC 5545, section 3.3.11.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into lines of at most 75 octets, see RFC 5545, section 3.1.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The space of the continuation counts as well.
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+fn place_name(place: &Place) -> String {
+    match place {
+        Place::Station(station) => station.name.clone().unwrap_or_else(|| station.id.clone()),
+        Place::Location(Location::Address { address, .. }) => address.clone(),
+        Place::Location(Location::Point {
+            name: Some(name), ..
+        }) => name.clone(),
+        Place::Location(Location::Point {
+            latitude,
+            longitude,
+            ..
+        }) => format!("{}, {}", latitude, longitude),
+    }
+}
+
+fn leg_summary(leg: &Leg) -> Option<String> {
+    if leg.walking || leg.transfer {
+        return Some("Walk".to_string());
+    }
+    leg.line.as_ref()?.name.clone()
+}
+
+/// What to show about a leg, e.g. `ICE 123: Köln Hbf 10:02, platform 5 → Frankfurt(Main)Hbf 11:05, platform 7`, followed by its remarks.
+fn leg_description(leg: &Leg) -> Vec<String> {
+    let stop = |place: &Place, time: Option<DateTime<Tz>>, platform: Option<&String>| {
+        let mut stop = place_name(place);
+        if let Some(time) = time {
+            stop += &format!(" {}", time.format("%H:%M"));
+        }
+        if let Some(platform) = platform {
+            stop += &format!(", platform {}", platform);
+        }
+        stop
+    };
+    let mut description = format!(
+        "{} → {}",
+        stop(
+            &leg.origin,
+            leg.departure.or(leg.planned_departure),
+            leg.departure_platform
+                .as_ref()
+                .or(leg.planned_departure_platform.as_ref())
+        ),
+        stop(
+            &leg.destination,
+            leg.arrival.or(leg.planned_arrival),
+            leg.arrival_platform
+                .as_ref()
+                .or(leg.planned_arrival_platform.as_ref())
+        ),
+    );
+    if let Some(summary) = leg_summary(leg) {
+        description = format!("{}: {}", summary, description);
+    }
+    if leg.cancelled {
+        description += " (cancelled)";
+    }
+    std::iter::once(description)
+        .chain(leg.remarks.iter().map(|r| format!("- {}", r.text)))
+        .collect()
+}
+
+fn leg_event(leg: &Leg) -> Option<Event> {
+    Some(Event {
+        start: leg.departure.or(leg.planned_departure)?,
+        end: leg.arrival.or(leg.planned_arrival)?,
+        summary: leg_summary(leg).unwrap_or_else(|| {
+            format!(
+                "{} → {}",
+                place_name(&leg.origin),
+                place_name(&leg.destination)
+            )
+        }),
+        location: place_name(&leg.origin),
+        description: leg_description(leg),
+        cancelled: leg.cancelled,
+    })
+}
+
+fn journey_event(journey: &Journey) -> Option<Event> {
+    let first = journey.legs.first()?;
+    let last = journey.legs.last()?;
+    let lines: Vec<_> = journey
+        .legs
+        .iter()
+        .filter_map(|l| l.line.as_ref()?.name.clone())
+        .collect();
+    Some(Event {
+        start: first.departure.or(first.planned_departure)?,
+        end: last.arrival.or(last.planned_arrival)?,
+        summary: if lines.is_empty() {
+            format!(
+                "{} → {}",
+                place_name(&first.origin),
+                place_name(&last.destination)
+            )
+        } else {
+            lines.join(" → ")
+        },
+        location: place_name(&first.origin),
+        description: journey.legs.iter().flat_map(leg_description).collect(),
+        cancelled: journey.legs.iter().any(|l| l.cancelled),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::test::time;
+    use crate::{Line, Mode, Product, Remark, RemarkAssociation, RemarkType, Station};
+
+    fn station(name: &str) -> Place {
+        Place::Station(Station {
+            id: name.to_string(),
+            name: Some(name.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn leg(origin: &str, destination: &str, line: Option<&str>, departure: u32) -> Leg {
+        Leg {
+            origin: station(origin),
+            destination: station(destination),
+            departure: Some(time(departure, 2)),
+            arrival: None,
+            line: line.map(|name| Line {
+                name: Some(name.to_string()),
+                fahrt_nr: None,
+                mode: Mode::RegionalTrain,
+                product: Product {
+                    mode: Mode::RegionalTrain,
+                    name: Cow::Borrowed("Regio"),
+                    short: Cow::Borrowed("RB"),
+                },
+                operator: None,
+                product_name: None,
+            }),
+            planned_departure_platform: Some("4".to_string()),
+            walking: line.is_none(),
+            ..crate::test::leg(origin, destination, (departure, 0), (departure, 45))
+        }
+    }
+
+    fn journey() -> Journey {
+        let mut first = leg("Köln Hbf", "Bonn Hbf", Some("RE 5"), 10);
+        first.remarks.push(Remark {
+            code: "FB".to_string(),
+            text: "Bicycles allowed; limited capacity".to_string(),
+            r#type: RemarkType::Hint,
+            association: RemarkAssociation::Bike,
+            summary: None,
+            trip_id: None,
+        });
+        Journey {
+            id: "¶HKI¶T$A=1@O=Köln Hbf".to_string(),
+            legs: vec![first, leg("Bonn Hbf", "Bonn, Markt", None, 11)],
+            price: None,
+        }
+    }
+
+    /// The lines of the calendar, unfolded and without the time-dependent `DTSTAMP`.
+    fn lines(calendar: &str) -> Vec<String> {
+        calendar
+            .replace("\r\n ", "")
+            .split("\r\n")
+            .filter(|l| !l.starts_with("DTSTAMP:"))
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn writes_journey_event() {
+        let calendar = to_icalendar(&[journey()], CalendarEvents::Journey);
+        assert!(calendar.split("\r\n").all(|l| l.len() <= 75));
+
+        let lines = lines(&calendar);
+        let uid = format!(
+            "UID:{:016x}@railway-backend",
+            fnv1a(journey().id.as_bytes())
+        );
+        assert_eq!(
+            &lines[4..12],
+            [
+                "BEGIN:VEVENT",
+                &uid,
+                "DTSTART:20240901T080200Z",
+                "DTEND:20240901T094500Z",
+                "SUMMARY:RE 5",
+                "LOCATION:Köln Hbf",
+                "DESCRIPTION:RE 5: Köln Hbf 10:02\\, platform 4 → Bonn Hbf 10:45\\n- Bicycles allowed\\; limited capacity\\nWalk: Bonn Hbf 11:02\\, platform 4 → Bonn\\, Markt 11:45",
+                "END:VEVENT",
+            ]
+        );
+        assert_eq!(lines.last().map(|l| &l[..]), Some(""));
+    }
+
+    #[test]
+    fn writes_leg_events() {
+        let lines = lines(&to_icalendar(&[journey()], CalendarEvents::Legs));
+        let summaries: Vec<_> = lines
+            .iter()
+            .filter(|l| l.starts_with("SUMMARY:") || l.starts_with("UID:"))
+            .collect();
+        let uid = format!("{:016x}", fnv1a(journey().id.as_bytes()));
+        assert_eq!(
+            summaries,
+            [
+                &format!("UID:{}-0@railway-backend", uid),
+                "SUMMARY:RE 5",
+                &format!("UID:{}-1@railway-backend", uid),
+                "SUMMARY:Walk",
+            ]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{Journey, Leg};
+
+/// The number of journeys searched when refreshing a [`JourneyRef`], as the journey may no longer be the first one at its departure.
+pub const REFRESH_RESULTS: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// What is needed to find a journey again, stored as JSON in the [id](Journey::id) of the journey.
+///
+/// For APIs without a way to look up a journey: it is searched again with the same query at its planned departure and identified by its legs.
+pub struct JourneyRef<Q> {
+    /// The provider-specific query the journey was found with, without the time to search at.
+    pub query: Q,
+    /// The planned departure of the journey, as returned by the API.
+    pub departure: String,
+    /// The [ids](Leg::id) of the legs with a vehicle.
+    pub legs: Vec<String>,
+}
+
+impl<Q: Serialize + DeserializeOwned> JourneyRef<Q> {
+    /// The reference to the journey with the given legs.
+    pub fn new(query: Q, departure: String, legs: &[Leg]) -> Self {
+        Self {
+            query,
+            departure,
+            legs: legs
+                .iter()
+                .filter(|l| l.trip_id.is_some())
+                .map(|l| l.id())
+                .collect(),
+        }
+    }
+
+    /// The reference stored in the id of a journey.
+    pub fn from_id(id: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(id)
+    }
+
+    /// The id of the journey.
+    pub fn to_id(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Find the referenced journey among the ones searched again.
+    pub fn find<I: IntoIterator<Item = Journey>>(
+        &self,
+        journeys: I,
+    ) -> Result<Journey, JourneyNotFound> {
+        journeys
+            .into_iter()
+            .find(|j| {
+                Self::from_id(&j.id)
+                    .is_ok_and(|r| r.departure == self.departure && r.legs == self.legs)
+            })
+            .ok_or(JourneyNotFound)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The journey of a [`JourneyRef`] was not found again, e.g. because it was removed from the timetable.
+pub struct JourneyNotFound;
+
+impl Display for JourneyNotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "the journey is no longer available")
+    }
+}
+
+impl StdError for JourneyNotFound {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{journey, leg, walk};
+
+    #[test]
+    fn finds_journeys_again() {
+        let mut legs = vec![
+            walk("a", "b", (9, 50), (10, 0)),
+            leg("b", "c", (10, 0), (10, 20)),
+        ];
+        legs[1].trip_id = Some("trip".to_string());
+        let reference = JourneyRef::new(vec!["query".to_string()], "10:00".to_string(), &legs);
+        assert_eq!(reference.legs, vec![legs[1].id()]);
+        let id = reference.to_id().unwrap();
+        assert_eq!(JourneyRef::from_id(&id).unwrap(), reference);
+
+        let mut delayed = journey(&id, legs.clone());
+        delayed.legs[1].departure = Some(crate::test::time(10, 5));
+        let other = JourneyRef::new(vec!["query".to_string()], "10:10".to_string(), &legs);
+        let candidates = vec![
+            journey("no reference", legs.clone()),
+            journey(&other.to_id().unwrap(), legs),
+            delayed.clone(),
+        ];
+        assert_eq!(reference.find(candidates.clone()), Ok(delayed));
+        assert_eq!(other.find(candidates[..1].to_vec()), Err(JourneyNotFound));
+    }
+}
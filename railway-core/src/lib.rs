@@ -1,13 +1,36 @@
 #![doc = include_str!("../README.md")]
 
 mod api;
+mod diff;
 mod error;
+#[cfg(feature = "fptf")]
+pub mod fptf;
+#[cfg(feature = "polylines")]
+mod geo;
+mod hash;
+mod ical;
+#[cfg(feature = "journey-ref")]
+mod journey_ref;
+mod pager;
+mod replan;
 mod requester;
 #[cfg(feature = "serde")]
 mod serialize;
+#[cfg(test)]
+mod test;
+mod transfer;
 mod types;
 
 pub use api::*;
+pub use diff::*;
 pub use error::*;
+#[cfg(feature = "polylines")]
+pub use geo::*;
+pub use ical::*;
+#[cfg(feature = "journey-ref")]
+pub use journey_ref::*;
+pub use pager::*;
+pub use replan::*;
 pub use requester::*;
+pub use transfer::*;
 pub use types::*;
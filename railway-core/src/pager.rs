@@ -0,0 +1,243 @@
+use std::collections::{HashSet, VecDeque};
+use std::marker::PhantomData;
+
+use futures::stream::{self, Stream};
+
+use crate::{Journey, JourneysOptions, JourneysResponse, Place, Provider, Requester};
+
+/// Page through the journeys between two places.
+///
+/// The pager owns the original query and keeps track of the references to query earlier or later journeys (see [`JourneysResponse::earlier_ref`] and [`JourneysResponse::later_ref`]).
+/// Journeys which were already returned by a previous page are filtered out, using [`Journey::id`].
+pub struct JourneysPager<'a, R: Requester, P: Provider<R>> {
+    provider: &'a P,
+    from: Place,
+    to: Place,
+    opts: JourneysOptions,
+    state: Option<PagerState>,
+    _requester: PhantomData<R>,
+}
+
+struct PagerState {
+    earlier_ref: Option<String>,
+    later_ref: Option<String>,
+    seen: HashSet<String>,
+}
+
+enum Direction {
+    Earlier,
+    Later,
+}
+
+impl<'a, R: Requester, P: Provider<R>> JourneysPager<'a, R, P> {
+    /// Create a new pager querying the given provider.
+    ///
+    /// No request is made until the first page is requested.
+    pub fn new(provider: &'a P, from: Place, to: Place, opts: JourneysOptions) -> Self {
+        Self {
+            provider,
+            from,
+            to,
+            opts,
+            state: None,
+            _requester: PhantomData,
+        }
+    }
+
+    /// Query the next (later) page of journeys.
+    ///
+    /// The first call to either [`JourneysPager::next_page`] or [`JourneysPager::previous_page`] queries the original request.
+    /// Returns [`None`] if the provider does not offer any later journeys.
+    pub async fn next_page(
+        &mut self,
+    ) -> Result<Option<Vec<Journey>>, crate::Error<R::Error, P::Error>> {
+        self.page(Direction::Later).await
+    }
+
+    /// Query the previous (earlier) page of journeys.
+    ///
+    /// The first call to either [`JourneysPager::next_page`] or [`JourneysPager::previous_page`] queries the original request.
+    /// Returns [`None`] if the provider does not offer any earlier journeys.
+    pub async fn previous_page(
+        &mut self,
+    ) -> Result<Option<Vec<Journey>>, crate::Error<R::Error, P::Error>> {
+        self.page(Direction::Earlier).await
+    }
+
+    async fn page(
+        &mut self,
+        direction: Direction,
+    ) -> Result<Option<Vec<Journey>>, crate::Error<R::Error, P::Error>> {
+        let mut opts = self.opts.clone();
+        if let Some(state) = &self.state {
+            let reference = match direction {
+                Direction::Earlier => &state.earlier_ref,
+                Direction::Later => &state.later_ref,
+            };
+            let Some(reference) = reference.clone() else {
+                return Ok(None);
+            };
+            opts.earlier_than = None;
+            opts.later_than = None;
+            match direction {
+                Direction::Earlier => opts.earlier_than = Some(reference),
+                Direction::Later => opts.later_than = Some(reference),
+            }
+        }
+
+        let JourneysResponse {
+            earlier_ref,
+            later_ref,
+            journeys,
+        } = self
+            .provider
+            .journeys(self.from.clone(), self.to.clone(), opts)
+            .await?;
+
+        let state = self.state.get_or_insert_with(|| PagerState {
+            earlier_ref: earlier_ref.clone(),
+            later_ref: later_ref.clone(),
+            seen: HashSet::new(),
+        });
+        match direction {
+            Direction::Earlier => state.earlier_ref = earlier_ref,
+            Direction::Later => state.later_ref = later_ref,
+        }
+
+        Ok(Some(
+            journeys
+                .into_iter()
+                .filter(|j| state.seen.insert(j.id.clone()))
+                .collect(),
+        ))
+    }
+
+    /// Stream all journeys, starting with the original request and continuing with later pages.
+    ///
+    /// The stream ends once the provider does not offer any later journeys or after the first error.
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<Journey, crate::Error<R::Error, P::Error>>> + 'a
+    where
+        R: 'a,
+    {
+        stream::unfold(
+            (self, VecDeque::new(), false),
+            |(mut pager, mut buffer, mut done)| async move {
+                loop {
+                    if let Some(journey) = buffer.pop_front() {
+                        return Some((Ok(journey), (pager, buffer, done)));
+                    }
+                    if done {
+                        return None;
+                    }
+                    match pager.next_page().await {
+                        Ok(Some(journeys)) => buffer.extend(journeys),
+                        Ok(None) => return None,
+                        Err(e) => {
+                            done = true;
+                            return Some((Err(e), (pager, buffer, done)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::test::MockProvider;
+    use crate::Station;
+
+    fn journey(id: i64) -> Journey {
+        Journey {
+            id: id.to_string(),
+            legs: vec![],
+            price: None,
+        }
+    }
+
+    /// Pages are numbered, page `n` contains journeys `n` and `n + 1` so that neighbouring pages overlap.
+    fn paged_provider() -> MockProvider {
+        MockProvider::new(|opts| {
+            let page: i64 = opts
+                .later_than
+                .as_ref()
+                .or(opts.earlier_than.as_ref())
+                .map(|r| r.parse().unwrap())
+                .unwrap_or_default();
+            JourneysResponse {
+                earlier_ref: (page > -2).then(|| (page - 1).to_string()),
+                later_ref: (page < 2).then(|| (page + 1).to_string()),
+                journeys: vec![journey(page), journey(page + 1)],
+            }
+        })
+    }
+
+    fn place() -> Place {
+        Place::Station(Station::default())
+    }
+
+    fn ids(journeys: Option<Vec<Journey>>) -> Option<Vec<String>> {
+        journeys.map(|js| js.into_iter().map(|j| j.id).collect())
+    }
+
+    #[test]
+    fn pages_in_both_directions() {
+        let provider = paged_provider();
+        let mut pager = JourneysPager::new(&provider, place(), place(), JourneysOptions::default());
+
+        block_on(async {
+            assert_eq!(
+                ids(pager.next_page().await.unwrap()),
+                Some(vec!["0".to_string(), "1".to_string()])
+            );
+            assert_eq!(
+                ids(pager.previous_page().await.unwrap()),
+                Some(vec!["-1".to_string()])
+            );
+            assert_eq!(
+                ids(pager.next_page().await.unwrap()),
+                Some(vec!["2".to_string()])
+            );
+            assert_eq!(
+                ids(pager.next_page().await.unwrap()),
+                Some(vec!["3".to_string()])
+            );
+            assert_eq!(ids(pager.next_page().await.unwrap()), None);
+        });
+
+        assert_eq!(
+            provider
+                .requests()
+                .into_iter()
+                .map(|(_, _, opts)| (opts.earlier_than, opts.later_than))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, None),
+                (Some("-1".to_string()), None),
+                (None, Some("1".to_string())),
+                (None, Some("2".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn streams_later_journeys() {
+        let provider = paged_provider();
+        let pager = JourneysPager::new(&provider, place(), place(), JourneysOptions::default());
+
+        let journeys = block_on(
+            pager
+                .into_stream()
+                .map(|j| j.unwrap().id)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(journeys, vec!["0", "1", "2", "3"]);
+    }
+}
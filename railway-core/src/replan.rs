@@ -0,0 +1,190 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::transfer::rest_of;
+use crate::{
+    Journey, JourneysOptions, Leg, Place, Provider, Requester, TransferStatus, TransferTimes,
+};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+/// Where a [`Journey`] breaks, see [`Journey::break_point`].
+pub struct BreakPoint {
+    /// The index in [`Journey::legs`] of the first leg that cannot be taken.
+    pub leg: usize,
+    /// The last place that can still be reached.
+    pub place: Place,
+    /// When the place is reached, using real-time data where available.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialize::datetime_with_timezone")
+    )]
+    pub time: Option<DateTime<Tz>>,
+}
+
+fn is_riding(leg: &Leg) -> bool {
+    !leg.walking && !leg.transfer
+}
+
+impl Journey {
+    /// The first point at which the journey cannot be continued as planned, if any.
+    ///
+    /// This is a cancelled or unreachable leg, or a leg whose transfer is [broken](TransferStatus::Broken).
+    /// If the journey breaks at the first leg not on foot, the journey cannot be started and its origin is returned.
+    pub fn break_point(&self, transfer_times: &TransferTimes) -> Option<BreakPoint> {
+        let transfers = self.transfers(transfer_times);
+        let (index, leg) = self.legs.iter().enumerate().find(|(index, leg)| {
+            is_riding(leg)
+                && (leg.cancelled
+                    || !leg.reachable
+                    || transfers
+                        .iter()
+                        .any(|t| t.to_leg == *index && t.status == TransferStatus::Broken))
+        })?;
+
+        let previous = self.legs[..index].iter().rev().find(|l| is_riding(l));
+        Some(match previous {
+            Some(previous) => BreakPoint {
+                leg: index,
+                place: previous.destination.clone(),
+                time: previous.arrival.or(previous.planned_arrival),
+            },
+            None => {
+                let first = self.legs.first().unwrap_or(leg);
+                BreakPoint {
+                    leg: index,
+                    place: first.origin.clone(),
+                    time: first.departure.or(first.planned_departure),
+                }
+            }
+        })
+    }
+
+    /// Alternatives from the [`BreakPoint`] of the journey to its destination, earliest arrival first.
+    ///
+    /// Empty if the journey is not broken. The query departs at the break point unless [`JourneysOptions::departure`] is later, e.g. the current time.
+    pub async fn alternatives<R: Requester, P: Provider<R>>(
+        &self,
+        provider: &P,
+        transfer_times: &TransferTimes,
+        opts: JourneysOptions,
+    ) -> Result<Vec<Journey>, crate::Error<R::Error, P::Error>> {
+        let Some((from, to, opts)) = self
+            .break_point(transfer_times)
+            .and_then(|b| rest_of(self, b.place, b.time, opts))
+        else {
+            return Ok(vec![]);
+        };
+        let mut journeys = provider.journeys(from, to, opts).await?.journeys;
+        journeys.sort_by_key(|j| {
+            let arrival = j.legs.last().and_then(|l| l.arrival.or(l.planned_arrival));
+            // Journeys without arrival last.
+            (arrival.is_none(), arrival)
+        });
+        Ok(journeys)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::test::{journey, leg, station, time, MockProvider};
+
+    #[test]
+    fn finds_break_points() {
+        let times = TransferTimes::new(Duration::minutes(5));
+        let mut j = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                leg("b", "c", (10, 30), (10, 50)),
+                leg("c", "d", (11, 0), (11, 20)),
+            ],
+        );
+        assert_eq!(j.break_point(&times), None);
+
+        j.legs[2].cancelled = true;
+        assert_eq!(
+            j.break_point(&times),
+            Some(BreakPoint {
+                leg: 2,
+                place: station("c"),
+                time: Some(time(10, 50)),
+            })
+        );
+
+        // A delay breaks the earlier transfer to the second leg.
+        j.legs[0].arrival = Some(time(10, 28));
+        assert_eq!(
+            j.break_point(&times),
+            Some(BreakPoint {
+                leg: 1,
+                place: station("b"),
+                time: Some(time(10, 28)),
+            })
+        );
+
+        j.legs[0].cancelled = true;
+        assert_eq!(
+            j.break_point(&times),
+            Some(BreakPoint {
+                leg: 0,
+                place: station("a"),
+                time: Some(time(10, 0)),
+            })
+        );
+    }
+
+    #[test]
+    fn ranks_alternatives_by_arrival() {
+        let mut broken = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                leg("b", "c", (10, 30), (10, 50)),
+            ],
+        );
+        broken.legs[1].reachable = false;
+        let provider = MockProvider::with_journeys(vec![
+            journey("late", vec![leg("b", "c", (10, 40), (11, 10))]),
+            journey("early", vec![leg("b", "c", (10, 45), (11, 0))]),
+        ]);
+        let times = TransferTimes::new(Duration::minutes(5));
+
+        let alternatives = block_on(broken.alternatives(
+            &provider,
+            &times,
+            JourneysOptions {
+                departure: Some(time(10, 25)),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+        assert_eq!(
+            alternatives.iter().map(|j| &j.id[..]).collect::<Vec<_>>(),
+            vec!["early", "late"]
+        );
+        let requests = provider.requests();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|(from, to, opts)| (from, to, opts.departure))
+                .collect::<Vec<_>>(),
+            vec![(&station("b"), &station("c"), Some(time(10, 25)))]
+        );
+
+        // Journeys that are not broken have no alternatives.
+        broken.legs[1].reachable = true;
+        assert!(
+            block_on(broken.alternatives(&provider, &times, JourneysOptions::default()))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(provider.requests().len(), 1);
+    }
+}
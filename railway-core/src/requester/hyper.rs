@@ -41,8 +41,7 @@ pub struct HyperRustlsRequesterBuilder(
 impl RequesterBuilder for HyperRustlsRequesterBuilder {
     type Requester = HyperRustlsRequester;
 
-    fn with_pem_bundle(mut self, bytes: &[u8]) -> Self {
-        let mut bytes = &bytes[..];
+    fn with_pem_bundle(mut self, mut bytes: &[u8]) -> Self {
         // TODO: This only allows calling this function once.
         self.1 = HttpsConnectorBuilder::new()
             .with_tls_config({
@@ -2,6 +2,10 @@
 mod hyper;
 #[cfg(feature = "hyper-requester")]
 pub use hyper::*;
+#[cfg(feature = "replay-requester")]
+mod replay;
+#[cfg(feature = "replay-requester")]
+pub use replay::*;
 
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -0,0 +1,285 @@
+use crate::hash::fnv1a;
+use crate::{Requester, RequesterBuilder};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The file a response is stored in, identified by a hash of the request method, URL and body.
+///
+/// Headers are not taken into account.
+fn response_path(dir: &Path, method: &str, url: &url::Url, body: &[u8]) -> PathBuf {
+    let hash = fnv1a(
+        method
+            .as_bytes()
+            .iter()
+            .chain(url.as_str().as_bytes())
+            .chain(body),
+    );
+    dir.join(format!("{}-{:016x}", method.to_lowercase(), hash))
+}
+
+/// A [`Requester`] answering requests with responses recorded by a [`RecordingRequester`], e.g. for tests.
+///
+/// Fails with [`ReplayRequesterError::NotRecorded`] for requests that were not recorded.
+#[derive(Clone, Debug)]
+pub struct ReplayRequester {
+    dir: PathBuf,
+}
+
+impl ReplayRequester {
+    /// Replay the responses stored in the given directory.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn replay(
+        &self,
+        method: &str,
+        url: &url::Url,
+        body: &[u8],
+    ) -> Result<Vec<u8>, ReplayRequesterError> {
+        let path = response_path(&self.dir, method, url, body);
+        std::fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ReplayRequesterError::NotRecorded(path),
+            _ => ReplayRequesterError::Io(e),
+        })
+    }
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl Requester for ReplayRequester {
+    type Error = ReplayRequesterError;
+
+    async fn get(
+        &self,
+        url: &url::Url,
+        body: &[u8],
+        _headers: HashMap<&str, &str>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.replay("GET", url, body)
+    }
+
+    async fn post(
+        &self,
+        url: &url::Url,
+        body: &[u8],
+        _headers: HashMap<&str, &str>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.replay("POST", url, body)
+    }
+}
+
+/// Custom certificates are irrelevant for replaying, so this only builds the [`ReplayRequester`].
+impl RequesterBuilder for ReplayRequester {
+    type Requester = ReplayRequester;
+
+    fn with_pem_bundle(self, _bytes: &[u8]) -> Self {
+        self
+    }
+
+    fn build(self) -> Self::Requester {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayRequesterError {
+    /// No response was recorded for the request, contains the path it was expected at.
+    NotRecorded(PathBuf),
+    /// Reading the recorded response failed.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ReplayRequesterError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::NotRecorded(path) => {
+                write!(fmt, "no response recorded at {}", path.display())
+            }
+            Self::Io(e) => write!(fmt, "failed to read recorded response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplayRequesterError {}
+
+/// A [`Requester`] storing the successful responses of another one, to be replayed by a [`ReplayRequester`].
+#[derive(Clone, Debug)]
+pub struct RecordingRequester<R> {
+    inner: R,
+    dir: PathBuf,
+}
+
+impl<R: Requester> RecordingRequester<R> {
+    /// Store the responses of the given requester in the given directory.
+    pub fn new<P: Into<PathBuf>>(inner: R, dir: P) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    fn record(
+        &self,
+        method: &str,
+        url: &url::Url,
+        body: &[u8],
+        response: &[u8],
+    ) -> Result<(), RecordingRequesterError<R::Error>> {
+        std::fs::create_dir_all(&self.dir).map_err(RecordingRequesterError::Io)?;
+        std::fs::write(response_path(&self.dir, method, url, body), response)
+            .map_err(RecordingRequesterError::Io)
+    }
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl<R: Requester> Requester for RecordingRequester<R> {
+    type Error = RecordingRequesterError<R::Error>;
+
+    async fn get(
+        &self,
+        url: &url::Url,
+        body: &[u8],
+        headers: HashMap<&str, &str>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let response = self
+            .inner
+            .get(url, body, headers)
+            .await
+            .map_err(RecordingRequesterError::Request)?;
+        self.record("GET", url, body, &response)?;
+        Ok(response)
+    }
+
+    async fn post(
+        &self,
+        url: &url::Url,
+        body: &[u8],
+        headers: HashMap<&str, &str>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let response = self
+            .inner
+            .post(url, body, headers)
+            .await
+            .map_err(RecordingRequesterError::Request)?;
+        self.record("POST", url, body, &response)?;
+        Ok(response)
+    }
+}
+
+/// Build a [`RecordingRequester`] wrapping the [`Requester`] of another [`RequesterBuilder`].
+pub struct RecordingRequesterBuilder<B> {
+    inner: B,
+    dir: PathBuf,
+}
+
+impl<B: RequesterBuilder> RecordingRequesterBuilder<B> {
+    /// Store the responses of the requester built by the given builder in the given directory.
+    pub fn new<P: Into<PathBuf>>(inner: B, dir: P) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+}
+
+impl<B: RequesterBuilder> RequesterBuilder for RecordingRequesterBuilder<B> {
+    type Requester = RecordingRequester<B::Requester>;
+
+    fn with_pem_bundle(mut self, bytes: &[u8]) -> Self {
+        self.inner = self.inner.with_pem_bundle(bytes);
+        self
+    }
+
+    fn build(self) -> Self::Requester {
+        RecordingRequester::new(self.inner.build(), self.dir)
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordingRequesterError<E> {
+    /// The wrapped requester failed.
+    Request(E),
+    /// Storing the response failed.
+    Io(std::io::Error),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for RecordingRequesterError<E> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Request(e) => write!(fmt, "{}", e),
+            Self::Io(e) => write!(fmt, "failed to record response: {}", e),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for RecordingRequesterError<E> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[derive(Debug)]
+    struct NoError;
+
+    impl std::fmt::Display for NoError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "no error")
+        }
+    }
+
+    impl std::error::Error for NoError {}
+
+    /// Responds with the request body.
+    struct EchoRequester;
+
+    #[cfg_attr(feature = "rt-multi-thread", async_trait)]
+    #[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+    impl Requester for EchoRequester {
+        type Error = NoError;
+
+        async fn get(
+            &self,
+            _url: &url::Url,
+            body: &[u8],
+            _headers: HashMap<&str, &str>,
+        ) -> Result<Vec<u8>, Self::Error> {
+            Ok(body.to_vec())
+        }
+
+        async fn post(
+            &self,
+            _url: &url::Url,
+            body: &[u8],
+            _headers: HashMap<&str, &str>,
+        ) -> Result<Vec<u8>, Self::Error> {
+            Ok(body.to_vec())
+        }
+    }
+
+    #[test]
+    fn replays_recorded_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = url::Url::parse("https://example.com/api").unwrap();
+
+        let recording = RecordingRequester::new(EchoRequester, dir.path());
+        block_on(recording.post(&url, b"request", HashMap::new())).unwrap();
+
+        let replay = ReplayRequester::new(dir.path());
+        assert_eq!(
+            block_on(replay.post(&url, b"request", HashMap::new())).unwrap(),
+            b"request"
+        );
+        assert!(matches!(
+            block_on(replay.get(&url, b"request", HashMap::new())),
+            Err(ReplayRequesterError::NotRecorded(_))
+        ));
+        assert!(matches!(
+            block_on(replay.post(&url, b"other", HashMap::new())),
+            Err(ReplayRequesterError::NotRecorded(_))
+        ));
+    }
+}
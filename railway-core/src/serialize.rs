@@ -22,6 +22,25 @@ pub(crate) mod duration {
     }
 }
 
+pub(crate) mod duration_required {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(v: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_i64(v.num_minutes())
+    }
+
+    pub(crate) fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Duration::minutes(i64::deserialize(d)?))
+    }
+}
+
 pub(crate) mod datetime_with_timezone {
     use chrono::{DateTime, NaiveDateTime};
     use chrono_tz::Tz;
@@ -0,0 +1,181 @@
+//! Helpers shared by the tests of this crate.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{
+    Journey, JourneysOptions, JourneysResponse, Leg, LocationsOptions, LocationsResponse, Place,
+    Provider, RefreshJourneyOptions, RefreshJourneyResponse, Requester, Station,
+};
+
+/// The given time on September 1, 2024 in Berlin.
+pub fn time(hour: u32, minute: u32) -> DateTime<Tz> {
+    chrono_tz::Europe::Berlin
+        .with_ymd_and_hms(2024, 9, 1, hour, minute, 0)
+        .unwrap()
+}
+
+/// A station with only an id.
+pub fn station(id: &str) -> Place {
+    Place::Station(Station {
+        id: id.to_string(),
+        ..Default::default()
+    })
+}
+
+/// A reachable leg between two stations, on time at the given hours and minutes.
+pub fn leg(from: &str, to: &str, departure: (u32, u32), arrival: (u32, u32)) -> Leg {
+    Leg {
+        origin: station(from),
+        destination: station(to),
+        departure: Some(time(departure.0, departure.1)),
+        planned_departure: Some(time(departure.0, departure.1)),
+        arrival: Some(time(arrival.0, arrival.1)),
+        planned_arrival: Some(time(arrival.0, arrival.1)),
+        reachable: true,
+        trip_id: None,
+        line: None,
+        direction: None,
+        arrival_platform: None,
+        planned_arrival_platform: None,
+        departure_platform: None,
+        planned_departure_platform: None,
+        frequency: None,
+        cancelled: false,
+        intermediate_locations: vec![],
+        load_factor: None,
+        remarks: vec![],
+        #[cfg(feature = "polylines")]
+        polyline: None,
+        walking: false,
+        transfer: false,
+        distance: None,
+    }
+}
+
+/// Like [`leg`], but on foot.
+pub fn walk(from: &str, to: &str, departure: (u32, u32), arrival: (u32, u32)) -> Leg {
+    Leg {
+        walking: true,
+        ..leg(from, to, departure, arrival)
+    }
+}
+
+pub fn journey(id: &str, legs: Vec<Leg>) -> Journey {
+    Journey {
+        id: id.to_string(),
+        legs,
+        price: None,
+    }
+}
+
+#[derive(Debug)]
+/// Returned by the mocks for everything they do not implement.
+pub struct Unimplemented;
+
+impl std::fmt::Display for Unimplemented {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not implemented by the mock")
+    }
+}
+
+impl std::error::Error for Unimplemented {}
+
+/// A requester that fails all requests.
+pub struct NoRequester;
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl Requester for NoRequester {
+    type Error = Unimplemented;
+
+    async fn get(
+        &self,
+        _url: &url::Url,
+        _body: &[u8],
+        _headers: HashMap<&str, &str>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        Err(Unimplemented)
+    }
+
+    async fn post(
+        &self,
+        _url: &url::Url,
+        _body: &[u8],
+        _headers: HashMap<&str, &str>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        Err(Unimplemented)
+    }
+}
+
+type Respond = dyn Fn(&JourneysOptions) -> JourneysResponse + Send + Sync;
+
+/// A provider answering journey queries using a function, recording the queries.
+///
+/// Everything else fails with [`Unimplemented`].
+pub struct MockProvider {
+    respond: Box<Respond>,
+    requests: Mutex<Vec<(Place, Place, JourneysOptions)>>,
+}
+
+impl MockProvider {
+    pub fn new<F>(respond: F) -> Self
+    where
+        F: Fn(&JourneysOptions) -> JourneysResponse + Send + Sync + 'static,
+    {
+        Self {
+            respond: Box::new(respond),
+            requests: Mutex::new(vec![]),
+        }
+    }
+
+    /// Always respond with the given journeys.
+    pub fn with_journeys(journeys: Vec<Journey>) -> Self {
+        Self::new(move |_| JourneysResponse {
+            earlier_ref: None,
+            later_ref: None,
+            journeys: journeys.clone(),
+        })
+    }
+
+    /// The origin, destination and options of all journey queries so far.
+    pub fn requests(&self) -> Vec<(Place, Place, JourneysOptions)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl Provider<NoRequester> for MockProvider {
+    type Error = Unimplemented;
+
+    async fn journeys(
+        &self,
+        from: Place,
+        to: Place,
+        opts: JourneysOptions,
+    ) -> Result<JourneysResponse, crate::Error<Unimplemented, Unimplemented>> {
+        let response = (self.respond)(&opts);
+        self.requests.lock().unwrap().push((from, to, opts));
+        Ok(response)
+    }
+
+    async fn locations(
+        &self,
+        _opts: LocationsOptions,
+    ) -> Result<LocationsResponse, crate::Error<Unimplemented, Unimplemented>> {
+        Err(crate::Error::Provider(Unimplemented))
+    }
+
+    async fn refresh_journey(
+        &self,
+        _journey: &Journey,
+        _opts: RefreshJourneyOptions,
+    ) -> Result<RefreshJourneyResponse, crate::Error<Unimplemented, Unimplemented>> {
+        Err(crate::Error::Provider(Unimplemented))
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Journey, JourneysOptions, JourneysResponse, Leg, Place, Provider, Requester};
+
+#[derive(Debug, Clone, PartialEq)]
+/// The minimum times required to transfer, see [`Journey::transfers`].
+pub struct TransferTimes {
+    /// The minimum transfer time at stations without a specific one.
+    pub default: Duration,
+    /// Minimum transfer times at specific stations, by [`Station::id`](crate::Station::id).
+    pub stations: HashMap<String, Duration>,
+    /// A transfer is tight if less than this is left on top of the minimum transfer time.
+    pub tight_margin: Duration,
+}
+
+impl Default for TransferTimes {
+    fn default() -> Self {
+        Self::new(Duration::zero())
+    }
+}
+
+impl From<Duration> for TransferTimes {
+    fn from(default: Duration) -> Self {
+        Self::new(default)
+    }
+}
+
+impl TransferTimes {
+    /// Use the given minimum transfer time at all stations.
+    pub fn new(default: Duration) -> Self {
+        Self {
+            default,
+            stations: HashMap::new(),
+            tight_margin: Duration::minutes(2),
+        }
+    }
+
+    /// Use a specific minimum transfer time at the station with the given id.
+    pub fn with_station<S: Into<String>>(mut self, id: S, time: Duration) -> Self {
+        self.stations.insert(id.into(), time);
+        self
+    }
+
+    /// The minimum transfer time at a place.
+    pub fn at(&self, place: &Place) -> Duration {
+        match place {
+            Place::Station(station) => self
+                .stations
+                .get(&station.id)
+                .copied()
+                .unwrap_or(self.default),
+            Place::Location(_) => self.default,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How feasible a [`Transfer`] is.
+pub enum TransferStatus {
+    /// There is enough time to transfer.
+    Safe,
+    /// The minimum transfer time is met, but there is little margin left.
+    Tight,
+    /// The transfer is not possible.
+    Broken,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+/// A transfer between two legs of a [`Journey`].
+pub struct Transfer {
+    /// The index in [`Journey::legs`] of the leg to transfer from.
+    pub from_leg: usize,
+    /// The index in [`Journey::legs`] of the leg to transfer to.
+    pub to_leg: usize,
+    /// Where the transfer starts, i.e. the destination of the leg to transfer from.
+    pub place: Place,
+    /// The time left for transferring, using real-time data where available and excluding walks in between.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration"))]
+    pub available: Option<Duration>,
+    /// The minimum time required to transfer.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::duration_required"))]
+    pub required: Duration,
+    /// How feasible the transfer is.
+    pub status: TransferStatus,
+}
+
+impl Journey {
+    /// Mark the walks between two legs with a line as [transfers](Leg::transfer).
+    ///
+    /// Walks to the first or from the last vehicle are not transfers.
+    pub fn mark_transfers(&mut self) {
+        let vehicles = self
+            .legs
+            .iter()
+            .map(|l| l.line.is_some())
+            .collect::<Vec<_>>();
+        for (i, leg) in self.legs.iter_mut().enumerate() {
+            leg.transfer = leg.walking
+                && vehicles[..i].iter().any(|v| *v)
+                && vehicles[i + 1..].iter().any(|v| *v);
+        }
+    }
+
+    /// Evaluate each transfer between public transport legs of the journey.
+    ///
+    /// Walks in between are taken into account using their planned duration.
+    pub fn transfers(&self, times: &TransferTimes) -> Vec<Transfer> {
+        let mut transfers = vec![];
+        let mut previous: Option<(usize, &Leg)> = None;
+        let mut walking = Duration::zero();
+
+        for (index, leg) in self.legs.iter().enumerate() {
+            if leg.walking || leg.transfer {
+                if let Some(duration) = leg
+                    .planned_arrival
+                    .zip(leg.planned_departure)
+                    .map(|(arrival, departure)| arrival - departure)
+                {
+                    walking += duration;
+                }
+                continue;
+            }
+            if let Some((from_index, from)) = previous {
+                let available = leg
+                    .departure
+                    .or(leg.planned_departure)
+                    .zip(from.arrival.or(from.planned_arrival))
+                    .map(|(departure, arrival)| departure - arrival - walking);
+                let required = times.at(&from.destination);
+                let status = if !leg.reachable || leg.cancelled || from.cancelled {
+                    TransferStatus::Broken
+                } else {
+                    match available {
+                        Some(a) if a < required => TransferStatus::Broken,
+                        Some(a) if a < required + times.tight_margin => TransferStatus::Tight,
+                        _ => TransferStatus::Safe,
+                    }
+                };
+                transfers.push(Transfer {
+                    from_leg: from_index,
+                    to_leg: index,
+                    place: from.destination.clone(),
+                    available,
+                    required,
+                    status,
+                });
+            }
+            previous = Some((index, leg));
+            walking = Duration::zero();
+        }
+
+        transfers
+    }
+}
+
+/// The query for the rest of `journey` from `origin`.
+///
+/// The options are based on the given ones, departing at `departure` unless [`JourneysOptions::departure`] is later, e.g. the current time.
+pub(crate) fn rest_of(
+    journey: &Journey,
+    origin: Place,
+    departure: Option<DateTime<Tz>>,
+    opts: JourneysOptions,
+) -> Option<(Place, Place, JourneysOptions)> {
+    let destination = journey.legs.last()?.destination.clone();
+    let departure = match (departure, opts.departure) {
+        (Some(time), Some(earliest)) => Some(time.max(earliest)),
+        (time, earliest) => time.or(earliest),
+    };
+    Some((
+        origin,
+        destination,
+        JourneysOptions {
+            departure,
+            arrival: None,
+            earlier_than: None,
+            later_than: None,
+            ..opts
+        },
+    ))
+}
+
+impl Transfer {
+    /// The query to re-plan the rest of the journey from the start of this transfer, if it is broken.
+    ///
+    /// The returned origin, destination and options are suitable for [`Provider::journeys`]; the options are based on the given ones, departing when the leg to transfer from arrives unless [`JourneysOptions::departure`] is later.
+    /// If the leg to transfer from is cancelled, its destination cannot be reached and the query departs from its origin instead.
+    pub fn replan_query(
+        &self,
+        journey: &Journey,
+        opts: JourneysOptions,
+    ) -> Option<(Place, Place, JourneysOptions)> {
+        if self.status != TransferStatus::Broken {
+            return None;
+        }
+        let from = journey.legs.get(self.from_leg)?;
+        let (origin, departure) = if from.cancelled {
+            (
+                from.origin.clone(),
+                from.departure.or(from.planned_departure),
+            )
+        } else {
+            (self.place.clone(), from.arrival.or(from.planned_arrival))
+        };
+        rest_of(journey, origin, departure, opts)
+    }
+
+    /// Re-plan the rest of the journey from the start of this transfer, if it is broken.
+    ///
+    /// See [`Transfer::replan_query`] for the query used.
+    pub async fn replan<R: Requester, P: Provider<R>>(
+        &self,
+        provider: &P,
+        journey: &Journey,
+        opts: JourneysOptions,
+    ) -> Result<Option<JourneysResponse>, crate::Error<R::Error, P::Error>> {
+        let Some((from, to, opts)) = self.replan_query(journey, opts) else {
+            return Ok(None);
+        };
+        provider.journeys(from, to, opts).await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{journey, leg, station, time, walk};
+
+    #[test]
+    fn classifies_transfers() {
+        let j = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                leg("b", "c", (10, 30), (10, 50)),
+                walk("c", "d", (10, 50), (10, 55)),
+                leg("d", "e", (10, 58), (11, 20)),
+                leg("e", "f", (11, 22), (11, 40)),
+            ],
+        );
+        let times = TransferTimes::new(Duration::minutes(5)).with_station("e", Duration::zero());
+
+        let transfers = j.transfers(&times);
+        assert_eq!(
+            transfers
+                .iter()
+                .map(|t| (t.from_leg, t.to_leg, t.available, t.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 1, Some(Duration::minutes(10)), TransferStatus::Safe),
+                (1, 3, Some(Duration::minutes(3)), TransferStatus::Broken),
+                (3, 4, Some(Duration::minutes(2)), TransferStatus::Safe),
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_transfer_time_where_the_transfer_starts() {
+        let j = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                walk("b", "c", (10, 20), (10, 25)),
+                leg("c", "d", (10, 30), (10, 50)),
+            ],
+        );
+        let times =
+            TransferTimes::new(Duration::minutes(5)).with_station("b", Duration::minutes(8));
+        let transfers = j.transfers(&times);
+        assert_eq!(transfers[0].place, station("b"));
+        assert_eq!(transfers[0].required, Duration::minutes(8));
+    }
+
+    #[test]
+    fn unreachable_and_tight_transfers() {
+        let mut j = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                leg("b", "c", (10, 26), (10, 50)),
+            ],
+        );
+        let times = TransferTimes::new(Duration::minutes(5));
+        assert_eq!(j.transfers(&times)[0].status, TransferStatus::Tight);
+
+        j.legs[1].reachable = false;
+        assert_eq!(j.transfers(&times)[0].status, TransferStatus::Broken);
+    }
+
+    #[test]
+    fn replans_broken_transfers() {
+        let j = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                leg("b", "c", (10, 30), (10, 50)),
+                walk("c", "d", (10, 50), (10, 55)),
+                leg("d", "e", (10, 58), (11, 20)),
+            ],
+        );
+        let transfers = j.transfers(&TransferTimes::new(Duration::minutes(5)));
+        assert_eq!(transfers[1].status, TransferStatus::Broken);
+
+        let (from, to, opts) = transfers[1]
+            .replan_query(&j, JourneysOptions::default())
+            .unwrap();
+        assert_eq!(from, station("c"));
+        assert_eq!(to, station("e"));
+        assert_eq!(opts.departure, Some(time(10, 50)));
+        assert!(transfers[0]
+            .replan_query(&j, JourneysOptions::default())
+            .is_none());
+
+        // Do not depart before the given time.
+        let (_, _, opts) = transfers[1]
+            .replan_query(
+                &j,
+                JourneysOptions {
+                    departure: Some(time(10, 52)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(opts.departure, Some(time(10, 52)));
+    }
+
+    #[test]
+    fn replans_before_cancelled_legs() {
+        let mut j = journey(
+            "journey",
+            vec![
+                leg("a", "b", (10, 0), (10, 20)),
+                leg("b", "c", (10, 30), (10, 50)),
+            ],
+        );
+        j.legs[0].cancelled = true;
+        let transfers = j.transfers(&TransferTimes::new(Duration::minutes(5)));
+        assert_eq!(transfers[0].status, TransferStatus::Broken);
+
+        let (from, to, opts) = transfers[0]
+            .replan_query(&j, JourneysOptions::default())
+            .unwrap();
+        assert_eq!(from, station("a"));
+        assert_eq!(to, station("c"));
+        assert_eq!(opts.departure, Some(time(10, 0)));
+    }
+}
@@ -69,6 +69,8 @@ pub struct Station {
     pub location: Option<Location>,
     /// The products served on the station.
     pub products: Vec<Product>,
+    /// How accessible the station is, if known.
+    pub accessibility: Option<StationAccessibility>,
 }
 
 impl PartialEq for Station {
@@ -77,6 +79,16 @@ impl PartialEq for Station {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Accessibility information of a [`Station`] or [`Stop`].
+pub struct StationAccessibility {
+    /// Whether the platforms can be reached without steps.
+    pub step_free: Option<bool>,
+    /// Whether there is an elevator.
+    pub elevator: Option<bool>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// A selection of modes.
 pub struct ProductsSelection(HashSet<Mode>);
@@ -163,6 +175,41 @@ pub enum Mode {
     // TODO: Walking?
 }
 
+impl Mode {
+    /// A kebab-case identifier, e.g. `high-speed-train`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Mode::HighSpeedTrain => "high-speed-train",
+            Mode::RegionalTrain => "regional-train",
+            Mode::SuburbanTrain => "suburban-train",
+            Mode::Subway => "subway",
+            Mode::Tram => "tram",
+            Mode::Bus => "bus",
+            Mode::Ferry => "ferry",
+            Mode::Cablecar => "cablecar",
+            Mode::OnDemand => "on-demand",
+            Mode::Unknown => "unknown",
+        }
+    }
+
+    /// The mode with the given [identifier](Mode::id), if any.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "high-speed-train" => Mode::HighSpeedTrain,
+            "regional-train" => Mode::RegionalTrain,
+            "suburban-train" => Mode::SuburbanTrain,
+            "subway" => Mode::Subway,
+            "tram" => Mode::Tram,
+            "bus" => Mode::Bus,
+            "ferry" => Mode::Ferry,
+            "cablecar" => Mode::Cablecar,
+            "on-demand" => Mode::OnDemand,
+            "unknown" => Mode::Unknown,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// Different kinds of loyalty cards supported.
@@ -222,26 +269,22 @@ impl LoyaltyCard {
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 /// What class to travel with.
 pub enum TariffClass {
     /// First class.
     First,
     /// Second class.
+    #[default]
     Second,
 }
 
-impl Default for TariffClass {
-    fn default() -> Self {
-        Self::Second
-    }
-}
-
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 /// How accessible a trip should be.
 pub enum Accessibility {
     /// Accessibility is not required.
+    #[default]
     r#None,
     /// Partial accessibility is required.
     Partial,
@@ -249,10 +292,17 @@ pub enum Accessibility {
     Complete,
 }
 
-impl Default for Accessibility {
-    fn default() -> Self {
-        Self::None
-    }
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How fast the passenger walks.
+pub enum WalkingSpeed {
+    /// Slower than usual, e.g. with heavy luggage.
+    Slow,
+    /// The usual walking speed.
+    #[default]
+    Normal,
+    /// Faster than usual.
+    Fast,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -287,6 +337,18 @@ pub struct Line {
     pub product_name: Option<String>,
 }
 
+#[cfg(feature = "polylines")]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How the [`Leg::polyline`] is shaped.
+pub enum PolylineFormat {
+    /// One `Point` feature per coordinate.
+    #[default]
+    Points,
+    /// A single `LineString` feature, see [`polyline_to_line_string`](crate::polyline_to_line_string).
+    LineString,
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq)]
 /// At what frequency the train drives.
@@ -450,6 +512,8 @@ pub struct Stop {
     pub cancelled: bool,
     /// Remarks specific to this stop.
     pub remarks: Vec<Remark>,
+    /// How accessible the stop is, if known.
+    pub accessibility: Option<StationAccessibility>,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -495,7 +559,7 @@ pub enum RemarkType {
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 /// What to associate a [`Remark`] with.
 pub enum RemarkAssociation {
     /// E.g. bikes allowed, disallowed, limited.
@@ -513,17 +577,12 @@ pub enum RemarkAssociation {
     /// There is no first class on this train.
     OnlySecondClass,
     /// The remark code specifies an association, but this could not yet be decoded.
+    #[default]
     Unknown,
     /// The remark did not specify an association.
     None,
 }
 
-impl Default for RemarkAssociation {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// A remark on a leg or stopover.
@@ -0,0 +1,297 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use rcore::{
+    Accessibility, Journey, JourneyRef, JourneysOptions, JourneysResponse, LocationsOptions,
+    LocationsResponse, Provider, RefreshJourneyOptions, RefreshJourneyResponse, Requester,
+    TransferOptions, WalkingSpeed, REFRESH_RESULTS,
+};
+
+use crate::client::EfaClient;
+use crate::parse::journey::{parse_journey, EfaTripResponse, TripQuery};
+use crate::parse::leg::LegOptions;
+use crate::parse::location::{parse_locations_response, place_to_efa, EfaLocationsResponse};
+use crate::{Error, ParseError};
+
+impl<R: Requester> EfaClient<R> {
+    #[allow(clippy::too_many_arguments)]
+    async fn trip_request(
+        &self,
+        origin: (&str, String),
+        destination: (&str, String),
+        when: DateTime<Tz>,
+        is_departure: bool,
+        results: u64,
+        language: Option<&str>,
+        options: &[(&str, String)],
+    ) -> Result<EfaTripResponse, rcore::Error<R::Error, Error>> {
+        let when = when.with_timezone(&self.profile.timezone());
+        let mut params = vec![
+            ("locationServerActive", "1".to_string()),
+            ("type_origin", origin.0.to_string()),
+            ("name_origin", origin.1),
+            ("type_destination", destination.0.to_string()),
+            ("name_destination", destination.1),
+            ("itdDate", when.format("%Y%m%d").to_string()),
+            ("itdTime", when.format("%H%M").to_string()),
+            (
+                "itdTripDateTimeDepArr",
+                if is_departure { "dep" } else { "arr" }.to_string(),
+            ),
+            ("calcNumberOfTrips", results.to_string()),
+            ("useRealtime", "1".to_string()),
+        ];
+        params.extend(options.iter().cloned());
+        self.request("XML_TRIP_REQUEST2", language, params).await
+    }
+}
+
+fn parse_ref(r: &str) -> Result<DateTime<Utc>, Error> {
+    Ok(DateTime::parse_from_rfc3339(r)
+        .map_err(ParseError::from)?
+        .with_timezone(&Utc))
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl<R: Requester> Provider<R> for EfaClient<R> {
+    type Error = crate::Error;
+
+    async fn locations(
+        &self,
+        opts: LocationsOptions,
+    ) -> Result<LocationsResponse, rcore::Error<R::Error, Self::Error>> {
+        let data: EfaLocationsResponse = self
+            .request(
+                "XML_STOPFINDER_REQUEST",
+                opts.language.as_deref(),
+                vec![
+                    ("locationServerActive", "1".to_string()),
+                    ("type_sf", "any".to_string()),
+                    ("name_sf", opts.query),
+                    ("anyMaxSizeHitList", opts.results.to_string()),
+                ],
+            )
+            .await?;
+
+        let mut locations = parse_locations_response(self.profile.as_ref(), data)
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        locations.truncate(opts.results as usize);
+        Ok(locations)
+    }
+
+    async fn journeys(
+        &self,
+        from: rcore::Place,
+        to: rcore::Place,
+        opts: JourneysOptions,
+    ) -> Result<JourneysResponse, rcore::Error<R::Error, Self::Error>> {
+        let timezone = self.profile.timezone();
+        let (when, is_departure) = match (
+            opts.departure,
+            opts.arrival,
+            opts.later_than.as_deref(),
+            opts.earlier_than.as_deref(),
+        ) {
+            (_, _, Some(_), Some(_)) => Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                "earlier_than and later_than are mutually exclusive".to_string(),
+            )))?,
+            (_, _, Some(r), None) => (
+                parse_ref(r)
+                    .map_err(rcore::Error::Provider)?
+                    .with_timezone(&timezone),
+                true,
+            ),
+            (_, _, None, Some(r)) => (
+                parse_ref(r)
+                    .map_err(rcore::Error::Provider)?
+                    .with_timezone(&timezone),
+                false,
+            ),
+            (Some(_), Some(_), None, None) => {
+                Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                    "departure and arrival are mutually exclusive".to_string(),
+                )))?
+            }
+            (Some(departure), None, None, None) => (departure.with_timezone(&timezone), true),
+            (None, Some(arrival), None, None) => (arrival.with_timezone(&timezone), false),
+            (None, None, None, None) => (Utc::now().with_timezone(&timezone), true),
+        };
+
+        if !opts.avoid.is_empty() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "avoiding places".to_string(),
+            )))?
+        }
+        if opts.via.len() > 1 {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "more than one via place".to_string(),
+            )))?
+        }
+        if opts.via.iter().any(|v| v.pass_through) {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "passing through a via place without stopping".to_string(),
+            )))?
+        }
+        if opts.transfer_time != Duration::zero() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "minimum transfer time".to_string(),
+            )))?
+        }
+        if opts.max_walking_distance.is_some() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "maximum walking distance".to_string(),
+            )))?
+        }
+
+        let mut options = vec![];
+        if let Some(via) = opts.via.first() {
+            let (r#type, name) = place_to_efa(&via.place);
+            options.push(("type_via", r#type.to_string()));
+            options.push(("name_via", name));
+            if let Some(dwell_time) = via.min_dwell_time {
+                options.push(("dwellTime", dwell_time.num_minutes().to_string()));
+            }
+        }
+        if let TransferOptions::Limited(transfers) = opts.transfers {
+            options.push(("maxChanges", transfers.to_string()));
+        }
+        // EFA has no minimum transfer time, only the speed of walking for changes.
+        options.push((
+            "changeSpeed",
+            match opts.walking_speed {
+                WalkingSpeed::Slow => "slow",
+                WalkingSpeed::Normal => "normal",
+                WalkingSpeed::Fast => "fast",
+            }
+            .to_string(),
+        ));
+        options.extend(self.profile.products_to_efa(&opts.products));
+
+        let mut impaired = vec![];
+        match opts.accessibility {
+            Accessibility::None => {}
+            Accessibility::Partial => impaired.push("lowPlatformVhcl"),
+            Accessibility::Complete => impaired.push("wheelchair"),
+        }
+        if opts.avoid_stairs {
+            impaired.push("noSolidStairs");
+        }
+        if opts.avoid_escalators {
+            impaired.push("noEscalators");
+        }
+        if !impaired.is_empty() {
+            options.push(("imparedOptionsActive", "1".to_string()));
+            options.extend(impaired.into_iter().map(|o| (o, "on".to_string())));
+        }
+        if opts.bike_friendly {
+            options.push(("bikeTakeAlong", "1".to_string()));
+        }
+
+        let data = self
+            .trip_request(
+                place_to_efa(&from),
+                place_to_efa(&to),
+                when,
+                is_departure,
+                opts.results,
+                opts.language.as_deref(),
+                &options,
+            )
+            .await?;
+
+        let leg_options = LegOptions {
+            stopovers: opts.stopovers,
+            polyline: opts.polylines.then_some(opts.polyline_format),
+        };
+        let mut journeys = data
+            .journeys
+            .iter()
+            .map(|j| {
+                parse_journey(
+                    self.profile.as_ref(),
+                    j,
+                    leg_options,
+                    opts.tariff_class,
+                    &options,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        if !opts.tickets {
+            journeys.iter_mut().for_each(|j| j.price = None);
+        }
+
+        // Search again a minute after the last departure or before the first arrival.
+        let later_ref = journeys
+            .iter()
+            .filter_map(|j| j.legs.first()?.planned_departure)
+            .max()
+            .map(|t| (t + Duration::minutes(1)).to_rfc3339());
+        let earlier_ref = journeys
+            .iter()
+            .filter_map(|j| j.legs.last()?.planned_arrival)
+            .min()
+            .map(|t| (t - Duration::minutes(1)).to_rfc3339());
+
+        Ok(JourneysResponse {
+            earlier_ref,
+            later_ref,
+            journeys,
+        })
+    }
+
+    async fn refresh_journey(
+        &self,
+        journey: &Journey,
+        opts: RefreshJourneyOptions,
+    ) -> Result<RefreshJourneyResponse, rcore::Error<R::Error, Self::Error>> {
+        let journey_ref = JourneyRef::<TripQuery>::from_id(&journey.id)
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        let query = &journey_ref.query;
+        let departure = parse_ref(&journey_ref.departure).map_err(rcore::Error::Provider)?;
+        let options = query
+            .options
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.clone()))
+            .collect::<Vec<_>>();
+
+        let data = self
+            .trip_request(
+                (&query.origin.0, query.origin.1.clone()),
+                (&query.destination.0, query.destination.1.clone()),
+                departure.with_timezone(&self.profile.timezone()),
+                true,
+                REFRESH_RESULTS,
+                opts.language.as_deref(),
+                &options,
+            )
+            .await?;
+
+        let leg_options = LegOptions {
+            stopovers: opts.stopovers,
+            polyline: opts.polylines.then_some(opts.polyline_format),
+        };
+        let journeys = data
+            .journeys
+            .iter()
+            .map(|j| {
+                parse_journey(
+                    self.profile.as_ref(),
+                    j,
+                    leg_options,
+                    opts.tariff_class,
+                    &options,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        let mut refreshed = journey_ref
+            .find(journeys)
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        if !opts.tickets {
+            refreshed.price = None;
+        }
+        Ok(refreshed)
+    }
+}
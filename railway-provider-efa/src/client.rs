@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::debug;
+use rcore::{Requester, RequesterBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::{Error, Profile};
+
+/// The codes of the messages EFA answers with if nothing matched the request, which are no errors for us.
+const NO_RESULTS_CODES: &[i64] = &[-8010, -4000];
+
+#[derive(Clone)]
+pub struct EfaClient<R: Requester> {
+    pub(crate) profile: Arc<Box<dyn Profile>>,
+    requester: Arc<R>,
+}
+
+impl<R: Requester> EfaClient<R> {
+    pub fn new<P: 'static + Profile, RB: RequesterBuilder<Requester = R>>(
+        profile: P,
+        requester: RB,
+    ) -> Self {
+        EfaClient {
+            profile: Arc::new(Box::new(profile)),
+            requester: Arc::new(requester.build()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EfaSystemMessage {
+    r#type: String,
+    #[serde(default)]
+    code: i64,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EfaResponse<T> {
+    #[serde(default)]
+    system_messages: Vec<EfaSystemMessage>,
+    #[serde(flatten)]
+    data: T,
+}
+
+impl<R: Requester> EfaClient<R> {
+    /// Request the given endpoint, e.g. `XML_TRIP_REQUEST2`, in the rapidJSON format of EFA.
+    pub(crate) async fn request<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        language: Option<&str>,
+        params: Vec<(&str, String)>,
+    ) -> Result<T, rcore::Error<R::Error, crate::Error>> {
+        let mut query = vec![
+            ("outputFormat", "rapidJSON".to_string()),
+            ("coordOutputFormat", "WGS84[dd.ddddd]".to_string()),
+            (
+                "language",
+                language
+                    .unwrap_or_else(|| self.profile.language())
+                    .to_string(),
+            ),
+        ];
+        query.extend(params);
+        self.profile.prepare_query(&mut query);
+
+        let mut url = url::Url::parse(self.profile.url())
+            .and_then(|u| u.join(endpoint))
+            .map_err(|e| rcore::Error::Provider(crate::Error::InvalidInput(e.to_string())))?;
+        url.query_pairs_mut().extend_pairs(query);
+        debug!("Request: {}", url);
+
+        let mut headers = HashMap::new();
+        headers.insert("Accept", "application/json");
+        self.profile.prepare_headers(&mut headers);
+
+        let bytes = self
+            .requester
+            .get(&url, &[], headers)
+            .await
+            .map_err(rcore::Error::Request)?;
+
+        let response: EfaResponse<T> =
+            serde_json::from_slice(&bytes).map_err(|e| rcore::Error::Provider(e.into()))?;
+        if let Some(error) = response
+            .system_messages
+            .into_iter()
+            .find(|m| m.r#type == "error" && !NO_RESULTS_CODES.contains(&m.code))
+        {
+            return Err(rcore::Error::Provider(Error::Efa {
+                text: if error.text.is_empty() {
+                    format!("Code {}", error.code)
+                } else {
+                    error.text
+                },
+                code: error.code,
+            }));
+        }
+        Ok(response.data)
+    }
+}
@@ -0,0 +1,26 @@
+pub use rcore::{ParseError, ParseResult};
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error("{source}")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+    #[error("{source}")]
+    Parse {
+        #[from]
+        source: ParseError,
+    },
+    #[error("{text}")]
+    Efa { code: i64, text: String },
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    NotFound(#[from] rcore::JourneyNotFound),
+    #[error("not supported by EFA: {0}")]
+    Unsupported(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
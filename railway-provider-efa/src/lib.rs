@@ -0,0 +1,12 @@
+#![doc = include_str!("../README.md")]
+
+pub mod api;
+pub mod client;
+pub mod error;
+pub mod parse;
+pub mod profile;
+
+pub use client::EfaClient;
+pub use error::{Error, ParseError, ParseResult, Result};
+pub use profile::Profile;
+use rcore::*;
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parse::leg::{parse_leg, EfaLeg, LegOptions};
+use crate::parse::location::location_to_efa;
+use crate::{Journey, JourneyRef, ParseResult, Price, Profile, TariffClass};
+
+#[derive(Debug, Deserialize)]
+pub struct EfaJourney {
+    pub legs: Vec<EfaLeg>,
+    pub fare: Option<EfaFare>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EfaFare {
+    #[serde(default)]
+    pub tickets: Vec<EfaTicket>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaTicket {
+    pub price_brutto: Option<f64>,
+    pub currency: Option<String>,
+    /// E.g. `ADULT` or `CHILD`.
+    pub person: Option<String>,
+    /// E.g. `FIRST` or `SECOND`.
+    pub traveller_class: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EfaTripResponse {
+    #[serde(default)]
+    pub journeys: Vec<EfaJourney>,
+}
+
+/// The query stored in the [`JourneyRef`] of a journey.
+///
+/// EFA has no way to reconstruct a journey, so it is searched again with the same options at the planned departure.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub(crate) struct TripQuery {
+    /// The type and name of the origin in requests.
+    pub origin: (String, String),
+    pub destination: (String, String),
+    /// The parameters of the search besides the places and time, e.g. the via place and products.
+    pub options: Vec<(String, String)>,
+}
+
+fn parse_price<P: Profile + ?Sized>(
+    profile: &P,
+    fare: &EfaFare,
+    tariff_class: TariffClass,
+) -> Option<Price> {
+    let class = match tariff_class {
+        TariffClass::First => "FIRST",
+        TariffClass::Second => "SECOND",
+    };
+    fare.tickets
+        .iter()
+        .filter(|t| t.person.as_deref().unwrap_or("ADULT") == "ADULT")
+        .find(|t| t.traveller_class.as_deref().unwrap_or("SECOND") == class)
+        .and_then(|t| {
+            Some(Price {
+                amount: t.price_brutto?,
+                currency: t
+                    .currency
+                    .clone()
+                    .unwrap_or_else(|| profile.price_currency().to_string()),
+            })
+        })
+}
+
+pub(crate) fn parse_journey<P: Profile + ?Sized>(
+    profile: &P,
+    data: &EfaJourney,
+    options: LegOptions,
+    tariff_class: TariffClass,
+    query_options: &[(&str, String)],
+) -> ParseResult<Journey> {
+    let legs = data
+        .legs
+        .iter()
+        .map(|l| parse_leg(profile, l, options))
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    let (first, last) = data
+        .legs
+        .first()
+        .zip(data.legs.last())
+        .ok_or("journey without legs")?;
+    let query = TripQuery {
+        origin: location_to_efa(&first.origin)
+            .map(|(t, n)| (t.to_string(), n))
+            .ok_or("unknown origin of journey")?,
+        destination: location_to_efa(&last.destination)
+            .map(|(t, n)| (t.to_string(), n))
+            .ok_or("unknown destination of journey")?,
+        options: query_options
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect(),
+    };
+    let departure = first
+        .origin
+        .departure_time_planned
+        .clone()
+        .ok_or("missing departure of journey")?;
+
+    Ok(Journey {
+        id: JourneyRef::new(query, departure, &legs)
+            .to_id()
+            .map_err(|e| format!("failed to serialize journey id: {}", e))?,
+        legs,
+        price: data
+            .fare
+            .as_ref()
+            .and_then(|f| parse_price(profile, f, tariff_class)),
+    })
+}
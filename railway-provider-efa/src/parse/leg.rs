@@ -0,0 +1,255 @@
+use geojson::{Feature, FeatureCollection, Geometry, Value};
+use rcore::PolylineFormat;
+use serde::Deserialize;
+
+use crate::parse::location::{parse_place, EfaLocation};
+use crate::parse::parse_time;
+use crate::{
+    IntermediateLocation, Leg, Line, LoadFactor, Operator, ParseResult, Profile, Remark,
+    RemarkAssociation, RemarkType, Stop,
+};
+
+/// Product classes of EFA for legs without a vehicle, in addition to the classes of the profile.
+const STAY_SEATED: u8 = 97;
+const SECURED_CONNECTION: u8 = 98;
+const FOOTPATH_TRANSFER: u8 = 99;
+const FOOTPATH: u8 = 100;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaLeg {
+    pub origin: EfaLocation,
+    pub destination: EfaLocation,
+    pub transportation: Option<EfaTransportation>,
+    /// The stops of the leg, including the origin and destination.
+    #[serde(default)]
+    pub stop_sequence: Vec<EfaLocation>,
+    /// The latitude and longitude of the points of the path.
+    #[serde(default)]
+    pub coords: Vec<[f64; 2]>,
+    #[serde(default)]
+    pub infos: Vec<EfaInfo>,
+    #[serde(default)]
+    pub hints: Vec<EfaHint>,
+    #[serde(default)]
+    pub realtime_status: Vec<String>,
+    pub distance: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaTransportation {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub disassembled_name: Option<String>,
+    pub number: Option<String>,
+    pub product: Option<EfaProduct>,
+    pub operator: Option<EfaOperator>,
+    pub destination: Option<EfaLocation>,
+    #[serde(default)]
+    pub properties: EfaTransportationProperties,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EfaProduct {
+    pub class: u8,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EfaOperator {
+    pub id: Option<String>,
+    pub code: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaTransportationProperties {
+    pub train_number: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EfaInfo {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaHint {
+    pub content: String,
+    pub provider_code: Option<String>,
+}
+
+/// How the leg is parsed, following the options of the request.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LegOptions {
+    pub stopovers: bool,
+    pub polyline: Option<PolylineFormat>,
+}
+
+fn parse_load_factor(occupancy: &str) -> Option<LoadFactor> {
+    match occupancy {
+        "MANY_SEATS" => Some(LoadFactor::LowToMedium),
+        "FEW_SEATS" => Some(LoadFactor::High),
+        "STANDING_ONLY" => Some(LoadFactor::VeryHigh),
+        "FULL" => Some(LoadFactor::ExceptionallyHigh),
+        _ => None,
+    }
+}
+
+fn parse_remarks(leg: &EfaLeg, trip_id: Option<&String>) -> Vec<Remark> {
+    let infos = leg.infos.iter().filter_map(|info| {
+        let text = info.content.clone().or_else(|| info.subtitle.clone())?;
+        Some(Remark {
+            code: info.id.clone().unwrap_or_default(),
+            text,
+            r#type: RemarkType::Status,
+            association: RemarkAssociation::None,
+            summary: info.title.clone().or_else(|| info.subtitle.clone()),
+            trip_id: trip_id.cloned(),
+        })
+    });
+    let hints = leg.hints.iter().map(|hint| Remark {
+        code: hint.provider_code.clone().unwrap_or_default(),
+        text: hint.content.clone(),
+        r#type: RemarkType::Hint,
+        association: RemarkAssociation::Unknown,
+        summary: None,
+        trip_id: trip_id.cloned(),
+    });
+    infos.chain(hints).collect()
+}
+
+fn parse_stop<P: Profile + ?Sized>(profile: &P, stop: &EfaLocation) -> ParseResult<Stop> {
+    let timezone = profile.timezone();
+    let planned_departure = parse_time(stop.departure_time_planned.as_deref(), timezone)?;
+    let planned_arrival = parse_time(stop.arrival_time_planned.as_deref(), timezone)?;
+    Ok(Stop {
+        place: parse_place(profile, stop)?,
+        departure: parse_time(stop.departure_time_estimated.as_deref(), timezone)?
+            .or(planned_departure),
+        planned_departure,
+        arrival: parse_time(stop.arrival_time_estimated.as_deref(), timezone)?.or(planned_arrival),
+        planned_arrival,
+        arrival_platform: stop.platform(),
+        planned_arrival_platform: stop.planned_platform(),
+        departure_platform: stop.platform(),
+        planned_departure_platform: stop.planned_platform(),
+        cancelled: false,
+        remarks: vec![],
+        accessibility: None,
+    })
+}
+fn parse_polyline(coords: &[[f64; 2]], format: PolylineFormat) -> FeatureCollection {
+    let polyline = FeatureCollection {
+        features: coords
+            .iter()
+            .map(|[latitude, longitude]| {
+                Feature::from(Geometry::new(Value::Point(vec![*longitude, *latitude])))
+            })
+            .collect(),
+        bbox: None,
+        foreign_members: None,
+    };
+    match format {
+        PolylineFormat::Points => polyline,
+        PolylineFormat::LineString => rcore::polyline_to_line_string(&polyline),
+    }
+}
+
+pub(crate) fn parse_leg<P: Profile + ?Sized>(
+    profile: &P,
+    leg: &EfaLeg,
+    options: LegOptions,
+) -> ParseResult<Leg> {
+    let timezone = profile.timezone();
+    let class = leg
+        .transportation
+        .as_ref()
+        .and_then(|t| t.product.as_ref())
+        .map(|p| p.class);
+    // Classes from 97 on are individual transport like walking, taxis or bikes.
+    let transportation = leg
+        .transportation
+        .as_ref()
+        .filter(|_| class.is_some_and(|c| c < STAY_SEATED));
+
+    let trip_id = transportation.and_then(|t| t.id.clone());
+    let line = transportation.map(|t| {
+        let product = profile.parse_product(class.unwrap_or_default());
+        Line {
+            name: t
+                .disassembled_name
+                .clone()
+                .or_else(|| t.number.clone())
+                .or_else(|| t.name.clone()),
+            fahrt_nr: t.properties.train_number.clone(),
+            mode: product.mode.clone(),
+            product,
+            operator: t.operator.as_ref().and_then(|o| {
+                Some(Operator {
+                    id: o.id.clone().or_else(|| o.code.clone())?,
+                    name: o.name.clone()?,
+                })
+            }),
+            product_name: t.product.as_ref().and_then(|p| p.name.clone()),
+        }
+    });
+
+    let planned_departure = parse_time(leg.origin.departure_time_planned.as_deref(), timezone)?;
+    let planned_arrival = parse_time(leg.destination.arrival_time_planned.as_deref(), timezone)?;
+
+    let intermediate_locations = if options.stopovers && leg.stop_sequence.len() > 2 {
+        leg.stop_sequence[1..leg.stop_sequence.len() - 1]
+            .iter()
+            .map(|s| parse_stop(profile, s).map(IntermediateLocation::Stop))
+            .collect::<ParseResult<_>>()?
+    } else {
+        vec![]
+    };
+
+    Ok(Leg {
+        origin: parse_place(profile, &leg.origin)?,
+        destination: parse_place(profile, &leg.destination)?,
+        departure: parse_time(leg.origin.departure_time_estimated.as_deref(), timezone)?
+            .or(planned_departure),
+        planned_departure,
+        arrival: parse_time(leg.destination.arrival_time_estimated.as_deref(), timezone)?
+            .or(planned_arrival),
+        planned_arrival,
+        reachable: true,
+        remarks: parse_remarks(leg, trip_id.as_ref()),
+        trip_id,
+        line,
+        direction: transportation
+            .and_then(|t| t.destination.as_ref())
+            .and_then(|d| d.name.clone()),
+        arrival_platform: leg.destination.platform(),
+        planned_arrival_platform: leg.destination.planned_platform(),
+        departure_platform: leg.origin.platform(),
+        planned_departure_platform: leg.origin.planned_platform(),
+        frequency: None,
+        cancelled: leg.realtime_status.iter().any(|s| s == "TRIP_CANCELLED"),
+        intermediate_locations,
+        load_factor: leg
+            .origin
+            .properties
+            .occupancy
+            .as_deref()
+            .and_then(parse_load_factor),
+        polyline: options
+            .polyline
+            .filter(|_| !leg.coords.is_empty())
+            .map(|format| parse_polyline(&leg.coords, format)),
+        walking: matches!(class, Some(FOOTPATH_TRANSFER) | Some(FOOTPATH)),
+        transfer: matches!(
+            class,
+            Some(STAY_SEATED) | Some(SECURED_CONNECTION) | Some(FOOTPATH_TRANSFER)
+        ),
+        distance: leg.distance,
+    })
+}
@@ -0,0 +1,174 @@
+use serde::Deserialize;
+
+use crate::{Location, ParseResult, Place, Product, Profile, Station};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaLocation {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// E.g. `stop`, `platform`, `street`, `address`, `poi` or `locality`.
+    pub r#type: Option<String>,
+    /// The latitude and longitude.
+    pub coord: Option<[f64; 2]>,
+    #[serde(default)]
+    pub product_classes: Vec<u8>,
+    pub parent: Option<Box<EfaLocation>>,
+    #[serde(default)]
+    pub match_quality: i64,
+    #[serde(default)]
+    pub properties: EfaLocationProperties,
+    pub departure_time_planned: Option<String>,
+    pub departure_time_estimated: Option<String>,
+    pub arrival_time_planned: Option<String>,
+    pub arrival_time_estimated: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaLocationProperties {
+    pub platform: Option<String>,
+    pub platform_name: Option<String>,
+    /// Only set if the platform changed.
+    pub planned_platform_name: Option<String>,
+    pub occupancy: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EfaLocationsResponse {
+    #[serde(default)]
+    pub locations: Vec<EfaLocation>,
+}
+
+impl EfaLocation {
+    /// The current platform, falling back to the planned one.
+    pub(crate) fn platform(&self) -> Option<String> {
+        self.properties
+            .platform_name
+            .clone()
+            .or_else(|| self.properties.platform.clone())
+    }
+
+    pub(crate) fn planned_platform(&self) -> Option<String> {
+        self.properties
+            .planned_platform_name
+            .clone()
+            .or_else(|| self.platform())
+    }
+
+    /// The stop this platform belongs to, or the location itself.
+    fn stop(&self) -> &EfaLocation {
+        match (self.r#type.as_deref(), &self.parent) {
+            (Some("platform"), Some(parent)) if parent.r#type.as_deref() == Some("stop") => parent,
+            _ => self,
+        }
+    }
+}
+
+pub(crate) fn parse_place<P: Profile + ?Sized>(
+    profile: &P,
+    location: &EfaLocation,
+) -> ParseResult<Place> {
+    let stop = location.stop();
+    // Stops referenced by a platform often come without coordinates.
+    let coord = stop.coord.or(location.coord);
+    let (latitude, longitude) = coord
+        .map(|[latitude, longitude]| (latitude as f32, longitude as f32))
+        .unzip();
+
+    match stop.r#type.as_deref() {
+        Some("stop") | Some("platform") => {
+            let id = stop
+                .id
+                .clone()
+                .ok_or_else(|| format!("missing id of stop {:?}", stop.name))?;
+            let mut products: Vec<Product> = vec![];
+            for product in stop
+                .product_classes
+                .iter()
+                .map(|c| profile.parse_product(*c))
+            {
+                if !products.contains(&product) {
+                    products.push(product);
+                }
+            }
+            Ok(Place::Station(Station {
+                id: id.clone(),
+                name: stop.name.clone(),
+                location: latitude
+                    .zip(longitude)
+                    .map(|(latitude, longitude)| Location::Point {
+                        id: Some(id),
+                        name: None,
+                        poi: None,
+                        latitude,
+                        longitude,
+                    }),
+                products,
+                accessibility: None,
+            }))
+        }
+        Some("street") | Some("address") | Some("singlehouse") => {
+            Ok(Place::Location(Location::Address {
+                address: stop.name.clone().unwrap_or_default(),
+                latitude: latitude.ok_or("missing coordinates of address")?,
+                longitude: longitude.ok_or("missing coordinates of address")?,
+            }))
+        }
+        r#type => Ok(Place::Location(Location::Point {
+            id: stop.id.clone(),
+            name: stop.name.clone(),
+            poi: Some(r#type == Some("poi")),
+            latitude: latitude.ok_or("missing coordinates of location")?,
+            longitude: longitude.ok_or("missing coordinates of location")?,
+        })),
+    }
+}
+
+pub(crate) fn parse_locations_response<P: Profile + ?Sized>(
+    profile: &P,
+    mut data: EfaLocationsResponse,
+) -> ParseResult<Vec<Place>> {
+    data.locations.sort_by_key(|l| -l.match_quality);
+    data.locations
+        .iter()
+        .map(|l| parse_place(profile, l))
+        .collect()
+}
+
+/// The type and name EFA identifies the place with in requests, e.g. `type_origin` and `name_origin`.
+pub(crate) fn place_to_efa(place: &Place) -> (&'static str, String) {
+    match place {
+        Place::Station(station) => ("stop", station.id.clone()),
+        Place::Location(Location::Point { id: Some(id), .. }) => ("any", id.clone()),
+        Place::Location(
+            Location::Point {
+                latitude,
+                longitude,
+                ..
+            }
+            | Location::Address {
+                latitude,
+                longitude,
+                ..
+            },
+        ) => (
+            "coord",
+            format!("{:.5}:{:.5}:WGS84[dd.ddddd]", longitude, latitude),
+        ),
+    }
+}
+
+/// The type and name of the location of a leg, as [`place_to_efa`] for the parsed place.
+pub(crate) fn location_to_efa(location: &EfaLocation) -> Option<(&'static str, String)> {
+    let stop = location.stop();
+    match (stop.r#type.as_deref(), &stop.id, location.coord) {
+        (Some("stop"), Some(id), _) => Some(("stop", id.clone())),
+        (_, _, Some([latitude, longitude])) => Some((
+            "coord",
+            format!("{:.5}:{:.5}:WGS84[dd.ddddd]", longitude, latitude),
+        )),
+        (_, Some(id), None) => Some(("any", id.clone())),
+        _ => None,
+    }
+}
@@ -0,0 +1,16 @@
+//! Parsing the rapidJSON responses of EFA.
+
+pub mod journey;
+pub mod leg;
+pub mod location;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::ParseResult;
+
+/// Parse a time like `2024-10-01T05:45:00Z`, which EFA always returns in UTC.
+pub(crate) fn parse_time(time: Option<&str>, timezone: Tz) -> ParseResult<Option<DateTime<Tz>>> {
+    time.map(|t| Ok(DateTime::parse_from_rfc3339(t)?.with_timezone(&timezone)))
+        .transpose()
+}
@@ -0,0 +1,65 @@
+use crate::{Product, Profile};
+
+mod products {
+    use crate::profile::products::*;
+    use crate::{Mode, Product};
+    use std::borrow::Cow;
+
+    /// Including the Pöstlingbergbahn, which is a tram.
+    pub const STRASSENBAHN: Product = Product {
+        mode: Mode::Tram,
+        name: Cow::Borrowed("Straßenbahn"),
+        short: Cow::Borrowed("Bim"),
+    };
+
+    pub const PRODUCTS: &[&Product] = &[
+        &ZUG,
+        &S_BAHN,
+        &Product::unknown(),
+        &Product::unknown(),
+        &STRASSENBAHN,
+        &STADTBUS,
+        &REGIONALBUS,
+        &SCHNELLBUS,
+        &SEILBAHN,
+        &SCHIFF,
+        &RUFBUS,
+    ];
+}
+
+/// Linz AG Linien.
+#[derive(Debug)]
+pub struct LinzProfile;
+
+impl Profile for LinzProfile {
+    fn url(&self) -> &'static str {
+        "https://www.linzag.at/linz2/"
+    }
+    fn timezone(&self) -> chrono_tz::Tz {
+        chrono_tz::Europe::Vienna
+    }
+
+    fn products(&self) -> &'static [&'static Product] {
+        products::PRODUCTS
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use crate::profile::test::check_search;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search() -> Result<(), Box<dyn Error>> {
+        check_search(
+            LinzProfile {},
+            "linz",
+            "Linz Hbf",
+            "Linz/Donau, Hauptbahnhof",
+        )
+        .await
+    }
+}
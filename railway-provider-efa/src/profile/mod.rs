@@ -0,0 +1,158 @@
+#[cfg(feature = "linz-profile")]
+pub mod linz;
+#[cfg(feature = "mvv-profile")]
+pub mod mvv;
+#[cfg(feature = "vrr-profile")]
+pub mod vrr;
+#[cfg(feature = "vvs-profile")]
+pub mod vvs;
+// ADD PROFILE HERE
+
+mod names;
+pub mod products;
+pub use names::*;
+
+use std::collections::HashMap;
+
+use rcore::ProductsSelection;
+
+use crate::Product;
+
+/// An instance of EFA.
+pub trait Profile: Send + Sync {
+    /// The base URL the endpoints like `XML_TRIP_REQUEST2` are relative to, ending with a slash.
+    fn url(&self) -> &'static str;
+    fn timezone(&self) -> chrono_tz::Tz;
+    fn language(&self) -> &'static str {
+        "de"
+    }
+    fn price_currency(&self) -> &'static str {
+        "EUR"
+    }
+
+    /// The products of the instance, indexed by the EFA product class, e.g. `0` for trains.
+    fn products(&self) -> &'static [&'static Product];
+
+    fn prepare_query(&self, _query: &mut Vec<(&str, String)>) {}
+    fn prepare_headers(&self, _headers: &mut HashMap<&str, &str>) {}
+
+    /// The query parameters restricting a journey search to the selected products.
+    fn products_to_efa(&self, selection: &ProductsSelection) -> Vec<(&'static str, String)> {
+        let products = self.products();
+        if products.iter().all(|p| selection.contains(&p.mode)) {
+            return vec![];
+        }
+
+        let mut result = vec![("includedMeans", "checkbox".to_string())];
+        for (class, product) in products.iter().enumerate() {
+            if selection.contains(&product.mode) {
+                result.push((INCLUDED_MEANS[class], "on".to_string()));
+            }
+        }
+        result
+    }
+
+    fn parse_product(&self, class: u8) -> Product {
+        self.products()
+            .get(usize::from(class))
+            .map(|p| (*p).clone())
+            .unwrap_or(Product::unknown())
+    }
+}
+
+/// The query parameters including the product classes, as the keys of the query have to be static.
+const INCLUDED_MEANS: [&str; 20] = [
+    "inclMOT_0",
+    "inclMOT_1",
+    "inclMOT_2",
+    "inclMOT_3",
+    "inclMOT_4",
+    "inclMOT_5",
+    "inclMOT_6",
+    "inclMOT_7",
+    "inclMOT_8",
+    "inclMOT_9",
+    "inclMOT_10",
+    "inclMOT_11",
+    "inclMOT_12",
+    "inclMOT_13",
+    "inclMOT_14",
+    "inclMOT_15",
+    "inclMOT_16",
+    "inclMOT_17",
+    "inclMOT_18",
+    "inclMOT_19",
+];
+
+impl<T: Profile + ?Sized> Profile for Box<T> {
+    fn url(&self) -> &'static str {
+        (**self).url()
+    }
+    fn timezone(&self) -> chrono_tz::Tz {
+        (**self).timezone()
+    }
+    fn language(&self) -> &'static str {
+        (**self).language()
+    }
+    fn price_currency(&self) -> &'static str {
+        (**self).price_currency()
+    }
+    fn products(&self) -> &'static [&'static Product] {
+        (**self).products()
+    }
+    fn prepare_query(&self, query: &mut Vec<(&str, String)>) {
+        (**self).prepare_query(query)
+    }
+    fn prepare_headers(&self, headers: &mut HashMap<&str, &str>) {
+        (**self).prepare_headers(headers)
+    }
+    fn products_to_efa(&self, selection: &ProductsSelection) -> Vec<(&'static str, String)> {
+        (**self).products_to_efa(selection)
+    }
+    fn parse_product(&self, class: u8) -> Product {
+        (**self).parse_product(class)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::{client::EfaClient, Location, Place, Profile};
+    use rcore::{LocationsOptions, Provider, ReplayRequester};
+
+    /// A client answering with the responses recorded in `fixtures/<name>`.
+    pub fn client<P: Profile + 'static>(profile: P, name: &str) -> EfaClient<ReplayRequester> {
+        EfaClient::new(
+            profile,
+            ReplayRequester::new(format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)),
+        )
+    }
+
+    pub async fn check_search<S: AsRef<str>, P: Profile + 'static>(
+        profile: P,
+        name: &str,
+        search: S,
+        expected: S,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let locations = client(profile, name)
+            .locations(LocationsOptions {
+                query: search.as_ref().to_string(),
+                ..Default::default()
+            })
+            .await?;
+        let results = locations
+            .into_iter()
+            .flat_map(|p| match p {
+                Place::Station(s) => s.name,
+                Place::Location(Location::Address { address, .. }) => Some(address),
+                Place::Location(Location::Point { name, .. }) => name,
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            results.iter().any(|s| s == expected.as_ref()),
+            "expected {} to be contained in {:#?}",
+            expected.as_ref(),
+            results
+        );
+        Ok(())
+    }
+}
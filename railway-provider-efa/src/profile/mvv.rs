@@ -0,0 +1,57 @@
+use crate::{Product, Profile};
+
+mod products {
+    use crate::profile::products::*;
+    use crate::Product;
+
+    pub const PRODUCTS: &[&Product] = &[
+        &ZUG,
+        &S_BAHN,
+        &U_BAHN,
+        &Product::unknown(),
+        &STRASSENBAHN,
+        &STADTBUS,
+        &REGIONALBUS,
+        &SCHNELLBUS,
+        &SEILBAHN,
+        &SCHIFF,
+        &RUFBUS,
+        &Product::unknown(),
+        &Product::unknown(),
+        &REGIONALZUG,
+        &FERNZUG,
+        &INTERNATIONALER_ZUG,
+        &HOCHGESCHWINDIGKEITSZUG,
+        &SCHIENENERSATZVERKEHR,
+    ];
+}
+
+#[derive(Debug)]
+pub struct MvvProfile;
+
+impl Profile for MvvProfile {
+    fn url(&self) -> &'static str {
+        "https://efa.mvv-muenchen.de/ng/"
+    }
+    fn timezone(&self) -> chrono_tz::Tz {
+        chrono_tz::Europe::Berlin
+    }
+
+    fn products(&self) -> &'static [&'static Product] {
+        products::PRODUCTS
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use crate::profile::test::check_search;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search() -> Result<(), Box<dyn Error>> {
+        check_search(MvvProfile {}, "mvv", "München Hbf", "München, Hauptbahnhof").await
+    }
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+macro_rules! profiles {
+    ($($name:literal if $feature:literal => $profile:path),* $(,)?) => {
+        const ALL_NAMES: &[&str] = &[$($name),*];
+
+        /// The profile with the given name, if compiled in.
+        ///
+        /// The names are the ones of the profile features, without the `-profile` suffix, e.g. `vrr`.
+        pub fn by_name(name: &str) -> Option<Box<dyn Profile>> {
+            match name {
+                $(
+                    #[cfg(feature = $feature)]
+                    $name => Some(Box::new($profile)),
+                )*
+                _ => None,
+            }
+        }
+    };
+}
+
+profiles! {
+    "linz" if "linz-profile" => linz::LinzProfile,
+    "mvv" if "mvv-profile" => mvv::MvvProfile,
+    "vrr" if "vrr-profile" => vrr::VrrProfile,
+    "vvs" if "vvs-profile" => vvs::VvsProfile,
+}
+
+/// The names of the compiled-in profiles, see [`by_name`].
+pub fn names() -> Vec<&'static str> {
+    ALL_NAMES
+        .iter()
+        .copied()
+        .filter(|name| by_name(name).is_some())
+        .collect()
+}
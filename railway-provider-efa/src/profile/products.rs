@@ -0,0 +1,92 @@
+//! The products of the EFA product classes most instances share.
+//!
+//! The product class `11` ("Sonstige") and the classes unused by an instance map to [`Product::unknown`].
+
+use crate::{Mode, Product};
+use std::borrow::Cow;
+
+pub const ZUG: Product = Product {
+    mode: Mode::RegionalTrain,
+    name: Cow::Borrowed("Zug"),
+    short: Cow::Borrowed("Zug"),
+};
+pub const S_BAHN: Product = Product {
+    mode: Mode::SuburbanTrain,
+    name: Cow::Borrowed("S-Bahn"),
+    short: Cow::Borrowed("S"),
+};
+pub const U_BAHN: Product = Product {
+    mode: Mode::Subway,
+    name: Cow::Borrowed("U-Bahn"),
+    short: Cow::Borrowed("U"),
+};
+pub const STADTBAHN: Product = Product {
+    mode: Mode::Tram,
+    name: Cow::Borrowed("Stadtbahn"),
+    short: Cow::Borrowed("Stadtbahn"),
+};
+pub const STRASSENBAHN: Product = Product {
+    mode: Mode::Tram,
+    name: Cow::Borrowed("Straßenbahn"),
+    short: Cow::Borrowed("Tram"),
+};
+pub const STADTBUS: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Stadtbus"),
+    short: Cow::Borrowed("Bus"),
+};
+pub const REGIONALBUS: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Regionalbus"),
+    short: Cow::Borrowed("Bus"),
+};
+pub const SCHNELLBUS: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Schnellbus"),
+    short: Cow::Borrowed("SB"),
+};
+pub const SEILBAHN: Product = Product {
+    mode: Mode::Cablecar,
+    name: Cow::Borrowed("Seil-/Zahnradbahn"),
+    short: Cow::Borrowed("Seilbahn"),
+};
+pub const SCHIFF: Product = Product {
+    mode: Mode::Ferry,
+    name: Cow::Borrowed("Schiff"),
+    short: Cow::Borrowed("Schiff"),
+};
+pub const RUFBUS: Product = Product {
+    mode: Mode::OnDemand,
+    name: Cow::Borrowed("Anruf-Sammel-Taxi"),
+    short: Cow::Borrowed("AST"),
+};
+pub const REGIONALZUG: Product = Product {
+    mode: Mode::RegionalTrain,
+    name: Cow::Borrowed("Regionalzug"),
+    short: Cow::Borrowed("RE"),
+};
+pub const FERNZUG: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("Fernzug"),
+    short: Cow::Borrowed("IC"),
+};
+pub const INTERNATIONALER_ZUG: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("Internationaler Zug"),
+    short: Cow::Borrowed("EC"),
+};
+pub const HOCHGESCHWINDIGKEITSZUG: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("Hochgeschwindigkeitszug"),
+    short: Cow::Borrowed("ICE"),
+};
+pub const SCHIENENERSATZVERKEHR: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Schienenersatzverkehr"),
+    short: Cow::Borrowed("SEV"),
+};
+pub const BUERGERBUS: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Bürgerbus"),
+    short: Cow::Borrowed("Bürgerbus"),
+};
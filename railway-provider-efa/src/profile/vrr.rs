@@ -0,0 +1,190 @@
+use crate::{Product, Profile};
+
+mod products {
+    use crate::profile::products::*;
+    use crate::Product;
+
+    pub const PRODUCTS: &[&Product] = &[
+        &ZUG,
+        &S_BAHN,
+        &U_BAHN,
+        &STADTBAHN,
+        &STRASSENBAHN,
+        &STADTBUS,
+        &REGIONALBUS,
+        &SCHNELLBUS,
+        &SEILBAHN,
+        &SCHIFF,
+        &RUFBUS,
+        &Product::unknown(),
+        &Product::unknown(),
+        &REGIONALZUG,
+        &FERNZUG,
+        &INTERNATIONALER_ZUG,
+        &HOCHGESCHWINDIGKEITSZUG,
+        &SCHIENENERSATZVERKEHR,
+        &Product::unknown(),
+        &BUERGERBUS,
+    ];
+}
+
+#[derive(Debug)]
+pub struct VrrProfile;
+
+impl Profile for VrrProfile {
+    fn url(&self) -> &'static str {
+        "https://efa.vrr.de/standard/"
+    }
+    fn timezone(&self) -> chrono_tz::Tz {
+        chrono_tz::Europe::Berlin
+    }
+
+    fn products(&self) -> &'static [&'static Product] {
+        products::PRODUCTS
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use chrono::TimeZone;
+    use rcore::{
+        IntermediateLocation, JourneysOptions, LoadFactor, Mode, Place, Price, Provider,
+        RefreshJourneyOptions, Station,
+    };
+
+    use crate::profile::test::{check_search, client};
+
+    use super::*;
+
+    fn station(id: &str) -> Place {
+        Place::Station(Station {
+            id: id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_search() -> Result<(), Box<dyn Error>> {
+        check_search(VrrProfile {}, "vrr", "Düsseldorf Hbf", "Düsseldorf Hbf").await
+    }
+
+    #[tokio::test]
+    async fn test_journeys() -> Result<(), Box<dyn Error>> {
+        let response = client(VrrProfile, "vrr")
+            .journeys(
+                station("de:05111:18235"),
+                station("de:05113:9289"),
+                JourneysOptions {
+                    departure: Some(
+                        chrono_tz::Europe::Berlin
+                            .with_ymd_and_hms(2024, 10, 1, 8, 0, 0)
+                            .unwrap(),
+                    ),
+                    stopovers: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(response.journeys.len(), 3);
+        assert_eq!(
+            response.later_ref.as_deref(),
+            Some("2024-10-01T08:36:00+02:00")
+        );
+        assert_eq!(
+            response.earlier_ref.as_deref(),
+            Some("2024-10-01T08:30:00+02:00")
+        );
+
+        let direct = &response.journeys[0];
+        assert_eq!(
+            direct.price,
+            Some(Price {
+                amount: 7.7,
+                currency: "EUR".to_string()
+            })
+        );
+        let leg = &direct.legs[0];
+        let line = leg.line.as_ref().unwrap();
+        assert_eq!(line.name.as_deref(), Some("RE1"));
+        assert_eq!(line.mode, Mode::RegionalTrain);
+        assert_eq!(leg.direction.as_deref(), Some("Aachen Hbf"));
+        assert_eq!(
+            leg.origin,
+            station("de:05111:18235"),
+            "platforms are parsed as their station"
+        );
+        assert_eq!(
+            leg.departure.unwrap() - leg.planned_departure.unwrap(),
+            chrono::Duration::minutes(3)
+        );
+        assert_eq!(leg.planned_arrival_platform.as_deref(), Some("1"));
+        assert_eq!(leg.arrival_platform.as_deref(), Some("2"));
+        assert_eq!(leg.load_factor, Some(LoadFactor::High));
+        assert_eq!(leg.remarks.len(), 2);
+        assert!(matches!(
+            &leg.intermediate_locations[..],
+            [IntermediateLocation::Stop(s)] if s.place == station("de:05112:18920")
+        ));
+
+        let transfer = &response.journeys[1];
+        assert_eq!(transfer.legs.len(), 3);
+        assert!(transfer.legs[1].walking && transfer.legs[1].transfer);
+        assert!(transfer.legs[1].line.is_none());
+
+        assert!(response.journeys[2].legs[0].cancelled);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_journey() -> Result<(), Box<dyn Error>> {
+        let client = client(VrrProfile, "vrr");
+        let journeys = client
+            .journeys(
+                station("de:05111:18235"),
+                station("de:05113:9289"),
+                JourneysOptions {
+                    departure: Some(
+                        chrono_tz::Europe::Berlin
+                            .with_ymd_and_hms(2024, 10, 1, 8, 0, 0)
+                            .unwrap(),
+                    ),
+                    stopovers: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let journey = &journeys.journeys[0];
+
+        let refreshed = client
+            .refresh_journey(
+                journey,
+                RefreshJourneyOptions {
+                    stopovers: true,
+                    tickets: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(refreshed.id, journey.id);
+        let leg = &refreshed.legs[0];
+        assert_eq!(
+            leg.departure.unwrap() - leg.planned_departure.unwrap(),
+            chrono::Duration::minutes(5)
+        );
+
+        let cancelled = client
+            .refresh_journey(&journeys.journeys[2], RefreshJourneyOptions::default())
+            .await;
+        assert!(
+            matches!(
+                cancelled,
+                Err(rcore::Error::Provider(crate::Error::NotFound(_)))
+            ),
+            "expected the cancelled journey not to be found, got {:?}",
+            cancelled
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{Product, Profile};
+
+mod products {
+    use crate::profile::products::*;
+    use crate::{Mode, Product};
+    use std::borrow::Cow;
+
+    /// The Stadtbahn of Stuttgart runs mostly underground, with lines named `U`.
+    pub const STADTBAHN: Product = Product {
+        mode: Mode::Subway,
+        name: Cow::Borrowed("Stadtbahn"),
+        short: Cow::Borrowed("U"),
+    };
+
+    pub const PRODUCTS: &[&Product] = &[
+        &ZUG,
+        &S_BAHN,
+        &Product::unknown(),
+        &STADTBAHN,
+        &Product::unknown(),
+        &STADTBUS,
+        &REGIONALBUS,
+        &SCHNELLBUS,
+        &SEILBAHN,
+        &SCHIFF,
+        &RUFBUS,
+        &Product::unknown(),
+        &Product::unknown(),
+        &REGIONALZUG,
+        &FERNZUG,
+        &INTERNATIONALER_ZUG,
+        &HOCHGESCHWINDIGKEITSZUG,
+        &SCHIENENERSATZVERKEHR,
+    ];
+}
+
+#[derive(Debug)]
+pub struct VvsProfile;
+
+impl Profile for VvsProfile {
+    fn url(&self) -> &'static str {
+        "https://www3.vvs.de/mngvvs/"
+    }
+    fn timezone(&self) -> chrono_tz::Tz {
+        chrono_tz::Europe::Berlin
+    }
+
+    fn products(&self) -> &'static [&'static Product] {
+        products::PRODUCTS
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use crate::profile::test::check_search;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search() -> Result<(), Box<dyn Error>> {
+        check_search(
+            VvsProfile {},
+            "vvs",
+            "Stuttgart Hbf",
+            "Stuttgart, Hauptbahnhof (tief)",
+        )
+        .await
+    }
+}
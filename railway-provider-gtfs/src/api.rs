@@ -0,0 +1,476 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rcore::{
+    JourneysOptions, JourneysResponse, LocationsOptions, LocationsResponse, Place, Provider,
+    RefreshJourneyOptions, RefreshJourneyResponse, Requester, TransferOptions,
+};
+
+use crate::client::GtfsClient;
+use crate::journey::{self, LegOptions, LegRef};
+use crate::raptor::{self, Query};
+use crate::{locations, Error, Result};
+
+/// The maximum number of searches for one request, as every search only finds journeys departing at about the same time.
+const MAX_SEARCHES: usize = 64;
+
+/// How far before the requested time journeys arriving or departing earlier are searched at most, in seconds.
+const MAX_WINDOW: i64 = 24 * 60 * 60;
+
+/// A journey found, with the unix timestamps of its departure and arrival.
+struct Found {
+    departure: i64,
+    arrival: i64,
+    legs: Vec<LegRef>,
+}
+
+impl<R: Requester> GtfsClient<R> {
+    /// The stops of a place, which must be a station of the feed.
+    fn stops(&self, place: &Place) -> Result<Vec<usize>> {
+        match place {
+            Place::Station(station) => self
+                .feed
+                .stop_index
+                .get(&station.id)
+                .map(|&s| self.feed.stops_of(s))
+                .ok_or_else(|| Error::NotFound(format!("stop {}", station.id))),
+            Place::Location(_) => Err(Error::InvalidInput(
+                "only stations of the feed are supported".to_string(),
+            )),
+        }
+    }
+
+    fn search(&self, query: &Query, departure: i64) -> Vec<Found> {
+        let query = Query {
+            departure,
+            origins: query.origins.clone(),
+            targets: query.targets.clone(),
+            avoid: query.avoid.clone(),
+            ..*query
+        };
+        raptor::route(&self.feed, &query)
+            .into_iter()
+            .filter_map(|parts| {
+                let legs = journey::leg_refs(&self.feed, &parts, departure);
+                let (departure, arrival) = journey::times(&self.feed, &legs)?;
+                Some(Found {
+                    departure,
+                    arrival,
+                    legs,
+                })
+            })
+            .collect()
+    }
+
+    /// Search journeys departing from `start`, until `results` are found or the journeys depart at `end`.
+    fn collect(
+        &self,
+        query: &Query,
+        start: i64,
+        end: Option<i64>,
+        results: Option<usize>,
+    ) -> Vec<Found> {
+        let mut found: Vec<Found> = vec![];
+        let mut time = start;
+        for _ in 0..MAX_SEARCHES {
+            if end.map(|e| time >= e).unwrap_or(false) {
+                break;
+            }
+            let solutions = self.search(query, time);
+            let Some(next) = solutions.iter().map(|s| s.departure).min() else {
+                break;
+            };
+            for solution in solutions {
+                if !found.iter().any(|f| f.legs == solution.legs) {
+                    found.push(solution);
+                }
+            }
+            if results.map(|r| found.len() >= r).unwrap_or(false) {
+                break;
+            }
+            // Journeys departing at the same time arrive later than those found.
+            time = next.max(time) + 60;
+        }
+        found.sort_by_key(|f| (f.departure, f.arrival));
+        found
+    }
+
+    /// Search the last journeys before `end` which match `filter`, in a window before `end` which grows until enough journeys are found.
+    fn collect_before<F: Fn(&Found) -> bool>(
+        &self,
+        query: &Query,
+        end: i64,
+        results: usize,
+        filter: F,
+    ) -> Vec<Found> {
+        let mut window = 2 * 60 * 60;
+        loop {
+            let mut found: Vec<_> = self
+                .collect(query, end - window, Some(end), None)
+                .into_iter()
+                .filter(&filter)
+                .collect();
+            if found.len() >= results || window >= MAX_WINDOW {
+                found.drain(..found.len().saturating_sub(results));
+                return found;
+            }
+            window *= 2;
+        }
+    }
+}
+
+fn parse_ref(reference: &str) -> Result<i64> {
+    reference
+        .parse()
+        .map_err(|_| Error::InvalidInput(format!("invalid reference: {}", reference)))
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl<R: Requester> Provider<R> for GtfsClient<R> {
+    type Error = crate::Error;
+
+    async fn locations(
+        &self,
+        opts: LocationsOptions,
+    ) -> std::result::Result<LocationsResponse, rcore::Error<R::Error, Self::Error>> {
+        Ok(
+            locations::search(&self.feed, &opts.query, opts.results as usize)
+                .into_iter()
+                .map(|s| journey::place(&self.feed, s))
+                .collect(),
+        )
+    }
+
+    async fn journeys(
+        &self,
+        from: Place,
+        to: Place,
+        opts: JourneysOptions,
+    ) -> std::result::Result<JourneysResponse, rcore::Error<R::Error, Self::Error>> {
+        if !opts.via.is_empty() {
+            Err(rcore::Error::Provider(Error::Unsupported(
+                "routing via places".to_string(),
+            )))?
+        }
+        let origins = self.stops(&from).map_err(rcore::Error::Provider)?;
+        let targets = self.stops(&to).map_err(rcore::Error::Provider)?;
+        let mut avoid = HashSet::new();
+        for place in &opts.avoid {
+            avoid.extend(self.stops(place).map_err(rcore::Error::Provider)?);
+        }
+        let query = Query {
+            origins,
+            targets,
+            departure: 0,
+            products: &opts.products,
+            avoid,
+            max_rides: match opts.transfers {
+                TransferOptions::Unlimited => None,
+                TransferOptions::Limited(transfers) => Some(transfers as usize + 1),
+            },
+            transfer_time: opts.transfer_time.num_seconds(),
+        };
+        let results = opts.results.max(1) as usize;
+
+        let found = match (
+            &opts.later_than,
+            &opts.earlier_than,
+            opts.departure,
+            opts.arrival,
+        ) {
+            (Some(_), Some(_), _, _) => Err(rcore::Error::Provider(Error::InvalidInput(
+                "earlier_than and later_than are mutually exclusive".to_string(),
+            )))?,
+            (_, _, Some(_), Some(_)) => Err(rcore::Error::Provider(Error::InvalidInput(
+                "departure and arrival are mutually exclusive".to_string(),
+            )))?,
+            (Some(later), None, _, _) => {
+                let start = parse_ref(later).map_err(rcore::Error::Provider)?;
+                let mut found = self.collect(&query, start, None, Some(results));
+                found.truncate(results);
+                found
+            }
+            (None, Some(earlier), _, _) => {
+                let end = parse_ref(earlier).map_err(rcore::Error::Provider)?;
+                self.collect_before(&query, end, results, |f| f.departure < end)
+            }
+            (None, None, None, Some(arrival)) => {
+                let end = arrival.timestamp();
+                self.collect_before(&query, end, results, |f| f.arrival <= end)
+            }
+            (None, None, departure, None) => {
+                let start = departure
+                    .map(|d| d.timestamp())
+                    .unwrap_or_else(|| Utc::now().timestamp());
+                let mut found = self.collect(&query, start, None, Some(results));
+                found.truncate(results);
+                found
+            }
+        };
+
+        let options = LegOptions {
+            stopovers: opts.stopovers,
+            polylines: opts.polylines,
+            polyline_format: opts.polyline_format,
+        };
+        let mut journeys = found
+            .iter()
+            .map(|f| journey::journey(&self.feed, &f.legs, options))
+            .collect::<Result<Vec<_>>>()
+            .map_err(rcore::Error::Provider)?;
+        if !self.realtime_sources.is_empty() {
+            // Journeys are still useful with planned times only, so an unavailable real-time feed is no error.
+            match self.realtime().await {
+                Ok(realtime) => {
+                    for (f, journey) in found.iter().zip(journeys.iter_mut()) {
+                        realtime.apply(&self.feed, &f.legs, journey, opts.language.as_deref());
+                    }
+                }
+                Err(e) => log::warn!("Failed to load real-time data: {}", e),
+            }
+        }
+        Ok(JourneysResponse {
+            earlier_ref: found.first().map(|f| f.departure.to_string()),
+            later_ref: found.last().map(|f| (f.departure + 60).to_string()),
+            journeys,
+        })
+    }
+
+    async fn refresh_journey(
+        &self,
+        journey: &rcore::Journey,
+        opts: RefreshJourneyOptions,
+    ) -> std::result::Result<RefreshJourneyResponse, rcore::Error<R::Error, Self::Error>> {
+        let options = LegOptions {
+            stopovers: opts.stopovers,
+            polylines: opts.polylines,
+            polyline_format: opts.polyline_format,
+        };
+        let legs = journey::parse_id(&journey.id).map_err(rcore::Error::Provider)?;
+        let mut journey =
+            journey::journey(&self.feed, &legs, options).map_err(rcore::Error::Provider)?;
+        if !self.realtime_sources.is_empty() {
+            self.realtime()
+                .await?
+                .apply(&self.feed, &legs, &mut journey, opts.language.as_deref());
+        }
+        Ok(journey)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+    use rcore::{Journey, Mode, ProductsSelection, ReplayRequester, Station};
+
+    use super::*;
+    use crate::Feed;
+
+    fn client() -> GtfsClient<ReplayRequester> {
+        GtfsClient::new(
+            Feed::open(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/feed")).unwrap(),
+            ReplayRequester::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/replay")),
+        )
+    }
+
+    fn station(id: &str) -> Place {
+        Place::Station(Station {
+            id: id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        chrono_tz::Europe::Berlin
+            .with_ymd_and_hms(2024, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    async fn journeys(opts: JourneysOptions) -> Vec<Journey> {
+        Provider::<ReplayRequester>::journeys(&client(), station("koeln"), station("beuel"), opts)
+            .await
+            .unwrap()
+            .journeys
+    }
+
+    fn lines(journey: &Journey) -> Vec<Option<String>> {
+        journey
+            .legs
+            .iter()
+            .map(|l| l.line.as_ref().and_then(|l| l.name.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn routes_journeys() {
+        let journeys = journeys(JourneysOptions {
+            departure: Some(time(1, 9, 30)),
+            results: 3,
+            stopovers: true,
+            polylines: true,
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(journeys.len(), 3);
+
+        let first = &journeys[0];
+        assert_eq!(lines(first), [Some("RE 5".to_string()), None]);
+        assert_eq!(first.legs[0].planned_departure, Some(time(1, 9, 45)));
+        assert_eq!(
+            first.legs[0].planned_departure_platform.as_deref(),
+            Some("1")
+        );
+        assert_eq!(first.legs[0].direction.as_deref(), Some("Koblenz Hbf"));
+        assert_eq!(
+            first.legs[0].line.as_ref().unwrap().mode,
+            Mode::RegionalTrain
+        );
+        assert!(first.legs[1].walking);
+        assert_eq!(first.legs[1].planned_arrival, Some(time(1, 10, 20)));
+        assert_eq!(first.legs[0].polyline.as_ref().unwrap().features.len(), 2);
+
+        // The bus passes Bonn Hbf at an interpolated time.
+        let bus = journeys
+            .iter()
+            .find(|j| lines(j) == [Some("ICE 10".to_string()), Some("529".to_string())])
+            .unwrap();
+        assert_eq!(bus.legs[1].intermediate_locations.len(), 1);
+        assert_eq!(bus.legs[1].planned_arrival, Some(time(1, 11, 5)));
+        assert!(journeys
+            .windows(2)
+            .all(|j| j[0].legs[0].planned_departure <= j[1].legs[0].planned_departure));
+    }
+
+    #[tokio::test]
+    async fn routes_with_selected_products_and_days() {
+        let mut products: HashSet<Mode> = ProductsSelection::all().into();
+        products.remove(&Mode::RegionalTrain);
+        let without_regional = journeys(JourneysOptions {
+            departure: Some(time(1, 9, 30)),
+            products: products.into(),
+            results: 1,
+            ..Default::default()
+        })
+        .await;
+        // The regional train does not run on holidays.
+        let holiday = journeys(JourneysOptions {
+            departure: Some(time(3, 9, 30)),
+            results: 1,
+            ..Default::default()
+        })
+        .await;
+        for journeys in [without_regional, holiday] {
+            assert_eq!(
+                lines(&journeys[0]),
+                [Some("ICE 10".to_string()), Some("529".to_string())]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_by_arrival_and_pages() {
+        let arriving = journeys(JourneysOptions {
+            arrival: Some(time(1, 11, 10)),
+            results: 2,
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(arriving.len(), 2);
+        assert!(arriving
+            .iter()
+            .all(|j| j.legs.last().unwrap().planned_arrival <= Some(time(1, 11, 10))));
+
+        let response = Provider::<ReplayRequester>::journeys(
+            &client(),
+            station("koeln"),
+            station("beuel"),
+            JourneysOptions {
+                departure: Some(time(1, 9, 30)),
+                results: 1,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let later = journeys(JourneysOptions {
+            later_than: response.later_ref,
+            results: 1,
+            ..Default::default()
+        })
+        .await;
+        assert!(later[0].legs[0].planned_departure > Some(time(1, 9, 45)));
+        let earlier = journeys(JourneysOptions {
+            earlier_than: response.earlier_ref,
+            results: 1,
+            ..Default::default()
+        })
+        .await;
+        assert!(earlier[0].legs[0].planned_departure < Some(time(1, 9, 45)));
+    }
+
+    #[tokio::test]
+    async fn searches_locations() {
+        let client = client();
+        let search = |query: &str| {
+            let client = client.clone();
+            let query = query.to_string();
+            async move {
+                Provider::<ReplayRequester>::locations(
+                    &client,
+                    LocationsOptions {
+                        query,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|p| match p {
+                    Place::Station(s) => s.id,
+                    Place::Location(_) => panic!("not a station"),
+                })
+                .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(search("koln").await, ["koeln"]);
+        assert_eq!(search("Bonn").await[..2], ["bonn", "beuel"]);
+        assert_eq!(search("Dusseldorf").await, ["duesseldorf"]);
+        assert!(search("Berlin").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn refreshes_journey() {
+        let journey = journeys(JourneysOptions {
+            departure: Some(time(1, 9, 30)),
+            results: 1,
+            ..Default::default()
+        })
+        .await
+        .remove(0);
+        let refreshed = Provider::<ReplayRequester>::refresh_journey(
+            &client(),
+            &journey,
+            RefreshJourneyOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(refreshed, journey);
+
+        let missing = Journey {
+            id: journey.id.replace("20241001", "20241003"),
+            ..journey
+        };
+        assert!(matches!(
+            Provider::<ReplayRequester>::refresh_journey(
+                &client(),
+                &missing,
+                RefreshJourneyOptions::default()
+            )
+            .await,
+            Err(rcore::Error::Provider(Error::NotFound(_)))
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono_tz::Tz;
+use rcore::{Requester, RequesterBuilder};
+
+use crate::feed::Feed;
+use crate::realtime::{Realtime, RealtimeSource};
+
+/// A provider routing offline on a GTFS [`Feed`].
+///
+/// The requester is only used to load real-time data from [`RealtimeSource::Url`].
+#[derive(Clone)]
+pub struct GtfsClient<R: Requester> {
+    pub(crate) feed: Arc<Feed>,
+    requester: Arc<R>,
+    pub(crate) realtime_sources: Vec<RealtimeSource>,
+}
+
+impl<R: Requester> GtfsClient<R> {
+    pub fn new<RB: RequesterBuilder<Requester = R>>(feed: Feed, requester: RB) -> Self {
+        GtfsClient {
+            feed: Arc::new(feed),
+            requester: Arc::new(requester.build()),
+            realtime_sources: vec![],
+        }
+    }
+
+    /// Apply the GTFS Realtime feeds from the given sources to the journeys, which are loaded again for every request.
+    pub fn with_realtime(mut self, sources: Vec<RealtimeSource>) -> Self {
+        self.realtime_sources = sources;
+        self
+    }
+
+    pub fn feed(&self) -> &Feed {
+        &self.feed
+    }
+
+    /// The timezone all times are returned in.
+    pub fn timezone(&self) -> Tz {
+        self.feed.timezone()
+    }
+
+    /// Load the real-time data from all sources.
+    pub async fn realtime(&self) -> Result<Realtime, rcore::Error<R::Error, crate::Error>> {
+        let mut realtime = Realtime::default();
+        for source in &self.realtime_sources {
+            let bytes = match source {
+                RealtimeSource::File(path) => {
+                    std::fs::read(path).map_err(|e| rcore::Error::Provider(e.into()))?
+                }
+                RealtimeSource::Url(url) => self
+                    .requester
+                    .get(url, &[], HashMap::new())
+                    .await
+                    .map_err(rcore::Error::Request)?,
+            };
+            realtime.merge(Realtime::decode(&bytes).map_err(rcore::Error::Provider)?);
+        }
+        Ok(realtime)
+    }
+}
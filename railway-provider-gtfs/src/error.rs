@@ -0,0 +1,37 @@
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error("{source}")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("{source}")]
+    Zip {
+        #[from]
+        source: zip::result::ZipError,
+    },
+    #[error("{file}: {source}")]
+    Csv {
+        file: &'static str,
+        source: csv::Error,
+    },
+    #[error("invalid GTFS Realtime feed: {source}")]
+    Protobuf {
+        #[from]
+        source: prost::DecodeError,
+    },
+    #[error("missing file in the feed: {0}")]
+    MissingFile(&'static str),
+    #[error("{file}: {info}")]
+    InvalidData { file: &'static str, info: String },
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("not in the timetable: {0}")]
+    NotFound(String),
+    #[error("not supported by the GTFS provider: {0}")]
+    Unsupported(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,668 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use rcore::Mode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use zip::ZipArchive;
+
+use crate::mode::mode;
+use crate::{Error, Result};
+
+#[derive(Debug, Deserialize)]
+struct AgencyRecord {
+    #[serde(default)]
+    agency_id: Option<String>,
+    agency_name: String,
+    agency_timezone: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StopRecord {
+    stop_id: String,
+    #[serde(default)]
+    stop_name: Option<String>,
+    #[serde(default)]
+    stop_lat: Option<f32>,
+    #[serde(default)]
+    stop_lon: Option<f32>,
+    #[serde(default)]
+    location_type: Option<u8>,
+    #[serde(default)]
+    parent_station: Option<String>,
+    #[serde(default)]
+    platform_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RouteRecord {
+    route_id: String,
+    #[serde(default)]
+    agency_id: Option<String>,
+    #[serde(default)]
+    route_short_name: Option<String>,
+    #[serde(default)]
+    route_long_name: Option<String>,
+    route_type: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct TripRecord {
+    route_id: String,
+    service_id: String,
+    trip_id: String,
+    #[serde(default)]
+    trip_headsign: Option<String>,
+    #[serde(default)]
+    trip_short_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StopTimeRecord {
+    trip_id: String,
+    #[serde(default)]
+    arrival_time: Option<String>,
+    #[serde(default)]
+    departure_time: Option<String>,
+    stop_id: String,
+    stop_sequence: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct CalendarRecord {
+    service_id: String,
+    monday: u8,
+    tuesday: u8,
+    wednesday: u8,
+    thursday: u8,
+    friday: u8,
+    saturday: u8,
+    sunday: u8,
+    start_date: String,
+    end_date: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CalendarDateRecord {
+    service_id: String,
+    date: String,
+    exception_type: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransferRecord {
+    from_stop_id: String,
+    to_stop_id: String,
+    #[serde(default)]
+    transfer_type: Option<u8>,
+    #[serde(default)]
+    min_transfer_time: Option<i64>,
+}
+
+/// An agency operating routes.
+#[derive(Debug, Clone)]
+pub struct Agency {
+    pub id: Option<String>,
+    pub name: String,
+}
+
+/// A stop, platform or station.
+#[derive(Debug, Clone)]
+pub struct Stop {
+    pub id: String,
+    pub name: Option<String>,
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    /// The index of the parent station.
+    pub parent: Option<usize>,
+    pub platform: Option<String>,
+    /// Whether this is a station containing other stops (`location_type` 1).
+    pub is_station: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub id: String,
+    pub name: Option<String>,
+    pub mode: Mode,
+    /// The index of the operating agency.
+    pub agency: Option<usize>,
+}
+
+/// The arrival and departure of a trip at a stop, in seconds since the start of the service day.
+#[derive(Debug, Clone, Copy)]
+pub struct StopTime {
+    pub stop: usize,
+    /// The `stop_sequence`, which identifies the stop time in real-time updates.
+    pub sequence: u32,
+    pub arrival: i64,
+    pub departure: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Trip {
+    pub id: String,
+    /// The index of the route.
+    pub route: usize,
+    /// The index of the service.
+    pub service: usize,
+    pub headsign: Option<String>,
+    pub short_name: Option<String>,
+    /// The stop times, ordered by `stop_sequence`.
+    pub stop_times: Vec<StopTime>,
+}
+
+/// The days a trip runs on, from `calendar.txt` and `calendar_dates.txt`.
+#[derive(Debug, Clone, Default)]
+pub struct Service {
+    /// Starting with Monday.
+    weekdays: [bool; 7],
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    added: HashSet<NaiveDate>,
+    removed: HashSet<NaiveDate>,
+}
+
+impl Service {
+    pub fn runs_on(&self, date: NaiveDate) -> bool {
+        if self.added.contains(&date) {
+            return true;
+        }
+        if self.removed.contains(&date) {
+            return false;
+        }
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => {
+                start <= date
+                    && date <= end
+                    && self.weekdays[date.weekday().num_days_from_monday() as usize]
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Trips serving the same sequence of stops, which are a route in terms of RAPTOR.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pub stops: Vec<usize>,
+    /// The indices of the trips, ordered by the departure at the first stop.
+    pub trips: Vec<usize>,
+}
+
+/// A way to walk from one stop to another.
+#[derive(Debug, Clone, Copy)]
+pub struct Footpath {
+    pub to: usize,
+    /// The time needed in seconds, if specified by the feed.
+    pub duration: Option<i64>,
+}
+
+/// A GTFS feed, indexed for routing.
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub(crate) timezone: Tz,
+    pub(crate) agencies: Vec<Agency>,
+    pub(crate) stops: Vec<Stop>,
+    pub(crate) stop_index: HashMap<String, usize>,
+    /// The stops of every station.
+    pub(crate) children: Vec<Vec<usize>>,
+    /// The modes of the routes serving every stop, including those of the children of stations.
+    pub(crate) stop_modes: Vec<Vec<Mode>>,
+    pub(crate) routes: Vec<Route>,
+    pub(crate) trips: Vec<Trip>,
+    pub(crate) trip_index: HashMap<String, usize>,
+    pub(crate) services: Vec<Service>,
+    /// The footpaths starting at every stop.
+    pub(crate) footpaths: Vec<Vec<Footpath>>,
+    pub(crate) patterns: Vec<Pattern>,
+    /// The patterns serving every stop, with the position of the stop in the pattern.
+    pub(crate) stop_patterns: Vec<Vec<(usize, usize)>>,
+}
+
+/// Where the files of a feed are read from.
+trait Source {
+    fn read(&mut self, file: &'static str) -> Result<Option<Vec<u8>>>;
+}
+
+impl<R: Read + Seek> Source for ZipArchive<R> {
+    fn read(&mut self, file: &'static str) -> Result<Option<Vec<u8>>> {
+        let mut entry = match self.by_name(file) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut data = vec![];
+        entry.read_to_end(&mut data)?;
+        Ok(Some(data))
+    }
+}
+
+impl Source for &Path {
+    fn read(&mut self, file: &'static str) -> Result<Option<Vec<u8>>> {
+        match std::fs::read(self.join(file)) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(file: &'static str, data: &[u8]) -> Result<Vec<T>> {
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data)
+        .deserialize()
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|source| Error::Csv { file, source })
+}
+
+fn records<T: DeserializeOwned>(source: &mut dyn Source, file: &'static str) -> Result<Vec<T>> {
+    match source.read(file)? {
+        Some(data) => parse(file, &data),
+        None => Ok(vec![]),
+    }
+}
+
+fn required_records<T: DeserializeOwned>(
+    source: &mut dyn Source,
+    file: &'static str,
+) -> Result<Vec<T>> {
+    match source.read(file)? {
+        Some(data) => parse(file, &data),
+        None => Err(Error::MissingFile(file)),
+    }
+}
+
+fn parse_date(file: &'static str, date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| Error::InvalidData {
+        file,
+        info: format!("invalid date: {}", date),
+    })
+}
+
+/// Seconds since the start of the service day, which can exceed 24 hours.
+fn parse_time(time: &str) -> Result<i64> {
+    let invalid = || Error::InvalidData {
+        file: "stop_times.txt",
+        info: format!("invalid time: {}", time),
+    };
+    let mut parts = time.split(':').map(|p| p.parse::<i64>());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(h)), Some(Ok(m)), Some(Ok(s)), None) => Ok(h * 3600 + m * 60 + s),
+        _ => Err(invalid()),
+    }
+}
+
+/// A stop with its arrival and departure, which may be missing at stops which are no timepoints.
+type RawStopTime = (usize, Option<i64>, Option<i64>);
+
+/// Fill in times missing at stops which are no timepoints, by interpolating between the surrounding times.
+fn interpolate(times: &mut [RawStopTime]) {
+    for time in times.iter_mut() {
+        if time.1.is_none() {
+            time.1 = time.2;
+        }
+        if time.2.is_none() {
+            time.2 = time.1;
+        }
+    }
+    let mut previous: Option<(usize, i64)> = None;
+    for i in 0..times.len() {
+        if let Some(departure) = times[i].2 {
+            previous = Some((i, departure));
+            continue;
+        }
+        let next = times[i..]
+            .iter()
+            .enumerate()
+            .find_map(|(j, t)| t.1.map(|a| (i + j, a)));
+        if let (Some((p, departure)), Some((n, arrival))) = (previous, next) {
+            let time = departure + (arrival - departure) * (i - p) as i64 / (n - p) as i64;
+            times[i].1 = Some(time);
+            times[i].2 = Some(time);
+        }
+    }
+}
+
+impl Feed {
+    /// Load a feed from a GTFS zip.
+    pub fn from_zip<R: Read + Seek>(reader: R) -> Result<Self> {
+        Self::load(&mut ZipArchive::new(reader)?)
+    }
+
+    /// Load a feed from a GTFS zip or a directory containing the extracted files.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            Self::load(&mut { path })
+        } else {
+            Self::from_zip(std::fs::File::open(path)?)
+        }
+    }
+
+    /// The timezone of the first agency, in which all times of the feed are.
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    fn load(source: &mut dyn Source) -> Result<Self> {
+        let agency_records: Vec<AgencyRecord> = required_records(source, "agency.txt")?;
+        let timezone = agency_records
+            .first()
+            .ok_or(Error::InvalidData {
+                file: "agency.txt",
+                info: "no agency".to_string(),
+            })?
+            .agency_timezone
+            .parse()
+            .map_err(|e| Error::InvalidData {
+                file: "agency.txt",
+                info: format!("invalid timezone: {}", e),
+            })?;
+        let agencies: Vec<_> = agency_records
+            .into_iter()
+            .map(|a| Agency {
+                id: a.agency_id,
+                name: a.agency_name,
+            })
+            .collect();
+
+        let stop_records: Vec<StopRecord> = required_records(source, "stops.txt")?;
+        let stop_index: HashMap<_, _> = stop_records
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.stop_id.clone(), i))
+            .collect();
+        let stops: Vec<_> = stop_records
+            .into_iter()
+            .map(|s| Stop {
+                parent: s
+                    .parent_station
+                    .as_ref()
+                    .and_then(|p| stop_index.get(p).copied()),
+                id: s.stop_id,
+                name: s.stop_name,
+                latitude: s.stop_lat,
+                longitude: s.stop_lon,
+                platform: s.platform_code,
+                is_station: s.location_type == Some(1),
+            })
+            .collect();
+        let mut children = vec![vec![]; stops.len()];
+        for (i, stop) in stops.iter().enumerate() {
+            if let Some(parent) = stop.parent {
+                children[parent].push(i);
+            }
+        }
+
+        let routes: Vec<_> = required_records::<RouteRecord>(source, "routes.txt")?
+            .into_iter()
+            .map(|r| Route {
+                agency: match &r.agency_id {
+                    Some(id) => agencies.iter().position(|a| a.id.as_ref() == Some(id)),
+                    // The agency is optional if there is only one.
+                    None => (agencies.len() == 1).then_some(0),
+                },
+                id: r.route_id,
+                name: r.route_short_name.or(r.route_long_name),
+                mode: mode(r.route_type),
+            })
+            .collect();
+        let route_index: HashMap<_, _> = routes
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.id.clone(), i))
+            .collect();
+
+        let mut services = vec![];
+        let mut service_index = HashMap::new();
+        let mut service = |id: String, services: &mut Vec<Service>| {
+            *service_index.entry(id).or_insert_with(|| {
+                services.push(Service::default());
+                services.len() - 1
+            })
+        };
+        for c in records::<CalendarRecord>(source, "calendar.txt")? {
+            let i = service(c.service_id, &mut services);
+            services[i].weekdays = [
+                c.monday,
+                c.tuesday,
+                c.wednesday,
+                c.thursday,
+                c.friday,
+                c.saturday,
+                c.sunday,
+            ]
+            .map(|d| d == 1);
+            services[i].start = Some(parse_date("calendar.txt", &c.start_date)?);
+            services[i].end = Some(parse_date("calendar.txt", &c.end_date)?);
+        }
+        for d in records::<CalendarDateRecord>(source, "calendar_dates.txt")? {
+            let i = service(d.service_id, &mut services);
+            let date = parse_date("calendar_dates.txt", &d.date)?;
+            match d.exception_type {
+                1 => services[i].added.insert(date),
+                _ => services[i].removed.insert(date),
+            };
+        }
+
+        let mut trips = vec![];
+        let mut trip_index = HashMap::new();
+        for t in required_records::<TripRecord>(source, "trips.txt")? {
+            let route = *route_index
+                .get(&t.route_id)
+                .ok_or_else(|| Error::InvalidData {
+                    file: "trips.txt",
+                    info: format!("unknown route: {}", t.route_id),
+                })?;
+            trip_index.insert(t.trip_id.clone(), trips.len());
+            trips.push(Trip {
+                id: t.trip_id,
+                route,
+                service: service(t.service_id, &mut services),
+                headsign: t.trip_headsign,
+                short_name: t.trip_short_name,
+                stop_times: vec![],
+            });
+        }
+
+        let mut stop_times: Vec<Vec<(u32, RawStopTime)>> = vec![vec![]; trips.len()];
+        for s in required_records::<StopTimeRecord>(source, "stop_times.txt")? {
+            let unknown = |what: &str, id: &str| Error::InvalidData {
+                file: "stop_times.txt",
+                info: format!("unknown {}: {}", what, id),
+            };
+            let trip = *trip_index
+                .get(&s.trip_id)
+                .ok_or_else(|| unknown("trip", &s.trip_id))?;
+            let stop = *stop_index
+                .get(&s.stop_id)
+                .ok_or_else(|| unknown("stop", &s.stop_id))?;
+            stop_times[trip].push((
+                s.stop_sequence,
+                (
+                    stop,
+                    s.arrival_time.as_deref().map(parse_time).transpose()?,
+                    s.departure_time.as_deref().map(parse_time).transpose()?,
+                ),
+            ));
+        }
+        for (trip, mut times) in trips.iter_mut().zip(stop_times) {
+            times.sort_by_key(|t| t.0);
+            let sequences: Vec<_> = times.iter().map(|t| t.0).collect();
+            let mut times: Vec<_> = times.into_iter().map(|t| t.1).collect();
+            interpolate(&mut times);
+            trip.stop_times = times
+                .into_iter()
+                .zip(sequences)
+                .filter_map(|((stop, arrival, departure), sequence)| {
+                    Some(StopTime {
+                        stop,
+                        sequence,
+                        arrival: arrival?,
+                        departure: departure?,
+                    })
+                })
+                .collect();
+        }
+
+        let mut footpaths = vec![vec![]; stops.len()];
+        for t in records::<TransferRecord>(source, "transfers.txt")? {
+            // Transfers between trips or routes are not supported, and type 3 means that transferring is not possible.
+            let (Some(&from), Some(&to)) = (
+                stop_index.get(&t.from_stop_id),
+                stop_index.get(&t.to_stop_id),
+            ) else {
+                continue;
+            };
+            if from != to && t.transfer_type != Some(3) {
+                footpaths[from].push(Footpath {
+                    to,
+                    duration: t.min_transfer_time,
+                });
+            }
+        }
+        // Stops of the same station are connected, unless the feed already specifies how long it takes.
+        for station in &children {
+            for &from in station {
+                for &to in station {
+                    if from != to && !footpaths[from].iter().any(|f: &Footpath| f.to == to) {
+                        footpaths[from].push(Footpath { to, duration: None });
+                    }
+                }
+            }
+        }
+
+        let mut patterns: Vec<Pattern> = vec![];
+        let mut pattern_index: HashMap<Vec<usize>, usize> = HashMap::new();
+        for (i, trip) in trips.iter().enumerate() {
+            if trip.stop_times.len() < 2 {
+                continue;
+            }
+            let stops: Vec<_> = trip.stop_times.iter().map(|s| s.stop).collect();
+            let pattern = *pattern_index.entry(stops.clone()).or_insert_with(|| {
+                patterns.push(Pattern {
+                    stops,
+                    trips: vec![],
+                });
+                patterns.len() - 1
+            });
+            patterns[pattern].trips.push(i);
+        }
+        let mut stop_patterns = vec![vec![]; stops.len()];
+        let mut stop_modes: Vec<Vec<Mode>> = vec![vec![]; stops.len()];
+        for (i, pattern) in patterns.iter_mut().enumerate() {
+            pattern
+                .trips
+                .sort_by_key(|&t| trips[t].stop_times[0].departure);
+            for (position, &stop) in pattern.stops.iter().enumerate() {
+                stop_patterns[stop].push((i, position));
+                for &trip in &pattern.trips {
+                    let mode = &routes[trips[trip].route].mode;
+                    for stop in std::iter::once(stop).chain(stops[stop].parent) {
+                        if !stop_modes[stop].contains(mode) {
+                            stop_modes[stop].push(mode.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Feed {
+            timezone,
+            agencies,
+            stops,
+            stop_index,
+            children,
+            stop_modes,
+            routes,
+            trips,
+            trip_index,
+            services,
+            footpaths,
+            patterns,
+            stop_patterns,
+        })
+    }
+
+    /// The start of the service day, which is noon minus twelve hours to be correct on days with a change of daylight saving time.
+    pub(crate) fn service_day_start(&self, date: NaiveDate) -> chrono::DateTime<Tz> {
+        let noon = date.and_time(NaiveTime::from_hms_opt(12, 0, 0).expect("noon is valid"));
+        self.timezone
+            .from_local_datetime(&noon)
+            .earliest()
+            .unwrap_or_else(|| self.timezone.from_utc_datetime(&noon))
+            - Duration::hours(12)
+    }
+
+    /// The stop itself, and all stops of it if it is a station.
+    pub(crate) fn stops_of(&self, stop: usize) -> Vec<usize> {
+        std::iter::once(stop)
+            .chain(self.children[stop].iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interpolates_times() {
+        let mut times = [
+            (0, Some(0), Some(60)),
+            (1, None, None),
+            (2, None, None),
+            (3, Some(360), None),
+        ];
+        interpolate(&mut times);
+        assert_eq!(
+            times.map(|t| (t.1, t.2)),
+            [
+                (Some(0), Some(60)),
+                (Some(160), Some(160)),
+                (Some(260), Some(260)),
+                (Some(360), Some(360)),
+            ]
+        );
+    }
+
+    #[test]
+    fn loads_zip() {
+        let dir = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/feed"));
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        for file in [
+            "agency.txt",
+            "stops.txt",
+            "routes.txt",
+            "trips.txt",
+            "stop_times.txt",
+        ] {
+            writer
+                .start_file(file, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            std::io::Write::write_all(&mut writer, &std::fs::read(dir.join(file)).unwrap())
+                .unwrap();
+        }
+        let feed = Feed::from_zip(writer.finish().unwrap()).unwrap();
+        assert_eq!(feed.timezone(), chrono_tz::Europe::Berlin);
+        assert_eq!(feed.stops.len(), 8);
+        assert_eq!(feed.children[feed.stop_index["koeln"]].len(), 2);
+        // Without a calendar, no trip runs.
+        let date = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+        assert!(feed.services.iter().all(|s| !s.runs_on(date)));
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        writer
+            .start_file("agency.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        assert!(matches!(
+            Feed::from_zip(writer.finish().unwrap()),
+            Err(Error::InvalidData { .. })
+        ));
+    }
+}
@@ -0,0 +1,366 @@
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use geojson::{Feature, FeatureCollection, Geometry, Value};
+use rcore::{
+    IntermediateLocation, Journey, Leg, Line, Location, Operator, Place, PolylineFormat, Station,
+    Stop,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::feed::Feed;
+use crate::mode::product;
+use crate::raptor::Part;
+use crate::{Error, Result};
+
+/// A leg as stored in the [`Journey::id`], from which it can be resolved against the timetable again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum LegRef {
+    Ride {
+        trip: String,
+        /// The service day, as `YYYYMMDD`.
+        date: String,
+        board: usize,
+        alight: usize,
+    },
+    Walk {
+        from: String,
+        to: String,
+        /// The unix timestamp.
+        departure: i64,
+        /// In seconds.
+        duration: i64,
+    },
+}
+
+/// What to include in the legs.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LegOptions {
+    pub stopovers: bool,
+    pub polylines: bool,
+    pub polyline_format: PolylineFormat,
+}
+
+/// The times of the parts of a journey.
+///
+/// Walks before the first ride start just in time, all other walks directly after the previous ride.
+/// Journeys only walking depart at `departure`.
+pub(crate) fn leg_refs(feed: &Feed, parts: &[Part], departure: i64) -> Vec<LegRef> {
+    let ride_times = |part: &Part| match part {
+        Part::Ride {
+            trip,
+            date,
+            board,
+            alight,
+        } => {
+            let start = feed.service_day_start(*date).timestamp();
+            let stop_times = &feed.trips[*trip].stop_times;
+            Some((
+                start + stop_times[*board].departure,
+                start + stop_times[*alight].arrival,
+            ))
+        }
+        Part::Walk { .. } => None,
+    };
+
+    // Walk backwards from the first ride.
+    let first_ride = parts.iter().position(|p| ride_times(p).is_some());
+    let mut time = first_ride
+        .and_then(|i| ride_times(&parts[i]))
+        .map(|t| t.0)
+        .unwrap_or(departure);
+    let mut departures = vec![0; parts.len()];
+    for (i, part) in parts
+        .iter()
+        .enumerate()
+        .take(first_ride.unwrap_or(parts.len()))
+        .rev()
+    {
+        if let Part::Walk { duration, .. } = part {
+            time -= duration;
+            departures[i] = time;
+        }
+    }
+    if first_ride.is_none() {
+        // There is no ride to walk towards, so depart at the requested time.
+        let offset = departure - departures.first().copied().unwrap_or(departure);
+        departures.iter_mut().for_each(|d| *d += offset);
+    }
+
+    let mut refs = vec![];
+    let mut arrival = None;
+    for (i, part) in parts.iter().enumerate() {
+        match part {
+            Part::Ride {
+                trip,
+                date,
+                board,
+                alight,
+            } => {
+                arrival = ride_times(part).map(|t| t.1);
+                refs.push(LegRef::Ride {
+                    trip: feed.trips[*trip].id.clone(),
+                    date: date.format("%Y%m%d").to_string(),
+                    board: *board,
+                    alight: *alight,
+                });
+            }
+            Part::Walk { from, to, duration } => {
+                let departure = match (first_ride, arrival) {
+                    (Some(first), Some(arrival)) if i > first => arrival,
+                    _ => departures[i],
+                };
+                arrival = Some(departure + duration);
+                refs.push(LegRef::Walk {
+                    from: feed.stops[*from].id.clone(),
+                    to: feed.stops[*to].id.clone(),
+                    departure,
+                    duration: *duration,
+                });
+            }
+        }
+    }
+    refs
+}
+
+fn stop_index(feed: &Feed, id: &str) -> Result<usize> {
+    feed.stop_index
+        .get(id)
+        .copied()
+        .ok_or_else(|| Error::NotFound(format!("stop {}", id)))
+}
+
+/// A stop as a place, which is its station if it has one.
+pub(crate) fn place(feed: &Feed, stop: usize) -> Place {
+    let station = feed.stops[stop].parent.unwrap_or(stop);
+    let s = &feed.stops[station];
+    Place::Station(Station {
+        id: s.id.clone(),
+        name: s.name.clone(),
+        location: location(feed, station),
+        products: feed.stop_modes[station].iter().map(product).collect(),
+        accessibility: None,
+    })
+}
+
+fn location(feed: &Feed, stop: usize) -> Option<Location> {
+    let s = &feed.stops[stop];
+    Some(Location::Point {
+        id: Some(s.id.clone()),
+        name: s.name.clone(),
+        poi: None,
+        latitude: s.latitude?,
+        longitude: s.longitude?,
+    })
+}
+
+fn time(feed: &Feed, timestamp: i64) -> Option<DateTime<Tz>> {
+    DateTime::from_timestamp(timestamp, 0).map(|t| t.with_timezone(&feed.timezone))
+}
+
+fn polyline(feed: &Feed, stops: &[usize], format: PolylineFormat) -> FeatureCollection {
+    let polyline = FeatureCollection {
+        features: stops
+            .iter()
+            .filter_map(|&s| {
+                let stop = &feed.stops[s];
+                Some(Feature::from(Geometry::new(Value::Point(vec![
+                    f64::from(stop.longitude?),
+                    f64::from(stop.latitude?),
+                ]))))
+            })
+            .collect(),
+        bbox: None,
+        foreign_members: None,
+    };
+    match format {
+        PolylineFormat::Points => polyline,
+        PolylineFormat::LineString => rcore::polyline_to_line_string(&polyline),
+    }
+}
+
+/// The distance between two stops in meters.
+fn distance(feed: &Feed, from: usize, to: usize) -> Option<u64> {
+    let (from, to) = (&feed.stops[from], &feed.stops[to]);
+    let (lat1, lon1) = (f64::from(from.latitude?), f64::from(from.longitude?));
+    let (lat2, lon2) = (f64::from(to.latitude?), f64::from(to.longitude?));
+    let (dlat, dlon) = ((lat2 - lat1).to_radians(), (lon2 - lon1).to_radians());
+    let a = (dlat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (dlon / 2.0).sin().powi(2);
+    Some((6_371_000.0 * 2.0 * a.sqrt().asin()).round() as u64)
+}
+
+fn leg(feed: &Feed, leg: &LegRef, options: LegOptions) -> Result<Leg> {
+    match leg {
+        LegRef::Ride {
+            trip,
+            date,
+            board,
+            alight,
+        } => {
+            let t = &feed.trips[*feed
+                .trip_index
+                .get(trip)
+                .ok_or_else(|| Error::NotFound(format!("trip {}", trip)))?];
+            let day = NaiveDate::parse_from_str(date, "%Y%m%d")
+                .map_err(|_| Error::InvalidInput(format!("invalid date: {}", date)))?;
+            if !feed.services[t.service].runs_on(day) {
+                return Err(Error::NotFound(format!("trip {} on {}", trip, date)));
+            }
+            if board >= alight || *alight >= t.stop_times.len() {
+                return Err(Error::NotFound(format!(
+                    "stops {} to {} of trip {}",
+                    board, alight, trip
+                )));
+            }
+            let start = feed.service_day_start(day).timestamp();
+            let stop_times = &t.stop_times[*board..=*alight];
+            let (first, last) = (&t.stop_times[*board], &t.stop_times[*alight]);
+            let platform = |stop: usize| feed.stops[stop].platform.clone();
+            let route = &feed.routes[t.route];
+
+            Ok(Leg {
+                origin: place(feed, first.stop),
+                destination: place(feed, last.stop),
+                departure: None,
+                planned_departure: time(feed, start + first.departure),
+                arrival: None,
+                planned_arrival: time(feed, start + last.arrival),
+                reachable: true,
+                trip_id: Some(t.id.clone()),
+                line: Some(Line {
+                    name: route.name.clone(),
+                    fahrt_nr: t.short_name.clone(),
+                    mode: route.mode.clone(),
+                    product: product(&route.mode),
+                    operator: route.agency.map(|a| {
+                        let agency = &feed.agencies[a];
+                        Operator {
+                            id: agency.id.clone().unwrap_or_else(|| agency.name.clone()),
+                            name: agency.name.clone(),
+                        }
+                    }),
+                    product_name: None,
+                }),
+                direction: t.headsign.clone(),
+                arrival_platform: None,
+                planned_arrival_platform: platform(last.stop),
+                departure_platform: None,
+                planned_departure_platform: platform(first.stop),
+                frequency: None,
+                cancelled: false,
+                intermediate_locations: if options.stopovers {
+                    stop_times[1..stop_times.len() - 1]
+                        .iter()
+                        .map(|s| {
+                            IntermediateLocation::Stop(Stop {
+                                place: place(feed, s.stop),
+                                departure: None,
+                                planned_departure: time(feed, start + s.departure),
+                                arrival: None,
+                                planned_arrival: time(feed, start + s.arrival),
+                                arrival_platform: None,
+                                planned_arrival_platform: platform(s.stop),
+                                departure_platform: None,
+                                planned_departure_platform: platform(s.stop),
+                                cancelled: false,
+                                remarks: vec![],
+                                accessibility: None,
+                            })
+                        })
+                        .collect()
+                } else {
+                    vec![]
+                },
+                load_factor: None,
+                remarks: vec![],
+                polyline: options.polylines.then(|| {
+                    let stops: Vec<_> = stop_times.iter().map(|s| s.stop).collect();
+                    polyline(feed, &stops, options.polyline_format)
+                }),
+                walking: false,
+                transfer: false,
+                distance: None,
+            })
+        }
+        LegRef::Walk {
+            from,
+            to,
+            departure,
+            duration,
+        } => {
+            let (from, to) = (stop_index(feed, from)?, stop_index(feed, to)?);
+            Ok(Leg {
+                origin: place(feed, from),
+                destination: place(feed, to),
+                departure: None,
+                planned_departure: time(feed, *departure),
+                arrival: None,
+                planned_arrival: time(feed, departure + duration),
+                reachable: true,
+                trip_id: None,
+                line: None,
+                direction: None,
+                arrival_platform: None,
+                planned_arrival_platform: feed.stops[to].platform.clone(),
+                departure_platform: None,
+                planned_departure_platform: feed.stops[from].platform.clone(),
+                frequency: None,
+                cancelled: false,
+                intermediate_locations: vec![],
+                load_factor: None,
+                remarks: vec![],
+                polyline: options
+                    .polylines
+                    .then(|| polyline(feed, &[from, to], options.polyline_format)),
+                walking: true,
+                transfer: true,
+                distance: distance(feed, from, to),
+            })
+        }
+    }
+}
+
+/// Build a journey from its legs, with the serialized legs as [`Journey::id`].
+pub(crate) fn journey(feed: &Feed, refs: &[LegRef], options: LegOptions) -> Result<Journey> {
+    Ok(Journey {
+        id: serde_json::to_string(refs).expect("legs are serializable"),
+        legs: refs
+            .iter()
+            .map(|l| leg(feed, l, options))
+            .collect::<Result<_>>()?,
+        price: None,
+    })
+}
+
+/// Resolve the legs of a journey again from its [`Journey::id`].
+pub(crate) fn parse_id(id: &str) -> Result<Vec<LegRef>> {
+    serde_json::from_str(id).map_err(|e| Error::InvalidInput(format!("invalid journey id: {}", e)))
+}
+
+/// The unix timestamps of the departure and arrival of a journey.
+pub(crate) fn times(feed: &Feed, refs: &[LegRef]) -> Option<(i64, i64)> {
+    let time = |leg: &LegRef, departure: bool| match leg {
+        LegRef::Ride {
+            trip,
+            date,
+            board,
+            alight,
+        } => {
+            let trip = &feed.trips[*feed.trip_index.get(trip)?];
+            let date = NaiveDate::parse_from_str(date, "%Y%m%d").ok()?;
+            let start = feed.service_day_start(date).timestamp();
+            Some(if departure {
+                start + trip.stop_times.get(*board)?.departure
+            } else {
+                start + trip.stop_times.get(*alight)?.arrival
+            })
+        }
+        LegRef::Walk {
+            departure: time,
+            duration,
+            ..
+        } => Some(if departure { *time } else { time + duration }),
+    };
+    Some((time(refs.first()?, true)?, time(refs.last()?, false)?))
+}
@@ -0,0 +1,16 @@
+#![doc = include_str!("../README.md")]
+
+pub mod api;
+pub mod client;
+pub mod error;
+pub mod feed;
+mod journey;
+mod locations;
+pub mod mode;
+mod raptor;
+pub mod realtime;
+
+pub use client::GtfsClient;
+pub use error::{Error, Result};
+pub use feed::Feed;
+pub use realtime::{Realtime, RealtimeSource};
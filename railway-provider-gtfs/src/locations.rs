@@ -0,0 +1,111 @@
+use crate::feed::Feed;
+
+/// The minimum Jaro-Winkler similarity for a name to match without containing the query.
+const MIN_SIMILARITY: f64 = 0.85;
+
+/// Lowercase and replace letters with diacritics by their base letters, e.g. `Köln` to `koln`.
+fn fold(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .flat_map(|c| {
+            let folded = match c {
+                'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' => "a",
+                'ç' | 'ć' | 'č' => "c",
+                'ď' => "d",
+                'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+                'ì' | 'í' | 'î' | 'ï' => "i",
+                'ł' | 'ľ' => "l",
+                'ñ' | 'ń' | 'ň' => "n",
+                'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ő' => "o",
+                'ř' => "r",
+                'ś' | 'š' => "s",
+                'ß' => "ss",
+                'ť' => "t",
+                'ù' | 'ú' | 'û' | 'ü' | 'ů' | 'ű' => "u",
+                'ý' | 'ÿ' => "y",
+                'ź' | 'ż' | 'ž' => "z",
+                'æ' => "ae",
+                'œ' => "oe",
+                _ => return vec![c],
+            };
+            folded.chars().collect()
+        })
+        .collect()
+}
+
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// How well a name matches the query, if at all.
+///
+/// Exact matches rank before prefixes of the name, prefixes of a word of the name, names containing all words of the query as prefixes of its words and finally similar names.
+fn score(query: &str, name: &str) -> Option<f64> {
+    if name == query {
+        return Some(4.0);
+    }
+    if name.starts_with(query) {
+        return Some(3.0);
+    }
+    let name_words = words(name);
+    if name_words.iter().any(|w| w.starts_with(query)) {
+        return Some(2.0);
+    }
+    let query_words = words(query);
+    if !query_words.is_empty()
+        && query_words
+            .iter()
+            .all(|q| name_words.iter().any(|w| w.starts_with(q)))
+    {
+        return Some(1.5);
+    }
+    let similarity = std::iter::once(name)
+        .chain(name_words.iter().copied())
+        .map(|n| strsim::jaro_winkler(query, n))
+        .fold(0.0, f64::max);
+    (similarity >= MIN_SIMILARITY).then_some(similarity)
+}
+
+/// Search the stations, or stops without a station, by name.
+pub(crate) fn search(feed: &Feed, query: &str, results: usize) -> Vec<usize> {
+    let query = fold(query.trim());
+    if query.is_empty() {
+        return vec![];
+    }
+    let mut matches: Vec<(f64, usize)> = feed
+        .stops
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.is_station || s.parent.is_none())
+        .filter_map(|(i, s)| Some((score(&query, &fold(s.name.as_ref()?))?, i)))
+        .collect();
+    // Prefer stations served by more modes and shorter names on equal scores.
+    matches.sort_by(|(a, i), (b, j)| {
+        b.total_cmp(a)
+            .then(feed.stop_modes[*j].len().cmp(&feed.stop_modes[*i].len()))
+            .then(
+                feed.stops[*i]
+                    .name
+                    .as_ref()
+                    .map(|n| n.len())
+                    .cmp(&feed.stops[*j].name.as_ref().map(|n| n.len())),
+            )
+    });
+    matches.into_iter().take(results).map(|m| m.1).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scores_names() {
+        assert_eq!(fold("Köln Straße"), "koln strasse");
+        assert!(score("koln", "koln hbf") > score("hbf", "koln hbf"));
+        assert!(score("bonn beuel", "bonn-beuel").is_some());
+        assert!(score("dusseldorf", "duesseldorf hbf").is_some());
+        assert!(score("berlin", "koln hbf").is_none());
+    }
+}
@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+use rcore::{Mode, Product};
+
+/// The [`Mode`] of a GTFS `route_type`, including the extended route types.
+///
+/// See <https://gtfs.org/schedule/reference/#routestxt> and <https://developers.google.com/transit/gtfs/reference/extended-route-types>.
+pub fn mode(route_type: u16) -> Mode {
+    match route_type {
+        // Tram, cable tram.
+        0 | 5 => Mode::Tram,
+        // Subway, monorail.
+        1 | 12 => Mode::Subway,
+        2 => Mode::RegionalTrain,
+        // Bus, trolleybus.
+        3 | 11 => Mode::Bus,
+        4 => Mode::Ferry,
+        // Aerial lift, funicular.
+        6 | 7 => Mode::Cablecar,
+        // High speed and long distance rail.
+        101 | 102 => Mode::HighSpeedTrain,
+        // Suburban railway.
+        109 => Mode::SuburbanTrain,
+        100..=199 => Mode::RegionalTrain,
+        // Coach services.
+        200..=299 => Mode::Bus,
+        400 => Mode::SuburbanTrain,
+        401..=499 => Mode::Subway,
+        // Demand and response bus.
+        715 => Mode::OnDemand,
+        700..=799 => Mode::Bus,
+        // Trolleybus.
+        800..=899 => Mode::Bus,
+        900..=999 => Mode::Tram,
+        1000..=1099 | 1200..=1299 => Mode::Ferry,
+        1300..=1499 => Mode::Cablecar,
+        // Taxi.
+        1500..=1599 => Mode::OnDemand,
+        _ => Mode::Unknown,
+    }
+}
+
+/// The product of lines with the given mode, as GTFS has no products.
+pub fn product(mode: &Mode) -> Product {
+    let (name, short) = match mode {
+        Mode::HighSpeedTrain => ("High-speed train", "H"),
+        Mode::RegionalTrain => ("Regional train", "R"),
+        Mode::SuburbanTrain => ("Suburban train", "S"),
+        Mode::Subway => ("Subway", "U"),
+        Mode::Tram => ("Tram", "T"),
+        Mode::Bus => ("Bus", "B"),
+        Mode::Ferry => ("Ferry", "F"),
+        Mode::Cablecar => ("Cable car", "C"),
+        Mode::OnDemand => ("On demand", "O"),
+        Mode::Unknown => return Product::unknown(),
+    };
+    Product {
+        mode: mode.clone(),
+        name: Cow::Borrowed(name),
+        short: Cow::Borrowed(short),
+    }
+}
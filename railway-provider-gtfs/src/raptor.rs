@@ -0,0 +1,339 @@
+//! Round-based public transit routing (RAPTOR), see <https://www.microsoft.com/en-us/research/publication/round-based-public-transit-routing/>.
+
+use std::collections::HashSet;
+
+use chrono::{Duration, NaiveDate};
+use rcore::ProductsSelection;
+
+use crate::feed::Feed;
+
+/// How long walking between stops of the same station takes, if the feed does not specify it.
+const DEFAULT_TRANSFER: i64 = 120;
+
+/// The maximum number of rides if the number of transfers is unlimited.
+const MAX_RIDES: usize = 8;
+
+pub(crate) struct Query<'a> {
+    /// The stops to start at.
+    pub origins: Vec<usize>,
+    /// The stops to arrive at.
+    pub targets: Vec<usize>,
+    /// The unix timestamp to depart at, or later.
+    pub departure: i64,
+    pub products: &'a ProductsSelection,
+    /// Stops which must not be passed.
+    pub avoid: HashSet<usize>,
+    pub max_rides: Option<usize>,
+    /// The minimum time to transfer between two rides, in seconds.
+    pub transfer_time: i64,
+}
+
+/// A part of a journey found by [`route`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Part {
+    /// Ride a trip from one position to another position in its stop times.
+    Ride {
+        trip: usize,
+        date: NaiveDate,
+        board: usize,
+        alight: usize,
+    },
+    /// Walk between two stops, taking `duration` seconds.
+    Walk {
+        from: usize,
+        to: usize,
+        duration: i64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Label {
+    Origin,
+    Ride {
+        trip: usize,
+        date: NaiveDate,
+        board: usize,
+        alight: usize,
+        from: usize,
+    },
+    Walk {
+        from: usize,
+        duration: i64,
+    },
+}
+
+/// The earliest arrival at every stop after a round, and the time from which another trip can be boarded there.
+#[derive(Clone)]
+struct Round {
+    arrival: Vec<i64>,
+    ready: Vec<i64>,
+    labels: Vec<Option<Label>>,
+}
+
+/// A trip running on a specific date.
+#[derive(Clone, Copy)]
+struct TripInstance {
+    trip: usize,
+    date: NaiveDate,
+    /// The unix timestamp of the start of the service day.
+    start: i64,
+}
+
+struct Router<'a> {
+    feed: &'a Feed,
+    query: &'a Query<'a>,
+    /// The start of the service days that trips departing around the query can run on.
+    days: Vec<(NaiveDate, i64)>,
+}
+
+impl<'a> Router<'a> {
+    fn new(feed: &'a Feed, query: &'a Query<'a>) -> Self {
+        let date = chrono::DateTime::from_timestamp(query.departure, 0)
+            .unwrap_or_default()
+            .with_timezone(&feed.timezone)
+            .date_naive();
+        let days = (-1..=1)
+            .map(|d| date + Duration::days(d))
+            .map(|d| (d, feed.service_day_start(d).timestamp()))
+            .collect();
+        Self { feed, query, days }
+    }
+
+    /// The earliest trip of a pattern departing at the given position not before `ready`.
+    fn earliest_trip(&self, pattern: usize, position: usize, ready: i64) -> Option<TripInstance> {
+        let mut earliest: Option<(i64, TripInstance)> = None;
+        for &trip in &self.feed.patterns[pattern].trips {
+            let t = &self.feed.trips[trip];
+            if !self
+                .query
+                .products
+                .contains(&self.feed.routes[t.route].mode)
+            {
+                continue;
+            }
+            for &(date, start) in &self.days {
+                let departure = start + t.stop_times[position].departure;
+                if departure >= ready
+                    && earliest.map(|e| departure < e.0).unwrap_or(true)
+                    && self.feed.services[t.service].runs_on(date)
+                {
+                    earliest = Some((departure, TripInstance { trip, date, start }));
+                }
+            }
+        }
+        earliest.map(|e| e.1)
+    }
+
+    fn route(&self) -> Vec<Vec<Part>> {
+        let stops = self.feed.stops.len();
+        let mut round = Round {
+            arrival: vec![i64::MAX; stops],
+            ready: vec![i64::MAX; stops],
+            labels: vec![None; stops],
+        };
+        // The earliest arrival at every stop over all rounds, for pruning.
+        let mut best = vec![i64::MAX; stops];
+        let mut marked = vec![];
+
+        for &origin in &self.query.origins {
+            round.arrival[origin] = self.query.departure;
+            round.ready[origin] = self.query.departure;
+            round.labels[origin] = Some(Label::Origin);
+            best[origin] = self.query.departure;
+        }
+        let origins = self.query.origins.clone();
+        marked.extend(self.walk(&mut round, &mut best, &origins));
+        marked.extend(origins);
+
+        let mut rounds = vec![round];
+        let max_rides = self.query.max_rides.unwrap_or(MAX_RIDES);
+        while !marked.is_empty() && rounds.len() <= max_rides {
+            let previous = rounds.last().expect("there is at least one round");
+            let mut round = Round {
+                labels: vec![None; stops],
+                ..previous.clone()
+            };
+
+            // The first position of every pattern at which a marked stop is served.
+            let mut queue: Vec<(usize, usize)> = vec![];
+            for &stop in &marked {
+                for &(pattern, position) in &self.feed.stop_patterns[stop] {
+                    match queue.iter_mut().find(|q| q.0 == pattern) {
+                        Some(q) => q.1 = q.1.min(position),
+                        None => queue.push((pattern, position)),
+                    }
+                }
+            }
+
+            let mut rode = vec![];
+            for (pattern, start) in queue {
+                let mut current: Option<(TripInstance, usize, usize)> = None;
+                for (position, &stop) in self.feed.patterns[pattern]
+                    .stops
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                {
+                    if self.query.avoid.contains(&stop) {
+                        current = None;
+                        continue;
+                    }
+                    if let Some((instance, board, from)) = current {
+                        let arrival = instance.start
+                            + self.feed.trips[instance.trip].stop_times[position].arrival;
+                        if arrival < best[stop].min(self.target_bound(&best)) {
+                            round.arrival[stop] = arrival;
+                            round.ready[stop] = arrival + self.query.transfer_time;
+                            round.labels[stop] = Some(Label::Ride {
+                                trip: instance.trip,
+                                date: instance.date,
+                                board,
+                                alight: position,
+                                from,
+                            });
+                            best[stop] = arrival;
+                            if !rode.contains(&stop) {
+                                rode.push(stop);
+                            }
+                        }
+                    }
+
+                    let ready = previous.ready[stop];
+                    if ready == i64::MAX {
+                        continue;
+                    }
+                    let departure = current.map(|(instance, _, _)| {
+                        instance.start
+                            + self.feed.trips[instance.trip].stop_times[position].departure
+                    });
+                    if departure.map(|d| ready <= d).unwrap_or(true) {
+                        if let Some(instance) = self.earliest_trip(pattern, position, ready) {
+                            let earlier = instance.start
+                                + self.feed.trips[instance.trip].stop_times[position].departure;
+                            if departure.map(|d| earlier < d).unwrap_or(true) {
+                                current = Some((instance, position, stop));
+                            }
+                        }
+                    }
+                }
+            }
+
+            marked = self.walk(&mut round, &mut best, &rode);
+            marked.extend(rode);
+            rounds.push(round);
+        }
+
+        self.solutions(&rounds)
+    }
+
+    /// The earliest arrival at any target.
+    fn target_bound(&self, best: &[i64]) -> i64 {
+        self.query
+            .targets
+            .iter()
+            .map(|&t| best[t])
+            .min()
+            .unwrap_or(i64::MAX)
+    }
+
+    /// Walk from the given stops, returning the stops improved.
+    fn walk(&self, round: &mut Round, best: &mut [i64], from: &[usize]) -> Vec<usize> {
+        let mut improved = vec![];
+        for &stop in from {
+            for footpath in &self.feed.footpaths[stop] {
+                if from.contains(&footpath.to) || self.query.avoid.contains(&footpath.to) {
+                    continue;
+                }
+                let duration = footpath
+                    .duration
+                    .unwrap_or(DEFAULT_TRANSFER)
+                    .max(self.query.transfer_time);
+                let arrival = round.arrival[stop] + duration;
+                if arrival < best[footpath.to].min(self.target_bound(best)) {
+                    round.arrival[footpath.to] = arrival;
+                    round.ready[footpath.to] = arrival;
+                    round.labels[footpath.to] = Some(Label::Walk {
+                        from: stop,
+                        duration,
+                    });
+                    best[footpath.to] = arrival;
+                    if !improved.contains(&footpath.to) {
+                        improved.push(footpath.to);
+                    }
+                }
+            }
+        }
+        improved
+    }
+
+    /// The journeys with the earliest arrival for every number of rides, if they arrive earlier than with fewer rides.
+    fn solutions(&self, rounds: &[Round]) -> Vec<Vec<Part>> {
+        let mut solutions = vec![];
+        let mut earliest = i64::MAX;
+        for k in 0..rounds.len() {
+            let Some(target) = self
+                .query
+                .targets
+                .iter()
+                .copied()
+                .min_by_key(|&t| rounds[k].arrival[t])
+            else {
+                continue;
+            };
+            if rounds[k].arrival[target] >= earliest {
+                continue;
+            }
+            earliest = rounds[k].arrival[target];
+            if let Some(parts) = self.reconstruct(rounds, k, target) {
+                if !parts.is_empty() {
+                    solutions.push(parts);
+                }
+            }
+        }
+        solutions
+    }
+
+    fn reconstruct(&self, rounds: &[Round], mut k: usize, mut stop: usize) -> Option<Vec<Part>> {
+        let mut parts = vec![];
+        loop {
+            // The label is in the last round which improved the stop.
+            while rounds[k].labels[stop].is_none() {
+                k = k.checked_sub(1)?;
+            }
+            match rounds[k].labels[stop]? {
+                Label::Origin => break,
+                Label::Ride {
+                    trip,
+                    date,
+                    board,
+                    alight,
+                    from,
+                } => {
+                    parts.push(Part::Ride {
+                        trip,
+                        date,
+                        board,
+                        alight,
+                    });
+                    stop = from;
+                    k = k.checked_sub(1)?;
+                }
+                Label::Walk { from, duration } => {
+                    parts.push(Part::Walk {
+                        from,
+                        to: stop,
+                        duration,
+                    });
+                    stop = from;
+                }
+            }
+        }
+        parts.reverse();
+        Some(parts)
+    }
+}
+
+/// Find the journeys arriving earliest at the targets, one for every number of rides which arrives earlier than with fewer rides.
+pub(crate) fn route(feed: &Feed, query: &Query) -> Vec<Vec<Part>> {
+    Router::new(feed, query).route()
+}
@@ -0,0 +1,595 @@
+mod proto;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate};
+use prost::Message;
+use rcore::{
+    IntermediateLocation, Journey, Leg, LoadFactor, Remark, RemarkAssociation, RemarkType,
+};
+
+use crate::feed::Feed;
+use crate::journey::LegRef;
+use crate::Result;
+use proto::{
+    Alert, Effect, FeedMessage, OccupancyStatus, StopScheduleRelationship, StopTimeEvent,
+    TranslatedString, TripScheduleRelationship, TripUpdate, VehiclePosition,
+};
+
+/// Where a GTFS Realtime feed is loaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RealtimeSource {
+    /// A file containing a protobuf-encoded `FeedMessage`.
+    File(PathBuf),
+    /// A URL to `GET` a protobuf-encoded `FeedMessage` from, using the requester of the client.
+    Url(url::Url),
+}
+
+/// Real-time data from GTFS Realtime feeds, with `TripUpdate`, `VehiclePosition` and `Alert` entities.
+#[derive(Debug, Clone, Default)]
+pub struct Realtime {
+    trip_updates: HashMap<String, Vec<TripUpdate>>,
+    vehicles: HashMap<String, Vec<VehiclePosition>>,
+    alerts: Vec<(String, Alert)>,
+}
+
+/// The real-time data of a trip at a stop, in unix timestamps.
+#[derive(Debug, Clone, Copy, Default)]
+struct StopRealtime {
+    arrival: Option<i64>,
+    departure: Option<i64>,
+    skipped: bool,
+    /// The stop served instead of the scheduled one.
+    assigned: Option<usize>,
+}
+
+impl Realtime {
+    /// Decode a protobuf-encoded `FeedMessage`.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut realtime = Self::default();
+        for entity in FeedMessage::decode(bytes)?.entity {
+            if entity.is_deleted.unwrap_or_default() {
+                continue;
+            }
+            if let Some(update) = entity.trip_update {
+                if let Some(trip) = update.trip.trip_id.clone() {
+                    realtime.trip_updates.entry(trip).or_default().push(update);
+                }
+            }
+            if let Some(vehicle) = entity.vehicle {
+                if let Some(trip) = vehicle.trip.as_ref().and_then(|t| t.trip_id.clone()) {
+                    realtime.vehicles.entry(trip).or_default().push(vehicle);
+                }
+            }
+            if let Some(alert) = entity.alert {
+                realtime.alerts.push((entity.id, alert));
+            }
+        }
+        Ok(realtime)
+    }
+
+    /// Add the data of another feed, e.g. if trip updates, vehicle positions and alerts are published separately.
+    pub fn merge(&mut self, other: Realtime) {
+        for (trip, updates) in other.trip_updates {
+            self.trip_updates.entry(trip).or_default().extend(updates);
+        }
+        for (trip, vehicles) in other.vehicles {
+            self.vehicles.entry(trip).or_default().extend(vehicles);
+        }
+        self.alerts.extend(other.alerts);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trip_updates.is_empty() && self.vehicles.is_empty() && self.alerts.is_empty()
+    }
+
+    /// The most recent trip update for a trip on a service day.
+    fn trip_update(&self, trip: &str, date: &str) -> Option<&TripUpdate> {
+        self.trip_updates
+            .get(trip)?
+            .iter()
+            .filter(|u| {
+                u.trip
+                    .start_date
+                    .as_deref()
+                    .map(|d| d == date)
+                    .unwrap_or(true)
+            })
+            .max_by_key(|u| u.timestamp)
+    }
+
+    fn load_factor(&self, trip: &str, date: &str) -> Option<LoadFactor> {
+        let vehicle = self
+            .vehicles
+            .get(trip)?
+            .iter()
+            .filter(|v| {
+                v.trip
+                    .as_ref()
+                    .and_then(|t| t.start_date.as_deref())
+                    .map(|d| d == date)
+                    .unwrap_or(true)
+            })
+            .max_by_key(|v| v.timestamp)?;
+        match OccupancyStatus::try_from(vehicle.occupancy_status?).ok()? {
+            OccupancyStatus::Empty
+            | OccupancyStatus::ManySeatsAvailable
+            | OccupancyStatus::FewSeatsAvailable => Some(LoadFactor::LowToMedium),
+            OccupancyStatus::StandingRoomOnly => Some(LoadFactor::High),
+            OccupancyStatus::CrushedStandingRoomOnly => Some(LoadFactor::VeryHigh),
+            OccupancyStatus::Full | OccupancyStatus::NotAcceptingPassengers => {
+                Some(LoadFactor::ExceptionallyHigh)
+            }
+            OccupancyStatus::NoDataAvailable | OccupancyStatus::NotBoardable => None,
+        }
+    }
+
+    /// The real-time data at every stop of a trip.
+    ///
+    /// Delays propagate to the following stops without updates, until a stop without data.
+    fn stops(
+        &self,
+        feed: &Feed,
+        update: &TripUpdate,
+        trip: usize,
+        start: i64,
+    ) -> Vec<StopRealtime> {
+        let delay_of = |event: &Option<StopTimeEvent>, planned: i64| {
+            let event = event.as_ref()?;
+            event
+                .time
+                .map(|t| t - planned)
+                .or(event.delay.map(i64::from))
+        };
+        let mut delay = update.delay.map(i64::from);
+        feed.trips[trip]
+            .stop_times
+            .iter()
+            .map(|stop_time| {
+                let id = &feed.stops[stop_time.stop].id;
+                let stop_update = update
+                    .stop_time_update
+                    .iter()
+                    .find(|u| match u.stop_sequence {
+                        Some(sequence) => sequence == stop_time.sequence,
+                        None => u.stop_id.as_ref() == Some(id),
+                    });
+                let (arrival, departure) = (start + stop_time.arrival, start + stop_time.departure);
+                let mut realtime = StopRealtime::default();
+                if let Some(u) = stop_update {
+                    match u.schedule_relationship() {
+                        StopScheduleRelationship::NoData => delay = None,
+                        StopScheduleRelationship::Skipped => realtime.skipped = true,
+                        _ => {
+                            let arrival_delay = delay_of(&u.arrival, arrival);
+                            let departure_delay = delay_of(&u.departure, departure);
+                            delay = departure_delay.or(arrival_delay).or(delay);
+                            realtime.arrival = arrival_delay.or(delay).map(|d| arrival + d);
+                        }
+                    }
+                    realtime.assigned = u
+                        .stop_time_properties
+                        .as_ref()
+                        .and_then(|p| p.assigned_stop_id.as_ref())
+                        .and_then(|s| feed.stop_index.get(s).copied());
+                }
+                if realtime.arrival.is_none() {
+                    realtime.arrival = delay.map(|d| arrival + d);
+                }
+                realtime.departure = delay.map(|d| departure + d);
+                realtime
+            })
+            .collect()
+    }
+
+    /// The alerts affecting a trip at one of the given stops between two times.
+    fn remarks(
+        &self,
+        feed: &Feed,
+        trip: usize,
+        stops: &[usize],
+        times: (i64, i64),
+        language: Option<&str>,
+    ) -> Vec<Remark> {
+        let t = &feed.trips[trip];
+        let route = &feed.routes[t.route];
+        let agency = route.agency.and_then(|a| feed.agencies[a].id.as_ref());
+        self.alerts
+            .iter()
+            .filter(|(_, alert)| {
+                alert.active_period.is_empty()
+                    || alert.active_period.iter().any(|p| {
+                        p.start.map(|s| s as i64 <= times.1).unwrap_or(true)
+                            && p.end.map(|e| times.0 <= e as i64).unwrap_or(true)
+                    })
+            })
+            .filter(|(_, alert)| {
+                alert.informed_entity.iter().any(|e| {
+                    let trip_id = e.trip.as_ref().and_then(|t| t.trip_id.as_ref());
+                    (e.agency_id.is_some()
+                        || e.route_id.is_some()
+                        || trip_id.is_some()
+                        || e.stop_id.is_some())
+                        && e.agency_id
+                            .as_ref()
+                            .map(|a| Some(a) == agency)
+                            .unwrap_or(true)
+                        && e.route_id.as_ref().map(|r| r == &route.id).unwrap_or(true)
+                        && trip_id.map(|i| i == &t.id).unwrap_or(true)
+                        && e.stop_id
+                            .as_ref()
+                            .map(|s| {
+                                stops.iter().any(|&stop| {
+                                    std::iter::once(stop)
+                                        .chain(feed.stops[stop].parent)
+                                        .any(|i| &feed.stops[i].id == s)
+                                })
+                            })
+                            .unwrap_or(true)
+                })
+            })
+            .filter_map(|(id, alert)| {
+                let header = text(&alert.header_text, language);
+                let description = text(&alert.description_text, language);
+                Some(Remark {
+                    code: id.clone(),
+                    text: description.clone().or(header.clone())?,
+                    r#type: match alert.effect() {
+                        Effect::NoService
+                        | Effect::ReducedService
+                        | Effect::SignificantDelays
+                        | Effect::Detour
+                        | Effect::ModifiedService
+                        | Effect::StopMoved => RemarkType::Status,
+                        _ => RemarkType::Hint,
+                    },
+                    association: RemarkAssociation::None,
+                    summary: description.and(header),
+                    trip_id: Some(t.id.clone()),
+                })
+            })
+            .collect()
+    }
+
+    /// Apply the real-time data to a journey built from the given legs.
+    pub(crate) fn apply(
+        &self,
+        feed: &Feed,
+        refs: &[LegRef],
+        journey: &mut Journey,
+        language: Option<&str>,
+    ) {
+        // The arrival of the previous leg, and whether it is a real-time one.
+        let mut previous_arrival: Option<(i64, bool)> = None;
+        // Whether the previous leg is cancelled or could not be reached itself.
+        let mut broken = false;
+        for (leg_ref, leg) in refs.iter().zip(journey.legs.iter_mut()) {
+            match leg_ref {
+                LegRef::Ride {
+                    trip,
+                    date,
+                    board,
+                    alight,
+                } => {
+                    if let Some(&t) = feed.trip_index.get(trip) {
+                        self.apply_ride(feed, t, date, (*board, *alight), leg, language);
+                    }
+                }
+                LegRef::Walk { duration, .. } => {
+                    // Walking starts as soon as the previous ride arrives.
+                    if let Some((arrival, true)) = previous_arrival {
+                        leg.departure = timestamp(feed, arrival);
+                        leg.arrival = timestamp(feed, arrival + duration);
+                    }
+                }
+            }
+            let departure = leg
+                .departure
+                .or(leg.planned_departure)
+                .map(|t| t.timestamp());
+            if let (Some((previous, _)), Some(departure)) = (previous_arrival, departure) {
+                leg.reachable = !broken && previous <= departure;
+            }
+            broken = leg.cancelled || !leg.reachable;
+            previous_arrival = match (leg.arrival, leg.planned_arrival) {
+                (Some(arrival), _) => Some((arrival.timestamp(), true)),
+                (None, planned) => planned.map(|p| (p.timestamp(), false)),
+            };
+        }
+    }
+
+    fn apply_ride(
+        &self,
+        feed: &Feed,
+        trip: usize,
+        date: &str,
+        (board, alight): (usize, usize),
+        leg: &mut Leg,
+        language: Option<&str>,
+    ) {
+        let Ok(day) = NaiveDate::parse_from_str(date, "%Y%m%d") else {
+            return;
+        };
+        let start = feed.service_day_start(day).timestamp();
+        let t = &feed.trips[trip];
+        let platform = |stop: usize, realtime: &StopRealtime| {
+            feed.stops[realtime.assigned.unwrap_or(stop)]
+                .platform
+                .clone()
+        };
+
+        if let Some(update) = self.trip_update(&t.id, date) {
+            let stops = self.stops(feed, update, trip, start);
+            let trip_cancelled = matches!(
+                update.trip.schedule_relationship(),
+                TripScheduleRelationship::Canceled | TripScheduleRelationship::Deleted
+            );
+            let (first, last) = (&stops[board], &stops[alight]);
+            leg.departure = first.departure.and_then(|d| timestamp(feed, d));
+            leg.arrival = last.arrival.and_then(|a| timestamp(feed, a));
+            if first.departure.is_some() {
+                leg.departure_platform = platform(t.stop_times[board].stop, first);
+            }
+            if last.arrival.is_some() {
+                leg.arrival_platform = platform(t.stop_times[alight].stop, last);
+            }
+            leg.cancelled = trip_cancelled || first.skipped || last.skipped;
+
+            // Intermediate locations are only present if stopovers were requested.
+            for (i, location) in leg.intermediate_locations.iter_mut().enumerate() {
+                let (IntermediateLocation::Stop(stop), Some(realtime)) =
+                    (location, stops.get(board + 1 + i))
+                else {
+                    continue;
+                };
+                let scheduled = t.stop_times[board + 1 + i].stop;
+                stop.arrival = realtime.arrival.and_then(|a| timestamp(feed, a));
+                stop.departure = realtime.departure.and_then(|d| timestamp(feed, d));
+                if realtime.arrival.is_some() || realtime.departure.is_some() {
+                    stop.arrival_platform = platform(scheduled, realtime);
+                    stop.departure_platform = platform(scheduled, realtime);
+                }
+                stop.cancelled = trip_cancelled || realtime.skipped;
+            }
+        }
+
+        leg.load_factor = self.load_factor(&t.id, date);
+        let times = (
+            start + t.stop_times[board].departure,
+            start + t.stop_times[alight].arrival,
+        );
+        leg.remarks = self.remarks(
+            feed,
+            trip,
+            &[t.stop_times[board].stop, t.stop_times[alight].stop],
+            times,
+            language,
+        );
+        for (i, location) in leg.intermediate_locations.iter_mut().enumerate() {
+            if let IntermediateLocation::Stop(stop) = location {
+                let stop_time = &t.stop_times[board + 1 + i];
+                let time = start + stop_time.arrival;
+                // Only alerts specific to the stop, as the others are already remarks of the leg.
+                stop.remarks = self
+                    .remarks(feed, trip, &[stop_time.stop], (time, time), language)
+                    .into_iter()
+                    .filter(|r| !leg.remarks.contains(r))
+                    .collect();
+            }
+        }
+    }
+}
+
+fn timestamp(feed: &Feed, timestamp: i64) -> Option<DateTime<chrono_tz::Tz>> {
+    DateTime::from_timestamp(timestamp, 0).map(|t| t.with_timezone(&feed.timezone))
+}
+
+/// The translation in the given language, or otherwise the one without language or the first one.
+fn text(text: &Option<TranslatedString>, language: Option<&str>) -> Option<String> {
+    let translations = &text.as_ref()?.translation;
+    translations
+        .iter()
+        .find(|t| language.is_some() && t.language.as_deref() == language)
+        .or_else(|| translations.iter().find(|t| t.language.is_none()))
+        .or_else(|| translations.first())
+        .map(|t| t.text.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+    use rcore::{
+        JourneysOptions, Place, Provider, RefreshJourneyOptions, ReplayRequester, Station,
+    };
+
+    use super::proto::*;
+    use super::*;
+    use crate::GtfsClient;
+
+    fn translated(texts: &[(&str, &str)]) -> Option<TranslatedString> {
+        Some(TranslatedString {
+            translation: texts
+                .iter()
+                .map(|(language, text)| Translation {
+                    text: text.to_string(),
+                    language: Some(language.to_string()),
+                })
+                .collect(),
+        })
+    }
+
+    fn trip(id: &str, relationship: TripScheduleRelationship) -> TripDescriptor {
+        TripDescriptor {
+            trip_id: Some(id.to_string()),
+            route_id: None,
+            start_date: Some("20241001".to_string()),
+            schedule_relationship: Some(relationship.into()),
+        }
+    }
+
+    fn message() -> FeedMessage {
+        let entity = |id: &str| FeedEntity {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: None,
+            },
+            entity: vec![
+                FeedEntity {
+                    trip_update: Some(TripUpdate {
+                        trip: trip("re5-1", TripScheduleRelationship::Scheduled),
+                        stop_time_update: vec![StopTimeUpdate {
+                            stop_sequence: Some(2),
+                            arrival: Some(StopTimeEvent {
+                                delay: Some(240),
+                                time: None,
+                            }),
+                            departure: Some(StopTimeEvent {
+                                delay: Some(300),
+                                time: None,
+                            }),
+                            stop_time_properties: Some(StopTimeProperties {
+                                assigned_stop_id: Some("koeln:2".to_string()),
+                            }),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }),
+                    ..entity("re5-1")
+                },
+                FeedEntity {
+                    trip_update: Some(TripUpdate {
+                        trip: trip("ice10-1", TripScheduleRelationship::Canceled),
+                        ..Default::default()
+                    }),
+                    ..entity("ice10-1")
+                },
+                FeedEntity {
+                    vehicle: Some(VehiclePosition {
+                        trip: Some(trip("re5-1", TripScheduleRelationship::Scheduled)),
+                        timestamp: None,
+                        occupancy_status: Some(OccupancyStatus::StandingRoomOnly.into()),
+                    }),
+                    ..entity("vehicle")
+                },
+                FeedEntity {
+                    alert: Some(Alert {
+                        informed_entity: vec![EntitySelector {
+                            route_id: Some("re5".to_string()),
+                            ..Default::default()
+                        }],
+                        effect: Some(Effect::SignificantDelays.into()),
+                        header_text: translated(&[("de", "Verspätungen"), ("en", "Delays")]),
+                        description_text: translated(&[
+                            ("de", "Wegen einer Störung"),
+                            ("en", "Due to a disruption"),
+                        ]),
+                        ..Default::default()
+                    }),
+                    ..entity("alert")
+                },
+            ],
+        }
+    }
+
+    fn client(sources: Vec<RealtimeSource>) -> GtfsClient<ReplayRequester> {
+        GtfsClient::new(
+            Feed::open(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/feed")).unwrap(),
+            ReplayRequester::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/replay")),
+        )
+        .with_realtime(sources)
+    }
+
+    async fn query(client: &GtfsClient<ReplayRequester>, regional: bool) -> Journey {
+        let mut opts = JourneysOptions {
+            departure: Some(
+                chrono_tz::Europe::Berlin
+                    .with_ymd_and_hms(2024, 10, 1, 9, 30, 0)
+                    .unwrap(),
+            ),
+            results: 1,
+            stopovers: true,
+            language: Some("en".to_string()),
+            ..Default::default()
+        };
+        if !regional {
+            opts.products =
+                std::collections::HashSet::from([rcore::Mode::HighSpeedTrain, rcore::Mode::Bus])
+                    .into();
+        }
+        let station = |id: &str| {
+            Place::Station(Station {
+                id: id.to_string(),
+                ..Default::default()
+            })
+        };
+        Provider::<ReplayRequester>::journeys(client, station("koeln"), station("beuel"), opts)
+            .await
+            .unwrap()
+            .journeys
+            .remove(0)
+    }
+
+    #[tokio::test]
+    async fn applies_realtime() {
+        // Removed when dropped, also if an assertion fails.
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), message().encode_to_vec()).unwrap();
+        let client = client(vec![RealtimeSource::File(file.path().to_path_buf())]);
+
+        let journey = query(&client, true).await;
+        let (ride, walk) = (&journey.legs[0], &journey.legs[1]);
+        let minutes =
+            |t: Option<DateTime<chrono_tz::Tz>>| t.map(|t| t.format("%H:%M").to_string()).unwrap();
+        assert_eq!(minutes(ride.departure), "09:50");
+        assert_eq!(ride.departure_platform.as_deref(), Some("2"));
+        // The delay propagates to the following stops.
+        assert_eq!(minutes(ride.arrival), "10:15");
+        assert_eq!(ride.load_factor, Some(LoadFactor::High));
+        assert_eq!(ride.remarks.len(), 1);
+        assert_eq!(ride.remarks[0].text, "Due to a disruption");
+        assert_eq!(ride.remarks[0].summary.as_deref(), Some("Delays"));
+        assert_eq!(minutes(walk.departure), "10:15");
+        assert_eq!(minutes(walk.arrival), "10:25");
+
+        let refreshed = Provider::<ReplayRequester>::refresh_journey(
+            &client,
+            &journey,
+            RefreshJourneyOptions {
+                stopovers: true,
+                language: Some("en".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(refreshed, journey);
+
+        let cancelled = query(&client, false).await;
+        assert!(cancelled.legs[0].cancelled);
+        assert!(!cancelled.legs[1].reachable);
+    }
+
+    #[tokio::test]
+    async fn handles_unavailable_realtime() {
+        let client = client(vec![RealtimeSource::Url(
+            "http://localhost:8080/gtfs-rt".parse().unwrap(),
+        )]);
+        // Journeys are returned with planned times only.
+        let journey = query(&client, true).await;
+        assert!(journey.legs[0].departure.is_none());
+        assert!(matches!(
+            Provider::<ReplayRequester>::refresh_journey(
+                &client,
+                &journey,
+                RefreshJourneyOptions::default()
+            )
+            .await,
+            Err(rcore::Error::Request(_))
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+//! The messages of GTFS Realtime used by the overlay, see <https://gtfs.org/realtime/reference/>.
+//!
+//! Fields not listed here are skipped when decoding.
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FeedMessage {
+    #[prost(message, required, tag = "1")]
+    pub header: FeedHeader,
+    #[prost(message, repeated, tag = "2")]
+    pub entity: Vec<FeedEntity>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FeedHeader {
+    #[prost(string, required, tag = "1")]
+    pub gtfs_realtime_version: String,
+    #[prost(uint64, optional, tag = "3")]
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FeedEntity {
+    #[prost(string, required, tag = "1")]
+    pub id: String,
+    #[prost(bool, optional, tag = "2")]
+    pub is_deleted: Option<bool>,
+    #[prost(message, optional, tag = "3")]
+    pub trip_update: Option<TripUpdate>,
+    #[prost(message, optional, tag = "4")]
+    pub vehicle: Option<VehiclePosition>,
+    #[prost(message, optional, tag = "5")]
+    pub alert: Option<Alert>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TripUpdate {
+    #[prost(message, required, tag = "1")]
+    pub trip: TripDescriptor,
+    #[prost(message, repeated, tag = "2")]
+    pub stop_time_update: Vec<StopTimeUpdate>,
+    #[prost(uint64, optional, tag = "4")]
+    pub timestamp: Option<u64>,
+    #[prost(int32, optional, tag = "5")]
+    pub delay: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StopTimeEvent {
+    #[prost(int32, optional, tag = "1")]
+    pub delay: Option<i32>,
+    #[prost(int64, optional, tag = "2")]
+    pub time: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StopTimeUpdate {
+    #[prost(uint32, optional, tag = "1")]
+    pub stop_sequence: Option<u32>,
+    #[prost(string, optional, tag = "4")]
+    pub stop_id: Option<String>,
+    #[prost(message, optional, tag = "2")]
+    pub arrival: Option<StopTimeEvent>,
+    #[prost(message, optional, tag = "3")]
+    pub departure: Option<StopTimeEvent>,
+    #[prost(enumeration = "StopScheduleRelationship", optional, tag = "5")]
+    pub schedule_relationship: Option<i32>,
+    #[prost(message, optional, tag = "6")]
+    pub stop_time_properties: Option<StopTimeProperties>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum StopScheduleRelationship {
+    Scheduled = 0,
+    Skipped = 1,
+    NoData = 2,
+    Unscheduled = 3,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StopTimeProperties {
+    /// The stop, usually another platform of the same station, the trip serves instead of the scheduled one.
+    #[prost(string, optional, tag = "1")]
+    pub assigned_stop_id: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VehiclePosition {
+    #[prost(message, optional, tag = "1")]
+    pub trip: Option<TripDescriptor>,
+    #[prost(uint64, optional, tag = "5")]
+    pub timestamp: Option<u64>,
+    #[prost(enumeration = "OccupancyStatus", optional, tag = "9")]
+    pub occupancy_status: Option<i32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum OccupancyStatus {
+    Empty = 0,
+    ManySeatsAvailable = 1,
+    FewSeatsAvailable = 2,
+    StandingRoomOnly = 3,
+    CrushedStandingRoomOnly = 4,
+    Full = 5,
+    NotAcceptingPassengers = 6,
+    NoDataAvailable = 7,
+    NotBoardable = 8,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Alert {
+    #[prost(message, repeated, tag = "1")]
+    pub active_period: Vec<TimeRange>,
+    #[prost(message, repeated, tag = "5")]
+    pub informed_entity: Vec<EntitySelector>,
+    #[prost(enumeration = "Effect", optional, tag = "7")]
+    pub effect: Option<i32>,
+    #[prost(message, optional, tag = "10")]
+    pub header_text: Option<TranslatedString>,
+    #[prost(message, optional, tag = "11")]
+    pub description_text: Option<TranslatedString>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+// Named as in the specification.
+#[allow(clippy::enum_variant_names)]
+pub enum Effect {
+    NoService = 1,
+    ReducedService = 2,
+    SignificantDelays = 3,
+    Detour = 4,
+    AdditionalService = 5,
+    ModifiedService = 6,
+    OtherEffect = 7,
+    UnknownEffect = 8,
+    StopMoved = 9,
+    NoEffect = 10,
+    AccessibilityIssue = 11,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TimeRange {
+    #[prost(uint64, optional, tag = "1")]
+    pub start: Option<u64>,
+    #[prost(uint64, optional, tag = "2")]
+    pub end: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EntitySelector {
+    #[prost(string, optional, tag = "1")]
+    pub agency_id: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub route_id: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    pub trip: Option<TripDescriptor>,
+    #[prost(string, optional, tag = "5")]
+    pub stop_id: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TranslatedString {
+    #[prost(message, repeated, tag = "1")]
+    pub translation: Vec<Translation>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Translation {
+    #[prost(string, required, tag = "1")]
+    pub text: String,
+    #[prost(string, optional, tag = "2")]
+    pub language: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TripDescriptor {
+    #[prost(string, optional, tag = "1")]
+    pub trip_id: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub route_id: Option<String>,
+    /// The service day, as `YYYYMMDD`.
+    #[prost(string, optional, tag = "3")]
+    pub start_date: Option<String>,
+    #[prost(enumeration = "TripScheduleRelationship", optional, tag = "4")]
+    pub schedule_relationship: Option<i32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum TripScheduleRelationship {
+    Scheduled = 0,
+    Added = 1,
+    Unscheduled = 2,
+    Canceled = 3,
+    Replacement = 5,
+    Duplicated = 6,
+    Deleted = 7,
+}
@@ -1,8 +1,11 @@
 use async_trait::async_trait;
 use chrono::Utc;
+#[cfg(feature = "polylines")]
+use rcore::PolylineFormat;
 use rcore::{
     Journey, JourneysOptions, JourneysResponse, LocationsOptions, LocationsResponse, LoyaltyCard,
     Place, Provider, RefreshJourneyOptions, RefreshJourneyResponse, Requester, TransferOptions,
+    WalkingSpeed,
 };
 use serde_json::json;
 
@@ -12,6 +15,18 @@ use crate::{
     parse::{journeys_response::HafasJourneysResponse, locations_response::HafasLocationsResponse},
 };
 
+/// Shape the polylines of the legs as requested, as they are parsed into one `Point` per coordinate.
+#[cfg(feature = "polylines")]
+fn format_polylines(journeys: &mut [Journey], format: PolylineFormat) {
+    if format == PolylineFormat::LineString {
+        for leg in journeys.iter_mut().flat_map(|j| j.legs.iter_mut()) {
+            if let Some(polyline) = &mut leg.polyline {
+                *polyline = rcore::polyline_to_line_string(polyline);
+            }
+        }
+    }
+}
+
 #[cfg_attr(feature = "rt-multi-thread", async_trait)]
 #[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
 impl<R: Requester> Provider<R> for HafasClient<R> {
@@ -67,6 +82,56 @@ impl<R: Requester> Provider<R> for HafasClient<R> {
             (None, None) => (Utc::now().with_timezone(&timezone), true),
         };
 
+        if opts.via.iter().any(|v| v.pass_through) && !self.profile.journeys_via_pass_through() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "passing through a via place without stopping".to_string(),
+            )))?
+        }
+        if opts.via.iter().any(|v| v.min_dwell_time.is_some())
+            && !self.profile.journeys_via_dwell_time()
+        {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "minimum dwell time at a via place".to_string(),
+            )))?
+        }
+        if !opts.avoid.is_empty() && !self.profile.journeys_avoid() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "avoiding places".to_string(),
+            )))?
+        }
+
+        if opts.walking_speed != WalkingSpeed::default() && !self.profile.journeys_walking_speed() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "walking speed".to_string(),
+            )))?
+        }
+        if opts.max_walking_distance.is_some() && !self.profile.journeys_max_walking_distance() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "maximum walking distance".to_string(),
+            )))?
+        }
+        let mut meta_filters = vec![];
+        for (avoid, meta, name) in [
+            (
+                opts.avoid_stairs,
+                self.profile.avoid_stairs_to_hafas(),
+                "avoiding stairs",
+            ),
+            (
+                opts.avoid_escalators,
+                self.profile.avoid_escalators_to_hafas(),
+                "avoiding escalators",
+            ),
+        ] {
+            match (avoid, meta) {
+                (true, Some(meta)) => meta_filters.push(meta),
+                (true, None) => Err(rcore::Error::Provider(Self::Error::Unsupported(
+                    name.to_string(),
+                )))?,
+                (false, _) => {}
+            }
+        }
+
         let tariff_class = opts.tariff_class;
 
         let mut req = json!({
@@ -86,7 +151,8 @@ impl<R: Requester> Provider<R> for HafasClient<R> {
                         "minChgTime": opts.transfer_time.num_minutes(),
                         "numF": opts.results,
                         "depLocL": [ from.to_hafas() ],
-                        "viaLocL": json!(opts.via.into_iter().map(|x| json!({ "loc": x.to_hafas() })).collect::<Vec<_>>()),
+                        "viaLocL": json!(opts.via.iter().map(|x| x.to_hafas()).collect::<Vec<_>>()),
+                        "antiViaLocL": json!(opts.avoid.iter().map(|x| json!({ "loc": x.to_hafas() })).collect::<Vec<_>>()),
                         "arrLocL": [ to.to_hafas() ],
                         "jnyFltrL": [
                             {
@@ -131,8 +197,37 @@ impl<R: Requester> Provider<R> for HafasClient<R> {
         {
             req["svcReqL"][0]["req"]["getPolyline"] = json!(false);
         }
-        if let Some(r) = opts.later_than.or(opts.earlier_than) {
-            req["svcReqL"][0]["req"]["ctxScr"] = json!(r);
+        match (opts.earlier_than, opts.later_than) {
+            (Some(_), Some(_)) => Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                "earlier_than and later_than are mutually exclusive".to_string(),
+            )))?,
+            (Some(r), None) | (None, Some(r)) => {
+                req["svcReqL"][0]["req"]["ctxScr"] = json!(r);
+            }
+            (None, None) => {}
+        }
+        for meta in meta_filters {
+            req["svcReqL"][0]["req"]["jnyFltrL"]
+                .as_array_mut()
+                .unwrap()
+                .push(json!({"type": "META", "mode": "INC", "meta": meta}))
+        }
+        let mut walking_filter = json!({"type": "M", "mode": "FB"});
+        if self.profile.journeys_walking_speed() {
+            walking_filter["meta"] = json!(opts.walking_speed.to_hafas());
+        }
+        if let Some(max_distance) = opts.max_walking_distance {
+            walking_filter["profile"] = json!({
+                "type": "F",
+                "linDistRouting": false,
+                "maxdist": max_distance,
+            });
+        }
+        if walking_filter.as_object().is_some_and(|f| f.len() > 2) {
+            req["svcReqL"][0]["req"]["gisFltrL"]
+                .as_array_mut()
+                .unwrap()
+                .push(walking_filter)
         }
         if opts.bike_friendly {
             req["svcReqL"][0]["req"]["jnyFltrL"]
@@ -142,10 +237,14 @@ impl<R: Requester> Provider<R> for HafasClient<R> {
         }
         let data = self.request(req).await?;
 
-        Ok(self
+        #[allow(unused_mut)]
+        let mut response = self
             .profile
             .parse_journeys_response(data, tariff_class)
-            .map_err(|e| rcore::Error::Provider(e.into()))?)
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        #[cfg(feature = "polylines")]
+        format_polylines(&mut response.journeys, opts.polyline_format);
+        Ok(response)
     }
 
     async fn refresh_journey(
@@ -189,6 +288,8 @@ impl<R: Requester> Provider<R> for HafasClient<R> {
             .profile
             .parse_journeys_response(data, tariff_class)
             .map_err(|e| rcore::Error::Provider(e.into()))?;
+        #[cfg(feature = "polylines")]
+        format_polylines(&mut journeys.journeys, opts.polyline_format);
         Ok(journeys.journeys.remove(0))
     }
 }
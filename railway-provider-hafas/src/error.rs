@@ -46,6 +46,8 @@ pub enum Error {
     Hafas { code: String, text: String },
     #[error("{0}")]
     InvalidInput(String),
+    #[error("not supported by this profile: {0}")]
+    Unsupported(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,4 +1,4 @@
-use super::{Accessibility, Location, Place, Station, TariffClass};
+use super::{Accessibility, Location, Place, Station, TariffClass, Via, WalkingSpeed};
 use serde_json::json;
 
 pub trait ToHafas<T> {
@@ -66,6 +66,20 @@ impl ToHafas<serde_json::Value> for Place {
     }
 }
 
+impl ToHafas<serde_json::Value> for Via {
+    fn to_hafas(&self) -> serde_json::Value {
+        let mut via = json!({ "loc": self.place.to_hafas() });
+        if let Some(dwell_time) = self.min_dwell_time {
+            via["min"] = json!(dwell_time.num_minutes());
+        }
+        if self.pass_through {
+            // Neither boarding nor alighting is required at the via place.
+            via["status"] = json!("NPNM");
+        }
+        via
+    }
+}
+
 impl ToHafas<String> for Accessibility {
     fn to_hafas(&self) -> String {
         match self {
@@ -77,6 +91,17 @@ impl ToHafas<String> for Accessibility {
     }
 }
 
+impl ToHafas<String> for WalkingSpeed {
+    fn to_hafas(&self) -> String {
+        match self {
+            WalkingSpeed::Slow => "foot_speed_slow",
+            WalkingSpeed::Normal => "foot_speed_normal",
+            WalkingSpeed::Fast => "foot_speed_fast",
+        }
+        .to_string()
+    }
+}
+
 impl ToHafas<u64> for TariffClass {
     fn to_hafas(&self) -> u64 {
         match *self {
@@ -85,3 +110,43 @@ impl ToHafas<u64> for TariffClass {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn station() -> Place {
+        Place::Station(Station {
+            id: "8000105".to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn formats_via() {
+        assert_eq!(
+            Via::from(station()).to_hafas(),
+            json!({ "loc": { "type": "S", "lid": "A=1@L=8000105@" } })
+        );
+        assert_eq!(
+            Via {
+                place: station(),
+                min_dwell_time: Some(Duration::minutes(15)),
+                pass_through: false,
+            }
+            .to_hafas(),
+            json!({ "loc": { "type": "S", "lid": "A=1@L=8000105@" }, "min": 15 })
+        );
+        assert_eq!(
+            Via {
+                place: station(),
+                min_dwell_time: None,
+                pass_through: true,
+            }
+            .to_hafas(),
+            json!({ "loc": { "type": "S", "lid": "A=1@L=8000105@" }, "status": "NPNM" })
+        );
+    }
+}
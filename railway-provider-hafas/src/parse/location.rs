@@ -50,6 +50,7 @@ pub(crate) fn default_parse_place<P: Profile + ?Sized>(
                     longitude: coords.1,
                     poi: None,
                 }),
+                accessibility: None,
             }))
         }
         Some("P") => Ok(Place::Location(Location::Point {
@@ -132,5 +132,6 @@ pub(crate) fn default_parse_stopover<P: Profile + ?Sized>(
         planned_departure_platform: dep.planned_platform,
         cancelled: cancelled.unwrap_or_default(),
         remarks: remarks.unwrap_or_default(),
+        accessibility: None,
     })
 }
@@ -84,6 +84,24 @@ impl Profile for DbProfile {
     fn salt(&self) -> bool {
         true
     }
+    fn journeys_via_dwell_time(&self) -> bool {
+        true
+    }
+    fn journeys_avoid(&self) -> bool {
+        true
+    }
+    fn journeys_walking_speed(&self) -> bool {
+        true
+    }
+    fn journeys_max_walking_distance(&self) -> bool {
+        true
+    }
+    fn avoid_stairs_to_hafas(&self) -> Option<&'static str> {
+        Some("noStairs")
+    }
+    fn avoid_escalators_to_hafas(&self) -> Option<&'static str> {
+        Some("noEscalators")
+    }
 
     fn products(&self) -> &'static [&'static Product] {
         products::PRODUCTS
@@ -176,11 +194,57 @@ impl Profile for DbProfile {
 
 #[cfg(test)]
 mod test {
+    use crate::client::HafasClient;
     use crate::profile::test::{check_journey, check_search};
+    use crate::{Place, Station};
+    use async_trait::async_trait;
+    use rcore::{JourneysOptions, Provider, Requester, RequesterBuilder};
     use std::error::Error;
+    use std::sync::{Arc, Mutex};
 
     use super::*;
 
+    /// A requester keeping the bodies of the requests, without answering them.
+    #[derive(Default, Clone)]
+    struct BodyRequester(Arc<Mutex<Vec<Value>>>);
+
+    impl RequesterBuilder for BodyRequester {
+        type Requester = BodyRequester;
+
+        fn with_pem_bundle(self, _bytes: &[u8]) -> Self {
+            self
+        }
+
+        fn build(self) -> Self::Requester {
+            self
+        }
+    }
+
+    #[cfg_attr(feature = "rt-multi-thread", async_trait)]
+    #[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+    impl Requester for BodyRequester {
+        type Error = std::io::Error;
+
+        async fn get(
+            &self,
+            url: &url::Url,
+            body: &[u8],
+            headers: HashMap<&str, &str>,
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.post(url, body, headers).await
+        }
+
+        async fn post(
+            &self,
+            _url: &url::Url,
+            body: &[u8],
+            _headers: HashMap<&str, &str>,
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.0.lock().unwrap().push(serde_json::from_slice(body)?);
+            Err(std::io::ErrorKind::Unsupported.into())
+        }
+    }
+
     #[tokio::test]
     async fn test_search() -> Result<(), Box<dyn Error>> {
         check_search(DbProfile {}, "Bayr", "Bayreuth Hbf").await
@@ -190,4 +254,40 @@ mod test {
     async fn test_path_available() -> Result<(), Box<dyn Error>> {
         check_journey(DbProfile {}, "8011167", "8000261").await
     }
+
+    #[tokio::test]
+    async fn test_avoid_stairs_and_escalators() {
+        let requester = BodyRequester::default();
+        let client = HafasClient::new(DbProfile {}, requester.clone());
+        let station = |id: &str| {
+            Place::Station(Station {
+                id: id.to_string(),
+                ..Default::default()
+            })
+        };
+        let _ = client
+            .journeys(
+                station("8011167"),
+                station("8000261"),
+                JourneysOptions {
+                    avoid_stairs: true,
+                    avoid_escalators: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        let bodies = requester.0.lock().unwrap();
+        let filters = bodies[0]["svcReqL"][0]["req"]["jnyFltrL"]
+            .as_array()
+            .unwrap();
+        for meta in ["noStairs", "noEscalators"] {
+            assert!(
+                filters.contains(&json!({ "type": "META", "mode": "INC", "meta": meta })),
+                "expected the filter {} in {:?}",
+                meta,
+                filters
+            );
+        }
+    }
 }
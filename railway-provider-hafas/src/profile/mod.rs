@@ -82,6 +82,9 @@ pub mod vvt;
 pub mod vvv;
 // ADD PROFILE HERE
 
+mod names;
+pub use names::*;
+
 // TODO:
 // BVG: Too many special things for now
 
@@ -149,6 +152,29 @@ pub trait Profile: Send + Sync {
     fn salt(&self) -> bool {
         false
     }
+    fn journeys_via_dwell_time(&self) -> bool {
+        false
+    }
+    fn journeys_via_pass_through(&self) -> bool {
+        false
+    }
+    fn journeys_avoid(&self) -> bool {
+        false
+    }
+    fn journeys_walking_speed(&self) -> bool {
+        false
+    }
+    fn journeys_max_walking_distance(&self) -> bool {
+        false
+    }
+    /// The `META` journey filter to avoid stairs, if the profile supports it.
+    fn avoid_stairs_to_hafas(&self) -> Option<&'static str> {
+        None
+    }
+    /// The `META` journey filter to avoid escalators, if the profile supports it.
+    fn avoid_escalators_to_hafas(&self) -> Option<&'static str> {
+        None
+    }
 
     fn products(&self) -> &'static [&'static Product];
 
@@ -295,6 +321,27 @@ impl<T: Profile + ?Sized> Profile for Box<T> {
     fn salt(&self) -> bool {
         (**self).salt()
     }
+    fn journeys_via_dwell_time(&self) -> bool {
+        (**self).journeys_via_dwell_time()
+    }
+    fn journeys_via_pass_through(&self) -> bool {
+        (**self).journeys_via_pass_through()
+    }
+    fn journeys_avoid(&self) -> bool {
+        (**self).journeys_avoid()
+    }
+    fn journeys_walking_speed(&self) -> bool {
+        (**self).journeys_walking_speed()
+    }
+    fn journeys_max_walking_distance(&self) -> bool {
+        (**self).journeys_max_walking_distance()
+    }
+    fn avoid_stairs_to_hafas(&self) -> Option<&'static str> {
+        (**self).avoid_stairs_to_hafas()
+    }
+    fn avoid_escalators_to_hafas(&self) -> Option<&'static str> {
+        (**self).avoid_escalators_to_hafas()
+    }
 
     fn products(&self) -> &'static [&'static Product] {
         (**self).products()
@@ -325,6 +372,9 @@ impl<T: Profile + ?Sized> Profile for Box<T> {
     fn parse_remark(&self, data: HafasRemark) -> ParseResult<Remark> {
         (**self).parse_remark(data)
     }
+    fn remark_association(&self, code: &str) -> RemarkAssociation {
+        (**self).remark_association(code)
+    }
     fn parse_products(&self, p_cls: u16) -> Vec<&'static Product> {
         (**self).parse_products(p_cls)
     }
@@ -0,0 +1,70 @@
+use super::*;
+
+macro_rules! profiles {
+    ($($name:literal if $feature:literal => $profile:path),* $(,)?) => {
+        const ALL_NAMES: &[&str] = &[$($name),*];
+
+        /// The profile with the given name, if compiled in.
+        ///
+        /// The names are the ones of the profile features, without the `-profile` suffix, e.g. `db` or `irish-rail`.
+        pub fn by_name(name: &str) -> Option<Box<dyn Profile>> {
+            match name {
+                $(
+                    #[cfg(feature = $feature)]
+                    $name => Some(Box::new($profile)),
+                )*
+                _ => None,
+            }
+        }
+    };
+}
+
+profiles! {
+    "avv" if "avv-profile" => avv::AvvProfile,
+    "bart" if "bart-profile" => bart::BartProfile,
+    "bls" if "bls-profile" => bls::BlsProfile,
+    "cfl" if "cfl-profile" => cfl::CflProfile,
+    "cmta" if "cmta-profile" => cmta::CmtaProfile,
+    "dart" if "dart-profile" => dart::DartProfile,
+    "db" if "db-profile" => db::DbProfile,
+    "insa" if "insa-profile" => insa::InsaProfile,
+    "irish-rail" if "irish-rail-profile" => irish_rail::IrishRailProfile,
+    "ivb" if "ivb-profile" => ivb::IvbProfile,
+    "kvb" if "kvb-profile" => kvb::KvbProfile,
+    "mobil-nrw" if "mobil-nrw-profile" => mobil_nrw::MobilNrwProfile,
+    "mobiliteit-lu" if "mobiliteit-lu-profile" => mobiliteit_lu::MobiliteitLuProfile,
+    "nahsh" if "nahsh-profile" => nahsh::NahSHProfile,
+    "nvv" if "nvv-profile" => nvv::NvvProfile,
+    "oebb" if "oebb-profile" => oebb::OebbProfile,
+    "ooevv" if "ooevv-profile" => ooevv::OoevvProfile,
+    "pkp" if "pkp-profile" => pkp::PkpProfile,
+    "rejseplanen" if "rejseplanen-profile" => rejseplanen::RejseplanenProfile,
+    "resrobot" if "resrobot-profile" => resrobot::ResrobotProfile,
+    "rmv" if "rmv-profile" => rmv::RmvProfile,
+    "rsag" if "rsag-profile" => rsag::RsagProfile,
+    "saarvv" if "saarvv-profile" => saarvv::SaarvvProfile,
+    "salzburg" if "salzburg-profile" => salzburg::SalzburgProfile,
+    "sbahn-muenchen" if "sbahn-muenchen-profile" => sbahn_muenchen::SBahnMuenchenProfile,
+    "svv" if "svv-profile" => svv::SvvProfile,
+    "vbb" if "vbb-profile" => vbb::VbbProfile,
+    "vbn" if "vbn-profile" => vbn::VbnProfile,
+    "verbundlinie" if "verbundlinie-profile" => verbundlinie::VerbundlinieProfile,
+    "vgi" if "vgi-profile" => vgi::VgiProfile,
+    "vkg" if "vkg-profile" => vkg::VkgProfile,
+    "vmt" if "vmt-profile" => vmt::VmtProfile,
+    "vor" if "vor-profile" => vor::VorProfile,
+    "vos" if "vos-profile" => vos::VosProfile,
+    "vrn" if "vrn-profile" => vrn::VrnProfile,
+    "vsn" if "vsn-profile" => vsn::VsnProfile,
+    "vvt" if "vvt-profile" => vvt::VvtProfile,
+    "vvv" if "vvv-profile" => vvv::VvvProfile,
+}
+
+/// The names of the compiled-in profiles, see [`by_name`].
+pub fn names() -> Vec<&'static str> {
+    ALL_NAMES
+        .iter()
+        .copied()
+        .filter(|name| by_name(name).is_some())
+        .collect()
+}
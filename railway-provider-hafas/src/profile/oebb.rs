@@ -92,6 +92,12 @@ impl Profile for OebbProfile {
     fn refresh_journey_use_out_recon_l(&self) -> bool {
         true
     }
+    fn journeys_via_dwell_time(&self) -> bool {
+        true
+    }
+    fn journeys_avoid(&self) -> bool {
+        true
+    }
 
     fn products(&self) -> &'static [&'static Product] {
         products::PRODUCTS
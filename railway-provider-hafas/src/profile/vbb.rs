@@ -58,6 +58,12 @@ impl Profile for VbbProfile {
     fn refresh_journey_use_out_recon_l(&self) -> bool {
         true
     }
+    fn journeys_via_dwell_time(&self) -> bool {
+        true
+    }
+    fn journeys_avoid(&self) -> bool {
+        true
+    }
 
     fn products(&self) -> &'static [&'static Product] {
         products::PRODUCTS
@@ -90,7 +96,10 @@ impl Profile for VbbProfile {
 mod test {
     use std::error::Error;
 
+    use crate::client::HafasClient;
     use crate::profile::test::{check_journey, check_search};
+    use crate::{Place, Station};
+    use rcore::{JourneysOptions, Provider, ReplayRequester, Via, WalkingSpeed};
 
     use super::*;
 
@@ -103,4 +112,52 @@ mod test {
     async fn test_path_available() -> Result<(), Box<dyn Error>> {
         check_journey(VbbProfile {}, "900003201", "900024101").await
     }
+
+    #[tokio::test]
+    async fn test_unsupported_options() {
+        let client = HafasClient::new(
+            VbbProfile {},
+            ReplayRequester::new(env!("CARGO_MANIFEST_DIR")),
+        );
+        let station = |id: &str| {
+            Place::Station(Station {
+                id: id.to_string(),
+                ..Default::default()
+            })
+        };
+        for opts in [
+            JourneysOptions {
+                via: vec![Via {
+                    place: station("900100003"),
+                    min_dwell_time: None,
+                    pass_through: true,
+                }],
+                ..Default::default()
+            },
+            JourneysOptions {
+                walking_speed: WalkingSpeed::Slow,
+                ..Default::default()
+            },
+            JourneysOptions {
+                max_walking_distance: Some(500),
+                ..Default::default()
+            },
+            JourneysOptions {
+                avoid_stairs: true,
+                ..Default::default()
+            },
+        ] {
+            let result = client
+                .journeys(station("900003201"), station("900024101"), opts)
+                .await;
+            assert!(
+                matches!(
+                    result,
+                    Err(rcore::Error::Provider(crate::Error::Unsupported(_)))
+                ),
+                "expected the option to be unsupported, got {:?}",
+                result
+            );
+        }
+    }
 }
@@ -0,0 +1,393 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use rcore::{
+    Accessibility, Journey, JourneyRef, JourneysOptions, JourneysResponse, LocationsOptions,
+    LocationsResponse, Place, Provider, RefreshJourneyOptions, RefreshJourneyResponse, Requester,
+    TransferOptions, WalkingSpeed, REFRESH_RESULTS,
+};
+
+use crate::client::MotisClient;
+use crate::parse::journey::{parse_journey, MotisPlanResponse, PlanQuery};
+use crate::parse::leg::LegOptions;
+use crate::parse::location::{parse_match, place_to_motis, MotisMatch};
+use crate::products::products_to_motis;
+use crate::{Error, ParseError};
+
+/// MOTIS routes via at most two stops.
+const MAX_VIA: usize = 2;
+
+fn format_time<T: TimeZone>(time: &DateTime<T>) -> String {
+    time.with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl<R: Requester> MotisClient<R> {
+    async fn plan(
+        &self,
+        mut params: Vec<(&str, String)>,
+        language: Option<String>,
+    ) -> Result<MotisPlanResponse, rcore::Error<R::Error, Error>> {
+        // The transfers are returned as walks from stop to stop, without the path in the station.
+        params.push(("detailedTransfers", "false".to_string()));
+        if let Some(language) = language {
+            params.push(("language", language));
+        }
+        self.request("api/v1/plan", params).await
+    }
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl<R: Requester> Provider<R> for MotisClient<R> {
+    type Error = crate::Error;
+
+    async fn locations(
+        &self,
+        opts: LocationsOptions,
+    ) -> Result<LocationsResponse, rcore::Error<R::Error, Self::Error>> {
+        let mut params = vec![("text", opts.query)];
+        if let Some(language) = opts.language {
+            params.push(("language", language));
+        }
+        let data: Vec<MotisMatch> = self.request("api/v1/geocode", params).await?;
+
+        Ok(data
+            .into_iter()
+            .take(opts.results as usize)
+            .map(parse_match)
+            .collect())
+    }
+
+    async fn journeys(
+        &self,
+        from: Place,
+        to: Place,
+        opts: JourneysOptions,
+    ) -> Result<JourneysResponse, rcore::Error<R::Error, Self::Error>> {
+        if !opts.avoid.is_empty() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "avoiding places".to_string(),
+            )))?
+        }
+        if opts.via.len() > MAX_VIA {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(format!(
+                "more than {} via places",
+                MAX_VIA
+            ))))?
+        }
+        if opts
+            .via
+            .iter()
+            .any(|v| v.pass_through || !matches!(v.place, Place::Station(_)))
+        {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "via places other than stations to stop at".to_string(),
+            )))?
+        }
+        if opts.max_walking_distance.is_some() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "maximum walking distance".to_string(),
+            )))?
+        }
+        if opts.walking_speed != WalkingSpeed::Normal {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "walking speed".to_string(),
+            )))?
+        }
+
+        let mut params = vec![
+            ("fromPlace", place_to_motis(&from)),
+            ("toPlace", place_to_motis(&to)),
+        ];
+        // The cursor of a page already contains the time to search at.
+        match (
+            opts.earlier_than,
+            opts.later_than,
+            opts.departure,
+            opts.arrival,
+        ) {
+            (Some(_), Some(_), _, _) => Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                "earlier_than and later_than are mutually exclusive".to_string(),
+            )))?,
+            (Some(cursor), None, _, _) | (None, Some(cursor), _, _) => {
+                params.push(("pageCursor", cursor))
+            }
+            (None, None, Some(_), Some(_)) => {
+                Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                    "departure and arrival are mutually exclusive".to_string(),
+                )))?
+            }
+            (None, None, Some(departure), None) => {
+                params.push(("time", format_time(&departure)));
+            }
+            (None, None, None, Some(arrival)) => {
+                params.push(("time", format_time(&arrival)));
+                params.push(("arriveBy", "true".to_string()));
+            }
+            (None, None, None, None) => {}
+        }
+        params.push(("numItineraries", opts.results.to_string()));
+
+        let mut options = vec![];
+        if !opts.via.is_empty() {
+            options.push((
+                "via",
+                opts.via
+                    .iter()
+                    .map(|v| place_to_motis(&v.place))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ));
+            if opts.via.iter().any(|v| v.min_dwell_time.is_some()) {
+                options.push((
+                    "viaMinimumStay",
+                    opts.via
+                        .iter()
+                        .map(|v| {
+                            v.min_dwell_time
+                                .unwrap_or_default()
+                                .num_minutes()
+                                .to_string()
+                        })
+                        .collect::<Vec<_>>()
+                        .join(","),
+                ));
+            }
+        }
+        if let TransferOptions::Limited(transfers) = opts.transfers {
+            options.push(("maxTransfers", transfers.to_string()));
+        }
+        options.push((
+            "minTransferTime",
+            opts.transfer_time.num_minutes().to_string(),
+        ));
+        if let Some(modes) = products_to_motis(&opts.products) {
+            options.push(("transitModes", modes));
+        }
+        // MOTIS only distinguishes walking and using a wheelchair, which avoids stairs and escalators.
+        if opts.accessibility != Accessibility::None || opts.avoid_stairs || opts.avoid_escalators {
+            options.push(("pedestrianProfile", "WHEELCHAIR".to_string()));
+        }
+        if opts.bike_friendly {
+            options.push(("requireBikeTransport", "true".to_string()));
+        }
+        params.extend(options.iter().cloned());
+
+        let data = self.plan(params, opts.language).await?;
+
+        let leg_options = LegOptions {
+            stopovers: opts.stopovers,
+            polyline: opts.polylines.then_some(opts.polyline_format),
+        };
+        let journeys = data
+            .itineraries
+            .iter()
+            .map(|i| parse_journey(i, leg_options, &options))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+
+        Ok(JourneysResponse {
+            earlier_ref: data.previous_page_cursor,
+            later_ref: data.next_page_cursor,
+            journeys,
+        })
+    }
+
+    async fn refresh_journey(
+        &self,
+        journey: &Journey,
+        opts: RefreshJourneyOptions,
+    ) -> Result<RefreshJourneyResponse, rcore::Error<R::Error, Self::Error>> {
+        let journey_ref = JourneyRef::<PlanQuery>::from_id(&journey.id)
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        let query = &journey_ref.query;
+        let departure = DateTime::parse_from_rfc3339(&journey_ref.departure)
+            .map_err(|e| rcore::Error::Provider(ParseError::from(e).into()))?;
+        let options = query
+            .options
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.clone()))
+            .collect::<Vec<_>>();
+
+        let mut params = vec![
+            ("fromPlace", query.from.clone()),
+            ("toPlace", query.to.clone()),
+            ("time", format_time(&departure)),
+            ("numItineraries", REFRESH_RESULTS.to_string()),
+        ];
+        params.extend(options.iter().cloned());
+        let data = self.plan(params, opts.language).await?;
+
+        let leg_options = LegOptions {
+            stopovers: opts.stopovers,
+            polyline: opts.polylines.then_some(opts.polyline_format),
+        };
+        let journeys = data
+            .itineraries
+            .iter()
+            .map(|i| parse_journey(i, leg_options, &options))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        journey_ref
+            .find(journeys)
+            .map_err(|e| rcore::Error::Provider(e.into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use chrono::TimeZone;
+    use rcore::{IntermediateLocation, Location, Mode, PolylineFormat, ReplayRequester, Station};
+
+    use super::*;
+
+    fn client() -> MotisClient<ReplayRequester> {
+        MotisClient::transitous(ReplayRequester::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/transitous"
+        )))
+    }
+
+    fn station(id: &str) -> Place {
+        Place::Station(Station {
+            id: id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn options() -> JourneysOptions {
+        JourneysOptions {
+            departure: Some(
+                chrono_tz::Europe::Berlin
+                    .with_ymd_and_hms(2024, 10, 1, 8, 0, 0)
+                    .unwrap(),
+            ),
+            stopovers: true,
+            polylines: true,
+            polyline_format: PolylineFormat::Points,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn searches_locations() -> Result<(), Box<dyn Error>> {
+        let locations = client()
+            .locations(LocationsOptions {
+                query: "Köln Hbf".to_string(),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(locations.len(), 3);
+        match &locations[0] {
+            Place::Station(station) => {
+                assert_eq!(station.name.as_deref(), Some("Köln Hbf"));
+                assert!(station
+                    .products
+                    .iter()
+                    .any(|p| p.mode == Mode::HighSpeedTrain));
+            }
+            place => panic!("expected a station, got {:?}", place),
+        }
+        assert!(matches!(
+            &locations[1],
+            Place::Location(Location::Address { address, .. }) if address == "Hauptbahnhof 1, Köln"
+        ));
+        assert!(matches!(
+            &locations[2],
+            Place::Location(Location::Point {
+                poi: Some(true),
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn plans_journeys() -> Result<(), Box<dyn Error>> {
+        let response = client()
+            .journeys(
+                station("de-DELFI_de:05315:11201"),
+                station("de-DELFI_de:06412:10"),
+                options(),
+            )
+            .await?;
+        assert_eq!(response.journeys.len(), 2);
+        assert_eq!(response.later_ref.as_deref(), Some("LATER|1727763360"));
+        assert_eq!(response.earlier_ref.as_deref(), Some("EARLIER|1727762400"));
+
+        let leg = &response.journeys[0].legs[0];
+        let line = leg.line.as_ref().unwrap();
+        assert_eq!(line.name.as_deref(), Some("ICE 123"));
+        assert_eq!(line.mode, Mode::HighSpeedTrain);
+        assert_eq!(line.operator.as_ref().unwrap().name, "DB Fernverkehr AG");
+        assert_eq!(leg.direction.as_deref(), Some("München Hbf"));
+        assert_eq!(
+            leg.planned_departure.unwrap().to_rfc3339(),
+            "2024-10-01T08:10:00+02:00"
+        );
+        assert_eq!(
+            leg.departure.unwrap() - leg.planned_departure.unwrap(),
+            chrono::Duration::minutes(4)
+        );
+        assert_eq!(leg.planned_departure_platform.as_deref(), Some("4"));
+        assert_eq!(leg.departure_platform.as_deref(), Some("5"));
+        assert_eq!(leg.remarks.len(), 1);
+        assert!(matches!(
+            &leg.intermediate_locations[..],
+            [IntermediateLocation::Stop(s)] if s.place == station("de-DELFI_de:05382:57775")
+        ));
+        assert_eq!(leg.polyline.as_ref().unwrap().features.len(), 3);
+
+        let legs = &response.journeys[1].legs;
+        assert_eq!(legs.len(), 3);
+        assert!(legs[1].walking && legs[1].transfer);
+        assert!(legs[1].line.is_none());
+        assert!(legs[2].cancelled);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refreshes_journey() -> Result<(), Box<dyn Error>> {
+        let client = client();
+        let journeys = client
+            .journeys(
+                station("de-DELFI_de:05315:11201"),
+                station("de-DELFI_de:06412:10"),
+                options(),
+            )
+            .await?;
+        let journey = &journeys.journeys[0];
+
+        let refreshed = client
+            .refresh_journey(
+                journey,
+                RefreshJourneyOptions {
+                    stopovers: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(refreshed.id, journey.id);
+        let leg = &refreshed.legs[0];
+        assert_eq!(
+            leg.departure.unwrap() - leg.planned_departure.unwrap(),
+            chrono::Duration::minutes(9)
+        );
+        assert!(leg.polyline.is_none());
+
+        let missing = client
+            .refresh_journey(&journeys.journeys[1], RefreshJourneyOptions::default())
+            .await;
+        assert!(
+            matches!(
+                missing,
+                Err(rcore::Error::Provider(crate::Error::NotFound(_)))
+            ),
+            "expected the journey not to be found, got {:?}",
+            missing
+        );
+        Ok(())
+    }
+}
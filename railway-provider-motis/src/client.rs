@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::debug;
+use rcore::{Requester, RequesterBuilder};
+use serde::de::DeserializeOwned;
+
+/// The instance of MOTIS run by [Transitous](https://transitous.org/), covering most of Europe and beyond.
+pub const TRANSITOUS_URL: &str = "https://api.transitous.org/";
+
+#[derive(Clone)]
+pub struct MotisClient<R: Requester> {
+    url: url::Url,
+    requester: Arc<R>,
+}
+
+impl<R: Requester> MotisClient<R> {
+    /// A client for the MOTIS instance at the given URL, which the `api/v1` endpoints are relative to.
+    pub fn new<RB: RequesterBuilder<Requester = R>>(url: url::Url, requester: RB) -> Self {
+        MotisClient {
+            url,
+            requester: Arc::new(requester.build()),
+        }
+    }
+
+    /// A client for [`TRANSITOUS_URL`].
+    pub fn transitous<RB: RequesterBuilder<Requester = R>>(requester: RB) -> Self {
+        Self::new(
+            url::Url::parse(TRANSITOUS_URL).expect("Failed to parse Transitous URL"),
+            requester,
+        )
+    }
+
+    /// Request the given endpoint, e.g. `api/v1/plan`.
+    pub(crate) async fn request<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: Vec<(&str, String)>,
+    ) -> Result<T, rcore::Error<R::Error, crate::Error>> {
+        let mut url = self
+            .url
+            .join(endpoint)
+            .map_err(|e| rcore::Error::Provider(crate::Error::InvalidInput(e.to_string())))?;
+        url.query_pairs_mut().extend_pairs(params);
+        debug!("Request: {}", url);
+
+        let mut headers = HashMap::new();
+        headers.insert("Accept", "application/json");
+
+        let bytes = self
+            .requester
+            .get(&url, &[], headers)
+            .await
+            .map_err(rcore::Error::Request)?;
+        serde_json::from_slice(&bytes).map_err(|e| rcore::Error::Provider(e.into()))
+    }
+}
@@ -0,0 +1,11 @@
+#![doc = include_str!("../README.md")]
+
+pub mod api;
+pub mod client;
+pub mod error;
+pub mod parse;
+pub mod products;
+
+pub use client::{MotisClient, TRANSITOUS_URL};
+pub use error::{Error, ParseError, ParseResult, Result};
+use rcore::*;
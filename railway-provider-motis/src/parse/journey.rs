@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parse::leg::{parse_leg, LegOptions, MotisLeg};
+use crate::parse::location::motis_place_to_motis;
+use crate::{Journey, JourneyRef, ParseResult};
+
+#[derive(Debug, Deserialize)]
+pub struct MotisItinerary {
+    pub legs: Vec<MotisLeg>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotisPlanResponse {
+    #[serde(default)]
+    pub itineraries: Vec<MotisItinerary>,
+    pub previous_page_cursor: Option<String>,
+    pub next_page_cursor: Option<String>,
+}
+
+/// The query stored in the [`JourneyRef`] of a journey.
+///
+/// MOTIS has no way to look up an itinerary, so it is planned again with the same options at the scheduled departure.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub(crate) struct PlanQuery {
+    /// The `fromPlace` of the plan request.
+    pub from: String,
+    pub to: String,
+    /// The parameters of the plan request besides the places, time and page, e.g. the via stops and transit modes.
+    pub options: Vec<(String, String)>,
+}
+
+pub(crate) fn parse_journey(
+    data: &MotisItinerary,
+    options: LegOptions,
+    query_options: &[(&str, String)],
+) -> ParseResult<Journey> {
+    let legs = data
+        .legs
+        .iter()
+        .map(|l| parse_leg(l, options))
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    let (first, last) = data
+        .legs
+        .first()
+        .zip(data.legs.last())
+        .ok_or("journey without legs")?;
+    let query = PlanQuery {
+        from: motis_place_to_motis(&first.from),
+        to: motis_place_to_motis(&last.to),
+        options: query_options
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect(),
+    };
+
+    let mut journey = Journey {
+        id: JourneyRef::new(query, first.scheduled_start_time.clone(), &legs)
+            .to_id()
+            .map_err(|e| format!("failed to serialize journey id: {}", e))?,
+        legs,
+        price: None,
+    };
+    journey.mark_transfers();
+    Ok(journey)
+}
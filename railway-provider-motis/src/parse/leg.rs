@@ -0,0 +1,185 @@
+use geojson::{Feature, FeatureCollection, Geometry, Value};
+use rcore::PolylineFormat;
+use serde::Deserialize;
+
+use crate::parse::location::{parse_place, MotisPlace};
+use crate::parse::parse_time;
+use crate::products::parse_product;
+use crate::{
+    IntermediateLocation, Leg, Line, Operator, ParseResult, Remark, RemarkAssociation, RemarkType,
+    Stop,
+};
+
+/// The precision of the polylines of the `api/v1` endpoints, if not given.
+const DEFAULT_PRECISION: u32 = 7;
+
+/// The modes of legs without a vehicle of transit.
+const INDIVIDUAL_MODES: &[&str] = &["WALK", "BIKE", "RENTAL", "CAR", "CAR_PARKING", "FLEX"];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotisLeg {
+    pub mode: String,
+    pub from: MotisPlace,
+    pub to: MotisPlace,
+    pub start_time: String,
+    pub end_time: String,
+    pub scheduled_start_time: String,
+    pub scheduled_end_time: String,
+    pub distance: Option<f64>,
+    pub headsign: Option<String>,
+    pub route_short_name: Option<String>,
+    pub trip_short_name: Option<String>,
+    pub agency_id: Option<String>,
+    pub agency_name: Option<String>,
+    pub trip_id: Option<String>,
+    /// The stops between `from` and `to`.
+    #[serde(default)]
+    pub intermediate_stops: Vec<MotisPlace>,
+    pub leg_geometry: Option<MotisPolyline>,
+    #[serde(default)]
+    pub cancelled: bool,
+    #[serde(default)]
+    pub alerts: Vec<MotisAlert>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MotisPolyline {
+    pub points: String,
+    pub precision: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotisAlert {
+    pub header_text: String,
+    pub description_text: String,
+    pub effect: Option<String>,
+}
+
+/// How the leg is parsed, following the options of the request.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LegOptions {
+    pub stopovers: bool,
+    pub polyline: Option<PolylineFormat>,
+}
+
+fn parse_remarks(alerts: &[MotisAlert], trip_id: Option<&String>) -> Vec<Remark> {
+    alerts
+        .iter()
+        .map(|alert| Remark {
+            code: alert.effect.clone().unwrap_or_default(),
+            text: alert.description_text.clone(),
+            r#type: RemarkType::Status,
+            association: RemarkAssociation::None,
+            summary: Some(alert.header_text.clone()),
+            trip_id: trip_id.cloned(),
+        })
+        .collect()
+}
+
+fn parse_stop(stop: &MotisPlace) -> ParseResult<Stop> {
+    let timezone = stop.timezone();
+    let planned_departure = parse_time(stop.scheduled_departure.as_deref(), timezone)?;
+    let planned_arrival = parse_time(stop.scheduled_arrival.as_deref(), timezone)?;
+    Ok(Stop {
+        place: parse_place(stop),
+        departure: parse_time(stop.departure.as_deref(), timezone)?.or(planned_departure),
+        planned_departure,
+        arrival: parse_time(stop.arrival.as_deref(), timezone)?.or(planned_arrival),
+        planned_arrival,
+        arrival_platform: stop.track.clone().or(stop.scheduled_track.clone()),
+        planned_arrival_platform: stop.scheduled_track.clone(),
+        departure_platform: stop.track.clone().or(stop.scheduled_track.clone()),
+        planned_departure_platform: stop.scheduled_track.clone(),
+        cancelled: stop.cancelled,
+        remarks: parse_remarks(&stop.alerts, None),
+        accessibility: None,
+    })
+}
+
+fn parse_polyline(data: &MotisPolyline, format: PolylineFormat) -> ParseResult<FeatureCollection> {
+    let coords =
+        polyline::decode_polyline(&data.points, data.precision.unwrap_or(DEFAULT_PRECISION))?;
+    let polyline = FeatureCollection {
+        features: coords
+            .into_points()
+            .into_iter()
+            .map(|point| Feature::from(Geometry::new(Value::Point(vec![point.x(), point.y()]))))
+            .collect(),
+        bbox: None,
+        foreign_members: None,
+    };
+    Ok(match format {
+        PolylineFormat::Points => polyline,
+        PolylineFormat::LineString => rcore::polyline_to_line_string(&polyline),
+    })
+}
+
+fn is_individual(leg: &MotisLeg) -> bool {
+    INDIVIDUAL_MODES.contains(&&leg.mode[..])
+}
+
+pub(crate) fn parse_leg(leg: &MotisLeg, options: LegOptions) -> ParseResult<Leg> {
+    let (departure_timezone, arrival_timezone) = (leg.from.timezone(), leg.to.timezone());
+    let individual = is_individual(leg);
+
+    let trip_id = leg.trip_id.clone().filter(|_| !individual);
+    let line = (!individual).then(|| {
+        let product = parse_product(&leg.mode);
+        Line {
+            name: leg
+                .route_short_name
+                .clone()
+                .or_else(|| leg.trip_short_name.clone()),
+            fahrt_nr: leg.trip_short_name.clone(),
+            mode: product.mode.clone(),
+            operator: leg
+                .agency_id
+                .clone()
+                .zip(leg.agency_name.clone())
+                .map(|(id, name)| Operator { id, name }),
+            product_name: Some(product.name.to_string()),
+            product,
+        }
+    });
+
+    let intermediate_locations = if options.stopovers {
+        leg.intermediate_stops
+            .iter()
+            .map(|s| parse_stop(s).map(IntermediateLocation::Stop))
+            .collect::<ParseResult<_>>()?
+    } else {
+        vec![]
+    };
+
+    Ok(Leg {
+        origin: parse_place(&leg.from),
+        destination: parse_place(&leg.to),
+        departure: parse_time(Some(&leg.start_time), departure_timezone)?,
+        planned_departure: parse_time(Some(&leg.scheduled_start_time), departure_timezone)?,
+        arrival: parse_time(Some(&leg.end_time), arrival_timezone)?,
+        planned_arrival: parse_time(Some(&leg.scheduled_end_time), arrival_timezone)?,
+        reachable: true,
+        remarks: parse_remarks(&leg.alerts, trip_id.as_ref()),
+        trip_id,
+        line,
+        direction: leg.headsign.clone().filter(|_| !individual),
+        arrival_platform: leg.to.track.clone().or(leg.to.scheduled_track.clone()),
+        planned_arrival_platform: leg.to.scheduled_track.clone(),
+        departure_platform: leg.from.track.clone().or(leg.from.scheduled_track.clone()),
+        planned_departure_platform: leg.from.scheduled_track.clone(),
+        frequency: None,
+        cancelled: leg.cancelled,
+        intermediate_locations,
+        load_factor: None,
+        polyline: match (options.polyline, &leg.leg_geometry) {
+            (Some(format), Some(geometry)) => Some(parse_polyline(geometry, format)?),
+            _ => None,
+        },
+        walking: leg.mode == "WALK",
+        // Set for walks between vehicles when parsing the journey.
+        transfer: false,
+        distance: leg.distance.map(|d| d.round() as u64),
+    })
+}
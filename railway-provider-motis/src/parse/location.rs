@@ -0,0 +1,156 @@
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::parse::leg::MotisAlert;
+use crate::products::parse_product;
+use crate::{Location, Place, Product, Station};
+
+/// A result of `geocode`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotisMatch {
+    /// `STOP`, `ADDRESS` or `PLACE`.
+    pub r#type: String,
+    pub id: String,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// The modes of transit of stops.
+    #[serde(default)]
+    pub modes: Vec<String>,
+    #[serde(default)]
+    pub areas: Vec<MotisArea>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MotisArea {
+    pub name: String,
+    /// Whether this is the area, usually the town, to show with the name.
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// A place of a leg, e.g. a stop or the coordinates the journey starts at.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotisPlace {
+    pub name: String,
+    pub stop_id: Option<String>,
+    pub lat: f64,
+    pub lon: f64,
+    /// The IANA timezone of the place.
+    pub tz: Option<String>,
+    pub arrival: Option<String>,
+    pub departure: Option<String>,
+    pub scheduled_arrival: Option<String>,
+    pub scheduled_departure: Option<String>,
+    pub track: Option<String>,
+    pub scheduled_track: Option<String>,
+    #[serde(default)]
+    pub cancelled: bool,
+    #[serde(default)]
+    pub alerts: Vec<MotisAlert>,
+}
+
+impl MotisPlace {
+    /// The timezone of the place, UTC if unknown.
+    pub(crate) fn timezone(&self) -> Tz {
+        self.tz
+            .as_deref()
+            .and_then(|tz| tz.parse().ok())
+            .unwrap_or(Tz::UTC)
+    }
+}
+
+pub(crate) fn parse_match(data: MotisMatch) -> Place {
+    match &data.r#type[..] {
+        "STOP" => {
+            let mut products: Vec<Product> = vec![];
+            for product in data.modes.iter().map(|m| parse_product(m)) {
+                if !products.contains(&product) {
+                    products.push(product);
+                }
+            }
+            Place::Station(Station {
+                id: data.id.clone(),
+                name: Some(data.name),
+                location: Some(Location::Point {
+                    id: Some(data.id),
+                    name: None,
+                    poi: None,
+                    latitude: data.lat as f32,
+                    longitude: data.lon as f32,
+                }),
+                products,
+                accessibility: None,
+            })
+        }
+        "ADDRESS" => Place::Location(Location::Address {
+            address: match data.areas.iter().find(|a| a.default) {
+                Some(area) => format!("{}, {}", data.name, area.name),
+                None => data.name,
+            },
+            latitude: data.lat as f32,
+            longitude: data.lon as f32,
+        }),
+        _ => Place::Location(Location::Point {
+            id: Some(data.id),
+            name: Some(data.name),
+            poi: Some(true),
+            latitude: data.lat as f32,
+            longitude: data.lon as f32,
+        }),
+    }
+}
+
+pub(crate) fn parse_place(data: &MotisPlace) -> Place {
+    match &data.stop_id {
+        Some(id) => Place::Station(Station {
+            id: id.clone(),
+            name: Some(data.name.clone()),
+            location: Some(Location::Point {
+                id: Some(id.clone()),
+                name: None,
+                poi: None,
+                latitude: data.lat as f32,
+                longitude: data.lon as f32,
+            }),
+            products: vec![],
+            accessibility: None,
+        }),
+        None => Place::Location(Location::Point {
+            id: None,
+            name: Some(data.name.clone()),
+            poi: None,
+            latitude: data.lat as f32,
+            longitude: data.lon as f32,
+        }),
+    }
+}
+
+/// How MOTIS identifies the place in `fromPlace`, `toPlace` and `via`: the id of a stop, otherwise the coordinates.
+pub(crate) fn place_to_motis(place: &Place) -> String {
+    match place {
+        Place::Station(station) => station.id.clone(),
+        Place::Location(
+            Location::Point {
+                latitude,
+                longitude,
+                ..
+            }
+            | Location::Address {
+                latitude,
+                longitude,
+                ..
+            },
+        ) => format!("{},{}", latitude, longitude),
+    }
+}
+
+/// As [`place_to_motis`] for the parsed place.
+pub(crate) fn motis_place_to_motis(place: &MotisPlace) -> String {
+    match &place.stop_id {
+        Some(id) => id.clone(),
+        None => format!("{},{}", place.lat, place.lon),
+    }
+}
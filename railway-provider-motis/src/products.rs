@@ -0,0 +1,124 @@
+//! The products of the modes of MOTIS, see `Mode` in its [API](https://redocly.github.io/redoc/?url=https://raw.githubusercontent.com/motis-project/motis/refs/heads/master/openapi.yaml).
+
+use std::borrow::Cow;
+
+use rcore::ProductsSelection;
+
+use crate::{Mode, Product};
+
+pub const HIGHSPEED_RAIL: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("High-speed train"),
+    short: Cow::Borrowed("HSR"),
+};
+pub const LONG_DISTANCE: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("Long-distance train"),
+    short: Cow::Borrowed("IC"),
+};
+pub const NIGHT_RAIL: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("Night train"),
+    short: Cow::Borrowed("NJ"),
+};
+pub const REGIONAL_RAIL: Product = Product {
+    mode: Mode::RegionalTrain,
+    name: Cow::Borrowed("Regional train"),
+    short: Cow::Borrowed("RB"),
+};
+pub const REGIONAL_FAST_RAIL: Product = Product {
+    mode: Mode::RegionalTrain,
+    name: Cow::Borrowed("Regional express"),
+    short: Cow::Borrowed("RE"),
+};
+pub const SUBURBAN: Product = Product {
+    mode: Mode::SuburbanTrain,
+    name: Cow::Borrowed("Suburban train"),
+    short: Cow::Borrowed("S"),
+};
+pub const SUBWAY: Product = Product {
+    mode: Mode::Subway,
+    name: Cow::Borrowed("Subway"),
+    short: Cow::Borrowed("U"),
+};
+pub const TRAM: Product = Product {
+    mode: Mode::Tram,
+    name: Cow::Borrowed("Tram"),
+    short: Cow::Borrowed("Tram"),
+};
+pub const BUS: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Bus"),
+    short: Cow::Borrowed("Bus"),
+};
+pub const COACH: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Coach"),
+    short: Cow::Borrowed("Coach"),
+};
+pub const FERRY: Product = Product {
+    mode: Mode::Ferry,
+    name: Cow::Borrowed("Ferry"),
+    short: Cow::Borrowed("Ferry"),
+};
+pub const AERIAL_LIFT: Product = Product {
+    mode: Mode::Cablecar,
+    name: Cow::Borrowed("Aerial lift"),
+    short: Cow::Borrowed("Lift"),
+};
+pub const FUNICULAR: Product = Product {
+    mode: Mode::Cablecar,
+    name: Cow::Borrowed("Funicular"),
+    short: Cow::Borrowed("Funicular"),
+};
+pub const ODM: Product = Product {
+    mode: Mode::OnDemand,
+    name: Cow::Borrowed("On-demand"),
+    short: Cow::Borrowed("On-demand"),
+};
+
+/// The modes of transit of MOTIS and their products.
+const PRODUCTS: &[(&str, &Product)] = &[
+    ("HIGHSPEED_RAIL", &HIGHSPEED_RAIL),
+    ("LONG_DISTANCE", &LONG_DISTANCE),
+    ("NIGHT_RAIL", &NIGHT_RAIL),
+    ("REGIONAL_FAST_RAIL", &REGIONAL_FAST_RAIL),
+    ("REGIONAL_RAIL", &REGIONAL_RAIL),
+    ("SUBURBAN", &SUBURBAN),
+    ("SUBWAY", &SUBWAY),
+    ("METRO", &SUBWAY),
+    ("TRAM", &TRAM),
+    ("CABLE_CAR", &TRAM),
+    ("BUS", &BUS),
+    ("COACH", &COACH),
+    ("FERRY", &FERRY),
+    ("AERIAL_LIFT", &AERIAL_LIFT),
+    ("FUNICULAR", &FUNICULAR),
+    ("ODM", &ODM),
+    ("RAIL", &REGIONAL_RAIL),
+    ("AIRPLANE", &Product::unknown()),
+    ("OTHER", &Product::unknown()),
+];
+
+pub fn parse_product(mode: &str) -> Product {
+    PRODUCTS
+        .iter()
+        .find(|(m, _)| *m == mode)
+        .map(|(_, p)| (*p).clone())
+        .unwrap_or(Product::unknown())
+}
+
+/// The `transitModes` of a plan request, or `None` if all products are selected.
+pub fn products_to_motis(selection: &ProductsSelection) -> Option<String> {
+    if PRODUCTS.iter().all(|(_, p)| selection.contains(&p.mode)) {
+        return None;
+    }
+    Some(
+        PRODUCTS
+            .iter()
+            .filter(|(_, p)| selection.contains(&p.mode))
+            .map(|(m, _)| *m)
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
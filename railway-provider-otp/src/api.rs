@@ -0,0 +1,363 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono_tz::Tz;
+use rcore::{
+    Accessibility, Journey, JourneyRef, JourneysOptions, JourneysResponse, LocationsOptions,
+    LocationsResponse, Place, Provider, RefreshJourneyOptions, RefreshJourneyResponse, Requester,
+    TransferOptions, WalkingSpeed, REFRESH_RESULTS,
+};
+use serde_json::{json, Map, Value};
+
+use crate::client::OtpClient;
+use crate::parse::journey::{parse_journey, OtpPlanResponse, PlanQuery};
+use crate::parse::leg::LegOptions;
+use crate::parse::location::{parse_stops_response, place_to_otp, OtpStopsResponse};
+use crate::products::products_to_otp;
+use crate::{Error, ParseError};
+
+const STOPS_QUERY: &str = include_str!("../queries/stops.graphql");
+const PLAN_QUERY: &str = include_str!("../queries/plan.graphql");
+
+/// The date and time of a plan request, in the timezone of the instance.
+fn when(time: DateTime<Tz>, timezone: Tz) -> [(&'static str, Value); 2] {
+    let time = time.with_timezone(&timezone);
+    [
+        ("date", json!(time.format("%Y-%m-%d").to_string())),
+        ("time", json!(time.format("%H:%M").to_string())),
+    ]
+}
+
+impl<R: Requester> OtpClient<R> {
+    async fn plan(
+        &self,
+        variables: Map<String, Value>,
+    ) -> Result<OtpPlanResponse, rcore::Error<R::Error, Error>> {
+        self.request(PLAN_QUERY, Value::Object(variables)).await
+    }
+}
+
+#[cfg_attr(feature = "rt-multi-thread", async_trait)]
+#[cfg_attr(not(feature = "rt-multi-thread"), async_trait(?Send))]
+impl<R: Requester> Provider<R> for OtpClient<R> {
+    type Error = crate::Error;
+
+    async fn locations(
+        &self,
+        opts: LocationsOptions,
+    ) -> Result<LocationsResponse, rcore::Error<R::Error, Self::Error>> {
+        let data: OtpStopsResponse = self
+            .request(STOPS_QUERY, json!({ "name": opts.query }))
+            .await?;
+
+        let mut locations = parse_stops_response(data);
+        locations.truncate(opts.results as usize);
+        Ok(locations)
+    }
+
+    async fn journeys(
+        &self,
+        from: Place,
+        to: Place,
+        opts: JourneysOptions,
+    ) -> Result<JourneysResponse, rcore::Error<R::Error, Self::Error>> {
+        if !opts.via.is_empty() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "via places".to_string(),
+            )))?
+        }
+        if !opts.avoid.is_empty() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "avoiding places".to_string(),
+            )))?
+        }
+        if opts.max_walking_distance.is_some() {
+            Err(rcore::Error::Provider(Self::Error::Unsupported(
+                "maximum walking distance".to_string(),
+            )))?
+        }
+
+        let mut variables = Map::new();
+        variables.insert("fromPlace".to_string(), json!(place_to_otp(&from)));
+        variables.insert("toPlace".to_string(), json!(place_to_otp(&to)));
+        // The cursor of a page already contains the time to search at.
+        match (
+            opts.earlier_than,
+            opts.later_than,
+            opts.departure,
+            opts.arrival,
+        ) {
+            (Some(_), Some(_), _, _) => Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                "earlier_than and later_than are mutually exclusive".to_string(),
+            )))?,
+            (Some(cursor), None, _, _) | (None, Some(cursor), _, _) => {
+                variables.insert("pageCursor".to_string(), json!(cursor));
+            }
+            (None, None, Some(_), Some(_)) => {
+                Err(rcore::Error::Provider(Self::Error::InvalidInput(
+                    "departure and arrival are mutually exclusive".to_string(),
+                )))?
+            }
+            (None, None, Some(departure), None) => {
+                variables.extend(when(departure, self.timezone()).map(|(k, v)| (k.to_string(), v)));
+            }
+            (None, None, None, Some(arrival)) => {
+                variables.extend(when(arrival, self.timezone()).map(|(k, v)| (k.to_string(), v)));
+                variables.insert("arriveBy".to_string(), json!(true));
+            }
+            (None, None, None, None) => {}
+        }
+        variables.insert("numItineraries".to_string(), json!(opts.results));
+
+        let mut options = Map::new();
+        // Taking a bike along is planned by cycling instead of walking to and between the vehicles.
+        let modes = products_to_otp(&opts.products);
+        if modes.is_some() || opts.bike_friendly {
+            let street = if opts.bike_friendly {
+                "BICYCLE"
+            } else {
+                "WALK"
+            };
+            let modes = modes.unwrap_or(vec!["TRANSIT"]);
+            options.insert(
+                "transportModes".to_string(),
+                json!(modes
+                    .into_iter()
+                    .chain([street])
+                    .map(|m| json!({ "mode": m }))
+                    .collect::<Vec<_>>()),
+            );
+        }
+        if let TransferOptions::Limited(transfers) = opts.transfers {
+            options.insert("maxTransfers".to_string(), json!(transfers));
+        }
+        options.insert(
+            "minTransferTime".to_string(),
+            json!(opts.transfer_time.num_seconds()),
+        );
+        // OpenTripPlanner only distinguishes walking and using a wheelchair, which avoids stairs and escalators.
+        if opts.accessibility != Accessibility::None || opts.avoid_stairs || opts.avoid_escalators {
+            options.insert("wheelchair".to_string(), json!(true));
+        }
+        options.insert(
+            "walkSpeed".to_string(),
+            json!(match opts.walking_speed {
+                WalkingSpeed::Slow => 1.0,
+                WalkingSpeed::Normal => 1.33,
+                WalkingSpeed::Fast => 1.67,
+            }),
+        );
+        variables.extend(options.clone());
+        if let Some(language) = opts.language {
+            variables.insert("locale".to_string(), json!(language));
+        }
+
+        let data = self.plan(variables).await?;
+
+        let leg_options = LegOptions {
+            stopovers: opts.stopovers,
+            polyline: opts.polylines.then_some(opts.polyline_format),
+        };
+        let journeys = data
+            .plan
+            .itineraries
+            .iter()
+            .map(|i| parse_journey(i, leg_options, self.timezone(), &options))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+
+        Ok(JourneysResponse {
+            earlier_ref: data.plan.previous_page_cursor,
+            later_ref: data.plan.next_page_cursor,
+            journeys,
+        })
+    }
+
+    async fn refresh_journey(
+        &self,
+        journey: &Journey,
+        opts: RefreshJourneyOptions,
+    ) -> Result<RefreshJourneyResponse, rcore::Error<R::Error, Self::Error>> {
+        let journey_ref = JourneyRef::<PlanQuery>::from_id(&journey.id)
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        let query = &journey_ref.query;
+        let departure = DateTime::parse_from_rfc3339(&journey_ref.departure)
+            .map_err(|e| rcore::Error::Provider(ParseError::from(e).into()))?
+            .with_timezone(&self.timezone());
+
+        let mut variables = Map::new();
+        variables.insert("fromPlace".to_string(), json!(query.from));
+        variables.insert("toPlace".to_string(), json!(query.to));
+        variables.extend(when(departure, self.timezone()).map(|(k, v)| (k.to_string(), v)));
+        variables.insert("numItineraries".to_string(), json!(REFRESH_RESULTS));
+        variables.extend(query.options.clone());
+        if let Some(language) = opts.language {
+            variables.insert("locale".to_string(), json!(language));
+        }
+        let data = self.plan(variables).await?;
+
+        let leg_options = LegOptions {
+            stopovers: opts.stopovers,
+            polyline: opts.polylines.then_some(opts.polyline_format),
+        };
+        let journeys = data
+            .plan
+            .itineraries
+            .iter()
+            .map(|i| parse_journey(i, leg_options, self.timezone(), &query.options))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| rcore::Error::Provider(e.into()))?;
+        journey_ref
+            .find(journeys)
+            .map_err(|e| rcore::Error::Provider(e.into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use chrono::TimeZone;
+    use rcore::{IntermediateLocation, Location, Mode, PolylineFormat, ReplayRequester, Station};
+
+    use super::*;
+
+    fn client() -> OtpClient<ReplayRequester> {
+        OtpClient::new(
+            url::Url::parse("https://otp.example.org/otp/gtfs/v1").unwrap(),
+            chrono_tz::Europe::Berlin,
+            ReplayRequester::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/otp")),
+        )
+    }
+
+    fn station(id: &str) -> Place {
+        Place::Station(Station {
+            id: id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn query() -> (Place, Place, JourneysOptions) {
+        (
+            Place::Location(Location::Address {
+                address: "Königstraße 1".to_string(),
+                latitude: 48.78,
+                longitude: 9.18,
+            }),
+            station("1:de:08111:2085"),
+            JourneysOptions {
+                departure: Some(
+                    chrono_tz::Europe::Berlin
+                        .with_ymd_and_hms(2024, 10, 1, 8, 0, 0)
+                        .unwrap(),
+                ),
+                stopovers: true,
+                polylines: true,
+                polyline_format: PolylineFormat::Points,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn searches_locations() -> Result<(), Box<dyn Error>> {
+        let locations = client()
+            .locations(LocationsOptions {
+                query: "Hauptbahnhof".to_string(),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(
+            locations.len(),
+            2,
+            "platforms are merged into their station"
+        );
+        match &locations[0] {
+            Place::Station(station) => {
+                assert_eq!(station.id, "1:de:08111:6118");
+                assert_eq!(
+                    station.products.iter().map(|p| &p.mode).collect::<Vec<_>>(),
+                    [&Mode::RegionalTrain, &Mode::Bus]
+                );
+            }
+            place => panic!("expected a station, got {:?}", place),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn plans_journeys() -> Result<(), Box<dyn Error>> {
+        let (from, to, opts) = query();
+        let response = client().journeys(from, to, opts).await?;
+        assert_eq!(response.journeys.len(), 2);
+        assert_eq!(
+            response.later_ref.as_deref(),
+            Some("MXxQQUdFX0NVUlNPUnwyMDI0LTEwLTAxVDA4OjMx")
+        );
+        assert!(response.earlier_ref.is_some());
+
+        let legs = &response.journeys[0].legs;
+        assert_eq!(legs.len(), 4);
+        assert!(legs[0].walking && !legs[0].transfer);
+        assert!(matches!(legs[0].origin, Place::Location(_)));
+
+        let ride = &legs[1];
+        let line = ride.line.as_ref().unwrap();
+        assert_eq!(line.name.as_deref(), Some("S1"));
+        assert_eq!(line.mode, Mode::SuburbanTrain);
+        assert_eq!(ride.direction.as_deref(), Some("Kirchheim (Teck)"));
+        assert_eq!(ride.origin, station("1:de:08111:6118"));
+        assert_eq!(ride.departure_platform.as_deref(), Some("101"));
+        assert_eq!(
+            ride.departure.unwrap() - ride.planned_departure.unwrap(),
+            chrono::Duration::minutes(2)
+        );
+        assert_eq!(ride.remarks.len(), 1);
+        assert!(matches!(
+            &ride.intermediate_locations[..],
+            [IntermediateLocation::Stop(s)] if s.place == station("1:de:08111:6056")
+        ));
+        assert_eq!(ride.polyline.as_ref().unwrap().features.len(), 3);
+
+        assert!(legs[2].walking && legs[2].transfer);
+        assert!(legs[3].cancelled);
+
+        let legs = &response.journeys[1].legs;
+        assert!(
+            !legs[0].walking && legs[0].line.is_none(),
+            "cycling is no walk"
+        );
+        assert_eq!(legs[1].line.as_ref().unwrap().mode, Mode::HighSpeedTrain);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refreshes_journey() -> Result<(), Box<dyn Error>> {
+        let client = client();
+        let (from, to, opts) = query();
+        let journeys = client.journeys(from, to, opts).await?;
+        let journey = &journeys.journeys[0];
+
+        let refreshed = client
+            .refresh_journey(journey, RefreshJourneyOptions::default())
+            .await?;
+        assert_eq!(refreshed.id, journey.id);
+        let ride = &refreshed.legs[1];
+        assert_eq!(
+            ride.departure.unwrap() - ride.planned_departure.unwrap(),
+            chrono::Duration::minutes(6)
+        );
+
+        let missing = client
+            .refresh_journey(&journeys.journeys[1], RefreshJourneyOptions::default())
+            .await;
+        assert!(
+            matches!(
+                missing,
+                Err(rcore::Error::Provider(crate::Error::NotFound(_)))
+            ),
+            "expected the journey not to be found, got {:?}",
+            missing
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono_tz::Tz;
+use log::debug;
+use rcore::{Requester, RequesterBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::Error;
+
+#[derive(Clone)]
+pub struct OtpClient<R: Requester> {
+    url: url::Url,
+    timezone: Tz,
+    requester: Arc<R>,
+}
+
+impl<R: Requester> OtpClient<R> {
+    /// A client for the GraphQL API of OpenTripPlanner 2 at the given URL, e.g. `https://example.org/otp/gtfs/v1`.
+    ///
+    /// The timezone is the one of the instance, which dates and times of requests are in.
+    pub fn new<RB: RequesterBuilder<Requester = R>>(
+        url: url::Url,
+        timezone: Tz,
+        requester: RB,
+    ) -> Self {
+        OtpClient {
+            url,
+            timezone,
+            requester: Arc::new(requester.build()),
+        }
+    }
+
+    /// The timezone all times are returned in.
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+}
+
+#[derive(Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+impl<R: Requester> OtpClient<R> {
+    pub(crate) async fn request<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: Value,
+    ) -> Result<T, rcore::Error<R::Error, crate::Error>> {
+        let body = json!({
+            "query": query,
+            "variables": variables,
+        });
+        debug!("Request variables: {}", body["variables"]);
+        let body = serde_json::to_vec(&body).map_err(|e| rcore::Error::Provider(e.into()))?;
+
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type", "application/json");
+        headers.insert("Accept", "application/json");
+
+        let bytes = self
+            .requester
+            .post(&self.url, &body, headers)
+            .await
+            .map_err(rcore::Error::Request)?;
+
+        let response: GraphQlResponse<T> =
+            serde_json::from_slice(&bytes).map_err(|e| rcore::Error::Provider(e.into()))?;
+        match response.data {
+            Some(data) if response.errors.is_empty() => Ok(data),
+            _ if response.errors.is_empty() => Err(rcore::Error::Provider(Error::Otp(
+                "response without data".to_string(),
+            ))),
+            _ => Err(rcore::Error::Provider(Error::Otp(
+                response
+                    .errors
+                    .into_iter()
+                    .map(|e| e.message)
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ))),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+pub use rcore::{ParseError, ParseResult};
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error("{source}")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+    #[error("{source}")]
+    Parse {
+        #[from]
+        source: ParseError,
+    },
+    /// The errors of the GraphQL response.
+    #[error("{0}")]
+    Otp(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    NotFound(#[from] rcore::JourneyNotFound),
+    #[error("not supported by OpenTripPlanner: {0}")]
+    Unsupported(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,71 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::parse::leg::{parse_leg, LegOptions, OtpLeg};
+use crate::parse::location::otp_place_to_otp;
+use crate::{Journey, JourneyRef, ParseResult};
+
+#[derive(Debug, Deserialize)]
+pub struct OtpItinerary {
+    pub legs: Vec<OtpLeg>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpPlan {
+    pub itineraries: Vec<OtpItinerary>,
+    pub previous_page_cursor: Option<String>,
+    pub next_page_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OtpPlanResponse {
+    pub plan: OtpPlan,
+}
+
+/// The GraphQL variables stored in the [`JourneyRef`] of a journey.
+///
+/// The `plan` query has no itinerary ids to look up, so the search is repeated from the scheduled departure.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub(crate) struct PlanQuery {
+    /// The `fromPlace` variable, a stop id or `latitude,longitude`.
+    pub from: String,
+    pub to: String,
+    /// The remaining variables except for the time and page cursor, e.g. the transport modes.
+    pub options: Map<String, Value>,
+}
+
+pub(crate) fn parse_journey(
+    data: &OtpItinerary,
+    options: LegOptions,
+    timezone: Tz,
+    query_options: &Map<String, Value>,
+) -> ParseResult<Journey> {
+    let legs = data
+        .legs
+        .iter()
+        .map(|l| parse_leg(l, options, timezone))
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    let (first, last) = data
+        .legs
+        .first()
+        .zip(data.legs.last())
+        .ok_or("journey without legs")?;
+    let query = PlanQuery {
+        from: otp_place_to_otp(&first.from),
+        to: otp_place_to_otp(&last.to),
+        options: query_options.clone(),
+    };
+
+    let mut journey = Journey {
+        id: JourneyRef::new(query, first.start.scheduled_time.clone(), &legs)
+            .to_id()
+            .map_err(|e| format!("failed to serialize journey id: {}", e))?,
+        legs,
+        price: None,
+    };
+    journey.mark_transfers();
+    Ok(journey)
+}
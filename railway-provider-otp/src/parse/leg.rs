@@ -0,0 +1,206 @@
+use chrono_tz::Tz;
+use geojson::{Feature, FeatureCollection, Geometry, Value};
+use rcore::PolylineFormat;
+use serde::Deserialize;
+
+use crate::parse::location::{parse_place, OtpPlace};
+use crate::parse::{parse_leg_time, OtpLegTime};
+use crate::products::parse_product;
+use crate::{
+    IntermediateLocation, Leg, Line, Operator, ParseResult, Remark, RemarkAssociation, RemarkType,
+    Stop,
+};
+
+/// The precision of the encoded polylines of OpenTripPlanner.
+const PRECISION: u32 = 5;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpLeg {
+    /// E.g. `WALK`, `BICYCLE`, `RAIL` or `BUS`.
+    pub mode: String,
+    pub transit_leg: bool,
+    /// E.g. `SCHEDULED`, `UPDATED` or `CANCELED`.
+    pub realtime_state: Option<String>,
+    pub distance: Option<f64>,
+    pub headsign: Option<String>,
+    pub start: OtpLegTime,
+    pub end: OtpLegTime,
+    pub from: OtpPlace,
+    pub to: OtpPlace,
+    /// The stops between `from` and `to`.
+    pub intermediate_places: Option<Vec<OtpPlace>>,
+    pub route: Option<OtpRoute>,
+    pub trip: Option<OtpTrip>,
+    pub leg_geometry: Option<OtpGeometry>,
+    pub alerts: Option<Vec<OtpAlert>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpRoute {
+    pub gtfs_id: String,
+    pub short_name: Option<String>,
+    pub long_name: Option<String>,
+    /// The route type of GTFS.
+    pub r#type: Option<i64>,
+    pub agency: Option<OtpAgency>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpAgency {
+    pub gtfs_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpTrip {
+    pub gtfs_id: String,
+    pub trip_short_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OtpGeometry {
+    pub points: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpAlert {
+    pub id: String,
+    pub alert_header_text: Option<String>,
+    pub alert_description_text: String,
+    pub alert_effect: Option<String>,
+}
+
+/// How the leg is parsed, following the options of the request.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LegOptions {
+    pub stopovers: bool,
+    pub polyline: Option<PolylineFormat>,
+}
+
+fn parse_remarks(alerts: &[OtpAlert], trip_id: Option<&String>) -> Vec<Remark> {
+    alerts
+        .iter()
+        .map(|alert| Remark {
+            code: alert
+                .alert_effect
+                .clone()
+                .unwrap_or_else(|| alert.id.clone()),
+            text: alert.alert_description_text.clone(),
+            r#type: RemarkType::Status,
+            association: RemarkAssociation::None,
+            summary: alert.alert_header_text.clone(),
+            trip_id: trip_id.cloned(),
+        })
+        .collect()
+}
+
+fn platform(place: &OtpPlace) -> Option<String> {
+    place.stop.as_ref().and_then(|s| s.platform_code.clone())
+}
+
+fn parse_stop(place: &OtpPlace, timezone: Tz) -> ParseResult<Stop> {
+    let (planned_arrival, arrival) = parse_leg_time(place.arrival.as_ref(), timezone)?;
+    let (planned_departure, departure) = parse_leg_time(place.departure.as_ref(), timezone)?;
+    Ok(Stop {
+        place: parse_place(place),
+        departure,
+        planned_departure,
+        arrival,
+        planned_arrival,
+        // The platform of the scheduled stop is unknown if the trip serves another one.
+        arrival_platform: platform(place),
+        planned_arrival_platform: platform(place),
+        departure_platform: platform(place),
+        planned_departure_platform: platform(place),
+        cancelled: false,
+        remarks: vec![],
+        accessibility: None,
+    })
+}
+
+fn parse_polyline(data: &OtpGeometry, format: PolylineFormat) -> ParseResult<FeatureCollection> {
+    let coords = polyline::decode_polyline(&data.points, PRECISION)?;
+    let polyline = FeatureCollection {
+        features: coords
+            .into_points()
+            .into_iter()
+            .map(|point| Feature::from(Geometry::new(Value::Point(vec![point.x(), point.y()]))))
+            .collect(),
+        bbox: None,
+        foreign_members: None,
+    };
+    Ok(match format {
+        PolylineFormat::Points => polyline,
+        PolylineFormat::LineString => rcore::polyline_to_line_string(&polyline),
+    })
+}
+
+pub(crate) fn parse_leg(leg: &OtpLeg, options: LegOptions, timezone: Tz) -> ParseResult<Leg> {
+    let (planned_departure, departure) = parse_leg_time(Some(&leg.start), timezone)?;
+    let (planned_arrival, arrival) = parse_leg_time(Some(&leg.end), timezone)?;
+
+    let trip_id = leg
+        .trip
+        .as_ref()
+        .map(|t| t.gtfs_id.clone())
+        .filter(|_| leg.transit_leg);
+    let line = leg.route.as_ref().filter(|_| leg.transit_leg).map(|route| {
+        let product = parse_product(&leg.mode, route.r#type);
+        Line {
+            name: route.short_name.clone().or_else(|| route.long_name.clone()),
+            fahrt_nr: leg.trip.as_ref().and_then(|t| t.trip_short_name.clone()),
+            mode: product.mode.clone(),
+            operator: route.agency.as_ref().map(|a| Operator {
+                id: a.gtfs_id.clone(),
+                name: a.name.clone(),
+            }),
+            product_name: Some(product.name.to_string()),
+            product,
+        }
+    });
+
+    let intermediate_locations = if options.stopovers {
+        leg.intermediate_places
+            .iter()
+            .flatten()
+            .map(|p| parse_stop(p, timezone).map(IntermediateLocation::Stop))
+            .collect::<ParseResult<_>>()?
+    } else {
+        vec![]
+    };
+
+    Ok(Leg {
+        origin: parse_place(&leg.from),
+        destination: parse_place(&leg.to),
+        departure,
+        planned_departure,
+        arrival,
+        planned_arrival,
+        reachable: true,
+        remarks: parse_remarks(leg.alerts.as_deref().unwrap_or_default(), trip_id.as_ref()),
+        trip_id,
+        line,
+        direction: leg.headsign.clone().filter(|_| leg.transit_leg),
+        arrival_platform: platform(&leg.to),
+        planned_arrival_platform: platform(&leg.to),
+        departure_platform: platform(&leg.from),
+        planned_departure_platform: platform(&leg.from),
+        frequency: None,
+        cancelled: leg.realtime_state.as_deref() == Some("CANCELED"),
+        intermediate_locations,
+        load_factor: None,
+        polyline: match (options.polyline, &leg.leg_geometry) {
+            (Some(format), Some(geometry)) => Some(parse_polyline(geometry, format)?),
+            _ => None,
+        },
+        walking: leg.mode == "WALK",
+        // Set for walks between vehicles when parsing the journey.
+        transfer: false,
+        distance: leg.distance.map(|d| d.round() as u64),
+    })
+}
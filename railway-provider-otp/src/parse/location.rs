@@ -0,0 +1,119 @@
+use serde::Deserialize;
+
+use crate::parse::OtpLegTime;
+use crate::products::parse_product;
+use crate::{Location, Place, Product, Station};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpStop {
+    /// The id including the feed, e.g. `1:de:05315:11201`.
+    pub gtfs_id: String,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub vehicle_mode: Option<String>,
+    pub platform_code: Option<String>,
+    pub parent_station: Option<Box<OtpStop>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OtpStopsResponse {
+    pub stops: Option<Vec<OtpStop>>,
+}
+
+/// A place of a leg, e.g. a stop or the coordinates the journey starts at.
+#[derive(Debug, Deserialize)]
+pub struct OtpPlace {
+    pub name: Option<String>,
+    pub lat: f64,
+    pub lon: f64,
+    pub arrival: Option<OtpLegTime>,
+    pub departure: Option<OtpLegTime>,
+    pub stop: Option<OtpStop>,
+}
+
+impl OtpStop {
+    /// The station of the stop, or the stop itself.
+    fn station(&self) -> &OtpStop {
+        self.parent_station.as_deref().unwrap_or(self)
+    }
+}
+
+fn parse_station(stop: &OtpStop, products: Vec<Product>) -> Station {
+    let station = stop.station();
+    Station {
+        id: station.gtfs_id.clone(),
+        name: Some(station.name.clone()),
+        location: Some(Location::Point {
+            id: Some(station.gtfs_id.clone()),
+            name: None,
+            poi: None,
+            latitude: station.lat as f32,
+            longitude: station.lon as f32,
+        }),
+        products,
+        accessibility: None,
+    }
+}
+
+/// The stations of the stops, which are often platforms, in the order of the stops.
+pub(crate) fn parse_stops_response(data: OtpStopsResponse) -> Vec<Place> {
+    let mut stations: Vec<Station> = vec![];
+    for stop in data.stops.unwrap_or_default() {
+        let product = stop.vehicle_mode.as_deref().map(|m| parse_product(m, None));
+        let station = match stations.iter_mut().find(|s| s.id == stop.station().gtfs_id) {
+            Some(station) => station,
+            None => {
+                stations.push(parse_station(&stop, vec![]));
+                stations.last_mut().expect("station was just added")
+            }
+        };
+        if let Some(product) = product {
+            if !station.products.contains(&product) {
+                station.products.push(product);
+            }
+        }
+    }
+    stations.into_iter().map(Place::Station).collect()
+}
+
+pub(crate) fn parse_place(data: &OtpPlace) -> Place {
+    match &data.stop {
+        Some(stop) => Place::Station(parse_station(stop, vec![])),
+        None => Place::Location(Location::Point {
+            id: None,
+            name: data.name.clone(),
+            poi: None,
+            latitude: data.lat as f32,
+            longitude: data.lon as f32,
+        }),
+    }
+}
+
+/// How OpenTripPlanner identifies the place in `fromPlace` and `toPlace`: the id of a stop, otherwise the coordinates.
+pub(crate) fn place_to_otp(place: &Place) -> String {
+    match place {
+        Place::Station(station) => station.id.clone(),
+        Place::Location(
+            Location::Point {
+                latitude,
+                longitude,
+                ..
+            }
+            | Location::Address {
+                latitude,
+                longitude,
+                ..
+            },
+        ) => format!("{},{}", latitude, longitude),
+    }
+}
+
+/// As [`place_to_otp`] for the parsed place.
+pub(crate) fn otp_place_to_otp(place: &OtpPlace) -> String {
+    match &place.stop {
+        Some(stop) => stop.station().gtfs_id.clone(),
+        None => format!("{},{}", place.lat, place.lon),
+    }
+}
@@ -0,0 +1,46 @@
+//! Parsing the responses of the GraphQL API of OpenTripPlanner.
+
+pub mod journey;
+pub mod leg;
+pub mod location;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::ParseResult;
+
+/// A scheduled time and its real-time estimate.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtpLegTime {
+    /// With offset, e.g. `2024-10-01T08:10:00+02:00`.
+    pub scheduled_time: String,
+    pub estimated: Option<OtpEstimate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OtpEstimate {
+    pub time: String,
+}
+
+fn parse_time(time: &str, timezone: Tz) -> ParseResult<DateTime<Tz>> {
+    Ok(DateTime::parse_from_rfc3339(time)?.with_timezone(&timezone))
+}
+
+/// The planned and the current time.
+type Times = (Option<DateTime<Tz>>, Option<DateTime<Tz>>);
+
+/// The planned and the current time, falling back to the planned one.
+pub(crate) fn parse_leg_time(time: Option<&OtpLegTime>, timezone: Tz) -> ParseResult<Times> {
+    let Some(time) = time else {
+        return Ok((None, None));
+    };
+    let planned = parse_time(&time.scheduled_time, timezone)?;
+    let estimated = time
+        .estimated
+        .as_ref()
+        .map(|e| parse_time(&e.time, timezone))
+        .transpose()?;
+    Ok((Some(planned), Some(estimated.unwrap_or(planned))))
+}
@@ -0,0 +1,127 @@
+//! The products of the modes of OpenTripPlanner, see `Mode` in its GraphQL schema.
+
+use std::borrow::Cow;
+
+use rcore::ProductsSelection;
+
+use crate::{Mode, Product};
+
+pub const HIGH_SPEED_RAIL: Product = Product {
+    mode: Mode::HighSpeedTrain,
+    name: Cow::Borrowed("High-speed train"),
+    short: Cow::Borrowed("HSR"),
+};
+pub const RAIL: Product = Product {
+    mode: Mode::RegionalTrain,
+    name: Cow::Borrowed("Train"),
+    short: Cow::Borrowed("Train"),
+};
+pub const SUBURBAN_RAIL: Product = Product {
+    mode: Mode::SuburbanTrain,
+    name: Cow::Borrowed("Suburban train"),
+    short: Cow::Borrowed("S"),
+};
+pub const SUBWAY: Product = Product {
+    mode: Mode::Subway,
+    name: Cow::Borrowed("Subway"),
+    short: Cow::Borrowed("U"),
+};
+pub const TRAM: Product = Product {
+    mode: Mode::Tram,
+    name: Cow::Borrowed("Tram"),
+    short: Cow::Borrowed("Tram"),
+};
+pub const BUS: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Bus"),
+    short: Cow::Borrowed("Bus"),
+};
+pub const COACH: Product = Product {
+    mode: Mode::Bus,
+    name: Cow::Borrowed("Coach"),
+    short: Cow::Borrowed("Coach"),
+};
+pub const FERRY: Product = Product {
+    mode: Mode::Ferry,
+    name: Cow::Borrowed("Ferry"),
+    short: Cow::Borrowed("Ferry"),
+};
+pub const GONDOLA: Product = Product {
+    mode: Mode::Cablecar,
+    name: Cow::Borrowed("Gondola"),
+    short: Cow::Borrowed("Gondola"),
+};
+pub const FUNICULAR: Product = Product {
+    mode: Mode::Cablecar,
+    name: Cow::Borrowed("Funicular"),
+    short: Cow::Borrowed("Funicular"),
+};
+pub const TAXI: Product = Product {
+    mode: Mode::OnDemand,
+    name: Cow::Borrowed("Taxi"),
+    short: Cow::Borrowed("Taxi"),
+};
+
+/// The modes of transit of OpenTripPlanner and their products.
+///
+/// Trains all have the mode `RAIL`, the products of which are told apart by the route type.
+const PRODUCTS: &[(&str, &Product)] = &[
+    ("RAIL", &RAIL),
+    ("SUBWAY", &SUBWAY),
+    ("MONORAIL", &SUBWAY),
+    ("TRAM", &TRAM),
+    ("CABLE_CAR", &TRAM),
+    ("BUS", &BUS),
+    ("TROLLEYBUS", &BUS),
+    ("COACH", &COACH),
+    ("FERRY", &FERRY),
+    ("GONDOLA", &GONDOLA),
+    ("FUNICULAR", &FUNICULAR),
+    ("TAXI", &TAXI),
+    ("AIRPLANE", &Product::unknown()),
+];
+
+/// The modes of rail routes in the extended route types of GTFS.
+fn rail_product(route_type: i64) -> &'static Product {
+    match route_type {
+        101 | 102 | 105 => &HIGH_SPEED_RAIL,
+        109 => &SUBURBAN_RAIL,
+        _ => &RAIL,
+    }
+}
+
+pub fn parse_product(mode: &str, route_type: Option<i64>) -> Product {
+    match (mode, route_type) {
+        ("RAIL", Some(route_type)) => rail_product(route_type).clone(),
+        _ => PRODUCTS
+            .iter()
+            .find(|(m, _)| *m == mode)
+            .map(|(_, p)| (*p).clone())
+            .unwrap_or(Product::unknown()),
+    }
+}
+
+/// The modes of transit to use in a plan request, or `None` if all products are selected.
+///
+/// As trains cannot be told apart by the mode, `RAIL` is used if any kind of train is selected.
+pub fn products_to_otp(selection: &ProductsSelection) -> Option<Vec<&'static str>> {
+    let trains = [&HIGH_SPEED_RAIL, &RAIL, &SUBURBAN_RAIL];
+    if PRODUCTS
+        .iter()
+        .map(|(_, p)| *p)
+        .chain(trains)
+        .all(|p| selection.contains(&p.mode))
+    {
+        return None;
+    }
+    Some(
+        PRODUCTS
+            .iter()
+            .filter(|(m, p)| {
+                selection.contains(&p.mode)
+                    || (*m == "RAIL" && trains.iter().any(|t| selection.contains(&t.mode)))
+            })
+            .map(|(m, _)| *m)
+            .collect(),
+    )
+}
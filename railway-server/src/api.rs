@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono_tz::Tz;
+use hafas::client::HafasClient;
+use rcore::fptf::Fptf;
+use rcore::{Journey, Provider, Requester};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache::Cache;
+use crate::error::ApiError;
+use crate::query::{JourneysQuery, LocationsQuery, RefreshQuery};
+
+const OPENAPI: &str = include_str!("../openapi.json");
+
+pub struct AppState<R: Requester> {
+    pub client: HafasClient<R>,
+    pub timezone: Tz,
+    pub cache: Cache,
+}
+
+type SharedState<R> = State<Arc<AppState<R>>>;
+
+/// The response of `/journeys`, like the one of hafas-rest-api.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JourneysBody {
+    earlier_ref: Option<String>,
+    later_ref: Option<String>,
+    journeys: Vec<Value>,
+}
+
+/// The response of `/journeys/:ref`.
+#[derive(Serialize)]
+struct JourneyBody {
+    journey: Value,
+}
+
+/// Answer from the cache, or cache the JSON of the given result.
+async fn cached<T: Serialize>(
+    cache: &Cache,
+    uri: &Uri,
+    result: impl std::future::Future<Output = Result<T, ApiError>>,
+) -> Result<Response, ApiError> {
+    let key = uri.to_string();
+    let body = match cache.get(&key) {
+        Some(body) => body,
+        None => {
+            let body = serde_json::to_string(&result.await?).map_err(|e| ApiError {
+                status: axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                message: e.to_string(),
+                code: None,
+            })?;
+            cache.insert(key, body.clone());
+            body
+        }
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CACHE_CONTROL,
+                format!("max-age={}", cache.ttl().as_secs()),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+async fn locations<R: Requester + 'static>(
+    State(state): SharedState<R>,
+    uri: Uri,
+    query: Result<Query<LocationsQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query) = query.map_err(ApiError::bad_request)?;
+    cached(&state.cache, &uri, async {
+        let locations = state.client.locations(query.into()).await?;
+        Ok(locations.iter().map(Fptf::to_fptf).collect::<Vec<_>>())
+    })
+    .await
+}
+
+async fn journeys<R: Requester + 'static>(
+    State(state): SharedState<R>,
+    uri: Uri,
+    query: Result<Query<JourneysQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query) = query.map_err(ApiError::bad_request)?;
+    let (from, to, opts) = query.into_options(state.timezone)?;
+    cached(&state.cache, &uri, async {
+        let response = state.client.journeys(from, to, opts).await?;
+        Ok(JourneysBody {
+            earlier_ref: response.earlier_ref,
+            later_ref: response.later_ref,
+            journeys: response.journeys.iter().map(Fptf::to_fptf).collect(),
+        })
+    })
+    .await
+}
+
+async fn refresh_journey<R: Requester + 'static>(
+    State(state): SharedState<R>,
+    Path(refresh_token): Path<String>,
+    uri: Uri,
+    query: Result<Query<RefreshQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query) = query.map_err(ApiError::bad_request)?;
+    // Refreshing only requires the id of the journey.
+    let journey = Journey {
+        id: refresh_token,
+        legs: vec![],
+        price: None,
+    };
+    cached(&state.cache, &uri, async {
+        Ok(JourneyBody {
+            journey: state
+                .client
+                .refresh_journey(&journey, query.into())
+                .await?
+                .to_fptf(),
+        })
+    })
+    .await
+}
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI)
+}
+
+/// The routes of the server.
+pub fn router<R: Requester + 'static>(state: AppState<R>) -> Router
+where
+    R::Error: Send,
+{
+    Router::new()
+        .route("/openapi.json", get(openapi))
+        .route("/locations", get(locations::<R>))
+        .route("/journeys", get(journeys::<R>))
+        .route("/journeys/:ref", get(refresh_journey::<R>))
+        .with_state(Arc::new(state))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use rcore::ReplayRequester;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn app() -> Router {
+        // Shared with the HAFAS crate and the other clients of the workspace.
+        let fixtures = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../railway-provider-hafas/fixtures/db"
+        );
+        let profile = hafas::profile::by_name("db").unwrap();
+        let timezone = profile.timezone();
+        router(AppState {
+            client: HafasClient::new(profile, ReplayRequester::new(fixtures)),
+            timezone,
+            cache: Cache::new(Duration::from_secs(60)),
+        })
+    }
+
+    async fn get(uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn serves_locations() {
+        let (status, body) = get("/locations?query=K%C3%B6ln%20Hbf").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["type"], "station");
+        assert_eq!(body[0]["id"], "8000207");
+        assert_eq!(body[0]["location"]["type"], "location");
+
+        let (status, _) = get("/locations").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Not recorded, so the request fails.
+        let (status, _) = get("/locations?query=Berlin").await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn serves_journeys() {
+        let (status, body) =
+            get("/journeys?from=8000207&to=8000044&departure=2024-09-01T10:00").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["laterRef"].is_string());
+        let journey = &body["journeys"][0];
+        assert_eq!(journey["type"], "journey");
+        let leg = &journey["legs"][0];
+        assert_eq!(leg["origin"]["type"], "station");
+        assert_eq!(leg["line"]["type"], "line");
+        assert_eq!(leg["line"]["name"], "RE 5");
+        assert_eq!(leg["departurePlatform"], "6");
+        assert_eq!(leg["departureDelay"], 120);
+
+        let (status, _) = get("/journeys?from=8000207&to=8000044&departure=tomorrow").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // The refresh token is the id of the journey, percent-encoded.
+        let token: String = journey["id"]
+            .as_str()
+            .unwrap()
+            .bytes()
+            .map(|b| format!("%{:02X}", b))
+            .collect();
+        let (status, body) = get(&format!("/journeys/{}", token)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["journey"]["id"], journey["id"]);
+        assert_ne!(
+            body["journey"]["legs"][0]["arrival"],
+            journey["legs"][0]["arrival"]
+        );
+
+        let (status, _) = get("/journeys/unknown").await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn documents_routes() {
+        let (status, body) = get("/openapi.json").await;
+        assert_eq!(status, StatusCode::OK);
+        for path in ["/locations", "/journeys", "/journeys/{ref}"] {
+            assert!(
+                body["paths"][path].is_object(),
+                "{} is not documented",
+                path
+            );
+        }
+    }
+}
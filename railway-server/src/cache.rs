@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Successful response bodies by request URI, kept for a fixed time.
+pub struct Cache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+impl Cache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        let (inserted, body) = entries.get(key)?;
+        (inserted.elapsed() < self.ttl).then(|| body.clone())
+    }
+
+    /// Store a body, dropping expired ones.
+    pub fn insert(&self, key: String, body: String) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (inserted, _)| inserted.elapsed() < self.ttl);
+        if !self.ttl.is_zero() {
+            entries.insert(key, (Instant::now(), body));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expires_entries() {
+        let cache = Cache::new(Duration::from_secs(60));
+        cache.insert("/db/locations?query=a".to_string(), "[]".to_string());
+        assert_eq!(cache.get("/db/locations?query=a").as_deref(), Some("[]"));
+        assert_eq!(cache.get("/db/locations?query=b"), None);
+
+        let cache = Cache::new(Duration::ZERO);
+        cache.insert("/db/locations?query=a".to_string(), "[]".to_string());
+        assert_eq!(cache.get("/db/locations?query=a"), None);
+    }
+}
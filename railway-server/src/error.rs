@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+
+/// An error response, with a JSON body containing the `message` and, for HAFAS errors, the HAFAS `code`.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+    pub code: Option<String>,
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Display) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.to_string(),
+            code: None,
+        }
+    }
+}
+
+/// The status for an error reported by HAFAS itself.
+///
+/// Invalid queries are client errors, queries without results are not found, everything else is a problem of the upstream API.
+fn hafas_status(code: &str) -> StatusCode {
+    match code {
+        // Unknown location, departure and arrival too close, date outside of the timetable.
+        "LOCATION" | "H9220" | "H9380" | "H9240" | "PARSE" | "H9360" => StatusCode::BAD_REQUEST,
+        // No journeys found.
+        "H890" | "H891" | "H892" | "H895" => StatusCode::NOT_FOUND,
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+impl<R: Display> From<rcore::Error<R, hafas::Error>> for ApiError {
+    fn from(e: rcore::Error<R, hafas::Error>) -> Self {
+        let (status, code) = match &e {
+            rcore::Error::Request(_) => (StatusCode::BAD_GATEWAY, None),
+            rcore::Error::Provider(hafas::Error::Hafas { code, .. }) => {
+                (hafas_status(code), Some(code.clone()))
+            }
+            rcore::Error::Provider(hafas::Error::InvalidInput(_)) => {
+                (StatusCode::BAD_REQUEST, None)
+            }
+            rcore::Error::Provider(hafas::Error::Unsupported(_)) => {
+                (StatusCode::NOT_IMPLEMENTED, None)
+            }
+            rcore::Error::Provider(hafas::Error::Json { .. } | hafas::Error::Parse { .. }) => {
+                (StatusCode::BAD_GATEWAY, None)
+            }
+        };
+        Self {
+            status,
+            message: e.to_string(),
+            code,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(json!({ "message": self.message, "code": self.code })),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maps_hafas_errors() {
+        let error = |code: &str| {
+            ApiError::from(rcore::Error::<String, _>::Provider(hafas::Error::Hafas {
+                code: code.to_string(),
+                text: "text".to_string(),
+            }))
+        };
+        assert_eq!(error("LOCATION").status, StatusCode::BAD_REQUEST);
+        assert_eq!(error("H890").status, StatusCode::NOT_FOUND);
+        assert_eq!(error("H890").code.as_deref(), Some("H890"));
+        assert_eq!(error("FAIL").status, StatusCode::BAD_GATEWAY);
+        assert_eq!(
+            ApiError::from(rcore::Error::<_, hafas::Error>::Request("timeout")).status,
+            StatusCode::BAD_GATEWAY
+        );
+    }
+}
@@ -0,0 +1,47 @@
+mod api;
+mod cache;
+mod error;
+mod query;
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::builder::PossibleValuesParser;
+use clap::Parser;
+use hafas::client::HafasClient;
+use rcore::HyperRustlsRequesterBuilder;
+
+use api::AppState;
+use cache::Cache;
+
+/// Serve public transport providers as a REST API.
+#[derive(Debug, Parser)]
+#[command(name = "railway-server", version)]
+struct Args {
+    /// The HAFAS profile to serve.
+    #[arg(short, long, value_parser = PossibleValuesParser::new(hafas::profile::names()))]
+    profile: String,
+    /// The address to listen on.
+    #[arg(short, long, default_value = "127.0.0.1:3000")]
+    listen: SocketAddr,
+    /// How many seconds to cache successful responses for.
+    #[arg(long, default_value_t = 30)]
+    cache_ttl: u64,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+    let profile =
+        hafas::profile::by_name(&args.profile).expect("profile names are validated by clap");
+    let timezone = profile.timezone();
+    let app = api::router(AppState {
+        client: HafasClient::new(profile, HyperRustlsRequesterBuilder::default()),
+        timezone,
+        cache: Cache::new(Duration::from_secs(args.cache_ttl)),
+    });
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await
+}
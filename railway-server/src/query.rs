@@ -0,0 +1,272 @@
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use rcore::{
+    Accessibility, JourneysOptions, LocationsOptions, Mode, Place, ProductsSelection,
+    RefreshJourneyOptions, Station, TransferOptions, Via, WalkingSpeed,
+};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+
+/// The query parameters of `/locations`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationsQuery {
+    pub query: String,
+    pub results: Option<u64>,
+    pub language: Option<String>,
+}
+
+impl From<LocationsQuery> for LocationsOptions {
+    fn from(query: LocationsQuery) -> Self {
+        let default = LocationsOptions::default();
+        LocationsOptions {
+            query: query.query,
+            results: query.results.unwrap_or(default.results),
+            language: query.language,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalkingSpeedParam {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl From<WalkingSpeedParam> for WalkingSpeed {
+    fn from(speed: WalkingSpeedParam) -> Self {
+        match speed {
+            WalkingSpeedParam::Slow => WalkingSpeed::Slow,
+            WalkingSpeedParam::Normal => WalkingSpeed::Normal,
+            WalkingSpeedParam::Fast => WalkingSpeed::Fast,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessibilityParam {
+    None,
+    Partial,
+    Complete,
+}
+
+impl From<AccessibilityParam> for Accessibility {
+    fn from(accessibility: AccessibilityParam) -> Self {
+        match accessibility {
+            AccessibilityParam::None => Accessibility::None,
+            AccessibilityParam::Partial => Accessibility::Partial,
+            AccessibilityParam::Complete => Accessibility::Complete,
+        }
+    }
+}
+
+/// The query parameters of `/journeys`, named like the ones of hafas-rest-api.
+///
+/// Places are station ids, times are RFC 3339 or local to the profile, e.g. `2024-09-01T10:00`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JourneysQuery {
+    pub from: String,
+    pub to: String,
+    pub via: Option<String>,
+    /// Comma-separated ids of stations to avoid.
+    pub avoid: Option<String>,
+    pub departure: Option<String>,
+    pub arrival: Option<String>,
+    pub earlier_than: Option<String>,
+    pub later_than: Option<String>,
+    pub results: Option<u64>,
+    pub stopovers: Option<bool>,
+    /// The maximum number of transfers.
+    pub transfers: Option<u64>,
+    /// The minimum time for a transfer in minutes.
+    pub transfer_time: Option<i64>,
+    pub bike: Option<bool>,
+    pub tickets: Option<bool>,
+    pub start_with_walking: Option<bool>,
+    pub walking_speed: Option<WalkingSpeedParam>,
+    /// The maximum distance to walk to or from a station in meters.
+    pub max_walking_distance: Option<u64>,
+    pub polylines: Option<bool>,
+    pub accessibility: Option<AccessibilityParam>,
+    /// Comma-separated modes to route with, e.g. `regional-train,bus`. Defaults to all.
+    pub products: Option<String>,
+    pub language: Option<String>,
+}
+
+fn station(id: String) -> Place {
+    Place::Station(Station {
+        id,
+        ..Default::default()
+    })
+}
+
+fn parse_time(s: &str, timezone: Tz) -> Result<DateTime<Tz>, ApiError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&timezone));
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .and_then(|time| timezone.from_local_datetime(&time).earliest())
+        .ok_or_else(|| ApiError::bad_request(format!("invalid time: {}", s)))
+}
+
+fn parse_mode(s: &str) -> Result<Mode, ApiError> {
+    Mode::from_id(s.trim())
+        .filter(|mode| *mode != Mode::Unknown)
+        .ok_or_else(|| ApiError::bad_request(format!("invalid product: {}", s)))
+}
+
+impl JourneysQuery {
+    /// The origin, destination and options of the query.
+    ///
+    /// Without `departure` and `arrival`, journeys depart now.
+    pub fn into_options(self, timezone: Tz) -> Result<(Place, Place, JourneysOptions), ApiError> {
+        let default = JourneysOptions::default();
+        let arrival = self.arrival.map(|a| parse_time(&a, timezone)).transpose()?;
+        let departure = match self.departure {
+            Some(departure) => Some(parse_time(&departure, timezone)?),
+            None if arrival.is_none()
+                && self.earlier_than.is_none()
+                && self.later_than.is_none() =>
+            {
+                Some(chrono::Utc::now().with_timezone(&timezone))
+            }
+            None => None,
+        };
+        let products = match self.products {
+            Some(products) => products
+                .split(',')
+                .map(parse_mode)
+                .collect::<Result<std::collections::HashSet<_>, _>>()?
+                .into(),
+            None => ProductsSelection::all(),
+        };
+
+        Ok((
+            station(self.from),
+            station(self.to),
+            JourneysOptions {
+                via: self
+                    .via
+                    .map(|v| Via::from(station(v)))
+                    .into_iter()
+                    .collect(),
+                avoid: self
+                    .avoid
+                    .map(|a| {
+                        a.split(',')
+                            .map(|id| station(id.trim().to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                earlier_than: self.earlier_than,
+                later_than: self.later_than,
+                results: self.results.unwrap_or(default.results),
+                stopovers: self.stopovers.unwrap_or(default.stopovers),
+                bike_friendly: self.bike.unwrap_or(default.bike_friendly),
+                tickets: self.tickets.unwrap_or(default.tickets),
+                start_with_walking: self
+                    .start_with_walking
+                    .unwrap_or(default.start_with_walking),
+                accessibility: self.accessibility.map(Into::into).unwrap_or_default(),
+                walking_speed: self.walking_speed.map(Into::into).unwrap_or_default(),
+                max_walking_distance: self.max_walking_distance,
+                polylines: self.polylines.unwrap_or(default.polylines),
+                transfers: self
+                    .transfers
+                    .map(TransferOptions::Limited)
+                    .unwrap_or_default(),
+                transfer_time: self
+                    .transfer_time
+                    .map(Duration::minutes)
+                    .unwrap_or(default.transfer_time),
+                arrival,
+                departure,
+                products,
+                language: self.language,
+                ..default
+            },
+        ))
+    }
+}
+
+/// The query parameters of `/journeys/:ref`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshQuery {
+    pub stopovers: Option<bool>,
+    pub tickets: Option<bool>,
+    pub language: Option<String>,
+}
+
+impl From<RefreshQuery> for RefreshJourneyOptions {
+    fn from(query: RefreshQuery) -> Self {
+        RefreshJourneyOptions {
+            stopovers: query.stopovers.unwrap_or_default(),
+            tickets: query.tickets.unwrap_or_default(),
+            language: query.language,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maps_journeys_query() {
+        let (from, _, opts) = JourneysQuery {
+            from: "8000207".to_string(),
+            to: "8000261".to_string(),
+            departure: Some("2024-09-01T10:00".to_string()),
+            transfers: Some(1),
+            transfer_time: Some(5),
+            products: Some("regional-train,bus".to_string()),
+            walking_speed: Some(WalkingSpeedParam::Slow),
+            avoid: Some("8000044, 8000105".to_string()),
+            max_walking_distance: Some(500),
+            polylines: Some(true),
+            ..Default::default()
+        }
+        .into_options(chrono_tz::Europe::Berlin)
+        .unwrap();
+
+        assert_eq!(from, station("8000207".to_string()));
+        assert_eq!(
+            opts.departure,
+            Some(
+                chrono_tz::Europe::Berlin
+                    .with_ymd_and_hms(2024, 9, 1, 10, 0, 0)
+                    .unwrap()
+            )
+        );
+        assert!(matches!(opts.transfers, TransferOptions::Limited(1)));
+        assert_eq!(opts.transfer_time, Duration::minutes(5));
+        assert_eq!(opts.walking_speed, WalkingSpeed::Slow);
+        assert_eq!(
+            opts.avoid,
+            vec![
+                station("8000044".to_string()),
+                station("8000105".to_string())
+            ]
+        );
+        assert_eq!(opts.max_walking_distance, Some(500));
+        assert!(opts.polylines);
+        assert!(opts.products.contains(&Mode::Bus));
+        assert!(!opts.products.contains(&Mode::Tram));
+        assert_eq!(opts.results, JourneysOptions::default().results);
+
+        let invalid = JourneysQuery {
+            products: Some("zeppelin".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.into_options(chrono_tz::Europe::Berlin).is_err());
+    }
+}